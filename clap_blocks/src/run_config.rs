@@ -1,4 +1,5 @@
 //! Common config for all `run` commands.
+use std::time::Duration;
 use trace_exporters::TracingConfig;
 use trogging::cli::LoggingConfig;
 
@@ -48,6 +49,58 @@ pub struct RunConfig {
     )]
     pub max_http_request_size: usize,
 
+    /// Log HTTP requests that take at least this long to handle, such as "1s",
+    /// along with an excerpt of their body with any credentials redacted.
+    #[clap(
+        long = "slow-http-request-threshold",
+        env = "INFLUXDB_IOX_SLOW_HTTP_REQUEST_THRESHOLD",
+        value_parser = humantime::parse_duration,
+    )]
+    pub slow_http_request_threshold: Option<Duration>,
+
+    /// Serve `POST /debug/metrics/reset`, which resets all counters to zero.
+    ///
+    /// Intended for integration tests only.
+    #[clap(
+        long = "enable-metrics-reset",
+        env = "INFLUXDB_IOX_ENABLE_METRICS_RESET",
+        action
+    )]
+    pub enable_metrics_reset: bool,
+
+    /// Replay the response to a write or delete request that carries an
+    /// `Idempotency-Key` header when it is retried with the same key within
+    /// this duration, such as "5m", rather than processing it again.
+    ///
+    /// If not set, the header is ignored.
+    #[clap(
+        long = "idempotency-key-ttl",
+        env = "INFLUXDB_IOX_IDEMPOTENCY_KEY_TTL",
+        value_parser = humantime::parse_duration,
+    )]
+    pub idempotency_key_ttl: Option<Duration>,
+
+    /// The maximum number of responses retained for replay to requests with
+    /// an `Idempotency-Key` header. The oldest are discarded first.
+    #[clap(
+        long = "idempotency-key-cache-size",
+        env = "INFLUXDB_IOX_IDEMPOTENCY_KEY_CACHE_SIZE",
+        default_value = "10000",
+        action
+    )]
+    pub idempotency_key_cache_size: usize,
+
+    /// The paths of the `POST` routes whose responses are replayed to
+    /// requests with an `Idempotency-Key` header, separated by commas.
+    #[clap(
+        long = "idempotency-key-routes",
+        env = "INFLUXDB_IOX_IDEMPOTENCY_KEY_ROUTES",
+        default_value = "/api/v2/write,/api/v2/delete,/write",
+        use_value_delimiter = true,
+        action = clap::ArgAction::Append
+    )]
+    pub idempotency_key_routes: Vec<String>,
+
     /// object store config
     #[clap(flatten)]
     pub(crate) object_store_config: ObjectStoreConfig,
@@ -101,6 +154,15 @@ impl RunConfig {
             http_bind_address,
             grpc_bind_address,
             max_http_request_size,
+            slow_http_request_threshold: None,
+            enable_metrics_reset: false,
+            idempotency_key_ttl: None,
+            idempotency_key_cache_size: 10_000,
+            idempotency_key_routes: vec![
+                "/api/v2/write".to_owned(),
+                "/api/v2/delete".to_owned(),
+                "/write".to_owned(),
+            ],
             object_store_config,
         }
     }
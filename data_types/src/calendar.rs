@@ -0,0 +1,215 @@
+//! Partitioning of a [`TimestampRange`] on calendar boundaries in a timezone.
+
+use crate::TimestampRange;
+use chrono::{Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+
+/// A calendar period, the length of which varies with its position in the
+/// calendar and the daylight saving transitions of the timezone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarUnit {
+    /// A day, from one local midnight to the next.
+    Day,
+    /// A month, from local midnight of its first day to that of the next month.
+    Month,
+    /// A year, from local midnight of the 1st of January to that of the next year.
+    Year,
+}
+
+impl CalendarUnit {
+    /// Returns the first day of the period containing `date`.
+    fn truncate(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Month => date.with_day(1).expect("every month has a first day"),
+            Self::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1).expect("valid date"),
+        }
+    }
+
+    /// Returns the first day of the period following that starting on `date`,
+    /// or [`None`] if it is beyond the dates chrono can represent.
+    fn next(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Self::Day => date.succ_opt(),
+            Self::Month if date.month() == 12 => NaiveDate::from_ymd_opt(date.year() + 1, 1, 1),
+            Self::Month => NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1),
+            Self::Year => NaiveDate::from_ymd_opt(date.year() + 1, 1, 1),
+        }
+    }
+}
+
+impl TimestampRange {
+    /// Splits this range on the boundaries of the calendar `unit` in `tz`,
+    /// returning the windows in ascending order.
+    ///
+    /// Each period starts at local midnight, or at the first instant after it
+    /// when midnight is skipped by a daylight saving transition, so periods
+    /// containing a transition are shorter or longer than usual. The first and
+    /// last windows are clipped to this range. An empty range has no windows.
+    pub fn calendar_windows(&self, unit: CalendarUnit, tz: Tz) -> Vec<Self> {
+        let mut windows = vec![];
+        if self.start >= self.end {
+            return windows;
+        }
+
+        let mut date = unit.truncate(tz.timestamp_nanos(self.start).naive_local().date());
+        let mut start = self.start;
+        loop {
+            let next = unit.next(date);
+            let end = next.map_or(self.end, |next| local_midnight(next, tz).min(self.end));
+            windows.push(Self::new(start, end));
+
+            match next {
+                Some(next) if end < self.end => {
+                    start = end;
+                    date = next;
+                }
+                _ => return windows,
+            }
+        }
+    }
+}
+
+/// Returns the first instant of `date` in `tz` as nanoseconds since the epoch,
+/// saturating if it is not representable.
+fn local_midnight(date: NaiveDate, tz: Tz) -> i64 {
+    let mut local = date.and_hms_opt(0, 0, 0).expect("midnight is valid");
+    loop {
+        match tz.from_local_datetime(&local) {
+            LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => {
+                return saturating_nanos(t.naive_utc())
+            }
+            // Midnight was skipped by a transition, so the day starts at the
+            // first local time that exists.
+            LocalResult::None => local += Duration::minutes(15),
+        }
+    }
+}
+
+fn saturating_nanos(t: NaiveDateTime) -> i64 {
+    t.timestamp()
+        .checked_mul(1_000_000_000)
+        .and_then(|v| v.checked_add(i64::from(t.timestamp_subsec_nanos())))
+        .unwrap_or(if t.timestamp() < 0 {
+            i64::MIN
+        } else {
+            i64::MAX
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    fn ts(s: &str) -> i64 {
+        DateTime::parse_from_rfc3339(s).unwrap().timestamp_nanos()
+    }
+
+    fn windows(start: &str, end: &str, unit: CalendarUnit, tz: Tz) -> Vec<(i64, i64)> {
+        TimestampRange::new(ts(start), ts(end))
+            .calendar_windows(unit, tz)
+            .into_iter()
+            .map(|r| (r.start(), r.end()))
+            .collect()
+    }
+
+    #[test]
+    fn test_calendar_windows_dst() {
+        // The clocks go forward at 01:00 UTC on the 27th, so that day is 23 hours
+        assert_eq!(
+            windows(
+                "2022-03-26T12:00:00Z",
+                "2022-03-29T00:00:00Z",
+                CalendarUnit::Day,
+                chrono_tz::Europe::London
+            ),
+            vec![
+                (ts("2022-03-26T12:00:00Z"), ts("2022-03-27T00:00:00Z")),
+                (ts("2022-03-27T00:00:00Z"), ts("2022-03-27T23:00:00Z")),
+                (ts("2022-03-27T23:00:00Z"), ts("2022-03-28T23:00:00Z")),
+                (ts("2022-03-28T23:00:00Z"), ts("2022-03-29T00:00:00Z")),
+            ]
+        );
+
+        // The clocks go back at 01:00 UTC on the 30th, so that day is 25 hours
+        assert_eq!(
+            windows(
+                "2022-10-29T23:00:00Z",
+                "2022-10-31T00:00:00Z",
+                CalendarUnit::Day,
+                chrono_tz::Europe::London
+            ),
+            vec![(ts("2022-10-29T23:00:00Z"), ts("2022-10-31T00:00:00Z")),]
+        );
+
+        // Midnight is skipped when the clocks go forward, so the 4th starts at 01:00
+        assert_eq!(
+            windows(
+                "2018-11-03T03:00:00Z",
+                "2018-11-05T02:00:00Z",
+                CalendarUnit::Day,
+                chrono_tz::America::Sao_Paulo
+            ),
+            vec![
+                (ts("2018-11-03T03:00:00Z"), ts("2018-11-04T03:00:00Z")),
+                (ts("2018-11-04T03:00:00Z"), ts("2018-11-05T02:00:00Z")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_calendar_windows_months() {
+        assert_eq!(
+            windows(
+                "2022-01-15T00:00:00Z",
+                "2022-03-15T00:00:00Z",
+                CalendarUnit::Month,
+                chrono_tz::UTC
+            ),
+            vec![
+                (ts("2022-01-15T00:00:00Z"), ts("2022-02-01T00:00:00Z")),
+                (ts("2022-02-01T00:00:00Z"), ts("2022-03-01T00:00:00Z")),
+                (ts("2022-03-01T00:00:00Z"), ts("2022-03-15T00:00:00Z")),
+            ]
+        );
+
+        // Months start at local midnight, with the offset in effect at the time
+        assert_eq!(
+            windows(
+                "2022-02-15T00:00:00Z",
+                "2022-04-15T00:00:00Z",
+                CalendarUnit::Month,
+                chrono_tz::Europe::London
+            ),
+            vec![
+                (ts("2022-02-15T00:00:00Z"), ts("2022-03-01T00:00:00Z")),
+                (ts("2022-03-01T00:00:00Z"), ts("2022-03-31T23:00:00Z")),
+                (ts("2022-03-31T23:00:00Z"), ts("2022-04-15T00:00:00Z")),
+            ]
+        );
+
+        // Years are split on the 1st of January
+        assert_eq!(
+            windows(
+                "2023-12-31T00:00:00Z",
+                "2024-03-02T00:00:00Z",
+                CalendarUnit::Year,
+                chrono_tz::UTC
+            ),
+            vec![
+                (ts("2023-12-31T00:00:00Z"), ts("2024-01-01T00:00:00Z")),
+                (ts("2024-01-01T00:00:00Z"), ts("2024-03-02T00:00:00Z")),
+            ]
+        );
+
+        // An empty range has no windows
+        assert!(windows(
+            "2022-01-15T00:00:00Z",
+            "2022-01-15T00:00:00Z",
+            CalendarUnit::Month,
+            chrono_tz::UTC
+        )
+        .is_empty());
+    }
+}
@@ -20,7 +20,7 @@ use schema::{
     builder::SchemaBuilder, sort::SortKey, InfluxColumnType, InfluxFieldType, Schema,
     TIME_COLUMN_NAME,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use sqlx::postgres::PgHasArrayType;
 use std::{
@@ -35,6 +35,11 @@ use std::{
 };
 use uuid::Uuid;
 
+#[cfg(feature = "tz")]
+mod calendar;
+#[cfg(feature = "tz")]
+pub use calendar::CalendarUnit;
+
 /// Compaction levels
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, sqlx::Type)]
 #[repr(i16)]
@@ -197,7 +202,9 @@ impl std::fmt::Display for ShardId {
 /// The index of the shard in the set of shards. When Kafka is used as the write buffer, this is
 /// the Kafka Partition ID. Used by the router and write buffer to shard requests to a particular
 /// index in a set of shards.
-#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type)]
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type,
+)]
 #[sqlx(transparent)]
 #[serde(transparent)]
 pub struct ShardIndex(i32);
@@ -241,8 +248,11 @@ pub enum IngesterMapping {
 }
 
 /// Unique ID for a `Partition`
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type)]
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type,
+)]
 #[sqlx(transparent)]
+#[serde(transparent)]
 pub struct PartitionId(i64);
 
 #[allow(missing_docs)]
@@ -794,6 +804,63 @@ impl PartitionKey {
     pub fn ptr_eq(&self, other: &Self) -> bool {
         Arc::ptr_eq(&self.0, &other.0)
     }
+
+    /// Returns the start of the time bucket this key was generated for by
+    /// `template`, as nanoseconds since the epoch, or [`None`] if `template`
+    /// has no [`TemplatePart::TimeFormat`] part or the key does not contain a
+    /// time formatted by it.
+    ///
+    /// Components absent from the time format are assumed to be at the start
+    /// of their range, so a key formatted with `%Y-%m-%d` resolves to
+    /// midnight UTC of that day. If the key contains several substrings
+    /// matching the format, the first is used.
+    pub fn time_bucket_start(&self, template: &PartitionTemplate) -> Option<i64> {
+        let format = template.parts.iter().find_map(|part| match part {
+            TemplatePart::TimeFormat(format) => Some(format),
+            _ => None,
+        })?;
+
+        // Template parts are joined with "-", which may also appear within
+        // the formatted time and the other parts, so try each substring
+        // delimited by part boundaries.
+        let key = &*self.0;
+        let boundaries = std::iter::once(0)
+            .chain(key.match_indices('-').map(|(i, _)| i))
+            .chain(std::iter::once(key.len()))
+            .collect::<Vec<_>>();
+        boundaries.iter().enumerate().find_map(|(i, &start)| {
+            let start = if start == 0 { 0 } else { start + 1 };
+            boundaries[i + 1..]
+                .iter()
+                .find_map(|&end| parse_time_bucket(key.get(start..end)?, format))
+        })
+    }
+}
+
+/// Parses `s` formatted with the `strftime` `format`, returning the earliest
+/// timestamp it could have been formatted from.
+fn parse_time_bucket(s: &str, format: &str) -> Option<i64> {
+    use chrono::format::{parse, Parsed, StrftimeItems};
+
+    let mut parsed = Parsed::new();
+    parse(&mut parsed, s, StrftimeItems::new(format)).ok()?;
+
+    if parsed.ordinal.is_none() {
+        parsed.month.get_or_insert(1);
+        parsed.day.get_or_insert(1);
+    }
+    if parsed.hour_div_12.is_none() && parsed.hour_mod_12.is_none() {
+        parsed.set_hour(0).ok()?;
+    }
+    parsed.minute.get_or_insert(0);
+
+    let t = parsed
+        .to_naive_date()
+        .ok()?
+        .and_time(parsed.to_naive_time().ok()?);
+    t.timestamp()
+        .checked_mul(1_000_000_000)?
+        .checked_add(i64::from(t.timestamp_subsec_nanos()))
 }
 
 impl Display for PartitionKey {
@@ -1146,6 +1213,14 @@ pub struct ProcessedTombstone {
 /// ID of a chunk.
 ///
 /// This ID is unique within a single partition.
+///
+/// # Ordering
+///
+/// IDs created with [`ChunkId::from_sequence`] store the sequence number in the
+/// low 64 bits of the UUID, leaving the high 64 bits zeroed, so they sort in
+/// sequence (creation) order. Random IDs created with [`ChunkId::new`] always
+/// have the UUID version bits set in their high 64 bits and therefore sort
+/// after every sequence-created ID, in arbitrary order among themselves.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ChunkId(Uuid);
 
@@ -1170,6 +1245,22 @@ impl ChunkId {
         Self(Uuid::from_u128(id))
     }
 
+    /// Create an ID from a monotonic sequence number, such that IDs compare in
+    /// the same order as their sequence numbers.
+    pub fn from_sequence(seq: u64) -> Self {
+        Self(Uuid::from_u128(u128::from(seq)))
+    }
+
+    /// Return the sequence number this ID was created from, or [`None`] for
+    /// random IDs.
+    ///
+    /// Note that IDs created with [`ChunkId::new_test`] or [`ChunkId::new_id`]
+    /// from values that fit in a [`u64`] are indistinguishable from
+    /// sequence-created IDs.
+    pub fn sequence(&self) -> Option<u64> {
+        u64::try_from(self.0.as_u128()).ok()
+    }
+
     /// Get inner UUID.
     pub fn get(&self) -> Uuid {
         self.0
@@ -2076,6 +2167,27 @@ impl StatValues<String> {
     }
 }
 
+/// Errors merging [`TableSummary`]s.
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum SchemaMergeError {
+    #[snafu(display(
+        "column '{}' has conflicting types: {:?}({}) and {:?}({})",
+        column,
+        existing_influxdb_type,
+        existing_type,
+        new_influxdb_type,
+        new_type
+    ))]
+    ConflictingColumnType {
+        column: String,
+        existing_influxdb_type: InfluxDbType,
+        existing_type: &'static str,
+        new_influxdb_type: InfluxDbType,
+        new_type: &'static str,
+    },
+}
+
 /// Metadata and statistics information for a table. This can be
 /// either for the portion of a Table stored within a single chunk or
 /// aggregated across chunks.
@@ -2137,6 +2249,36 @@ impl TableSummary {
         }
     }
 
+    /// Merges `summaries`, such as those of each chunk of a partition, into a
+    /// single summary, combining their statistics as per [`Self::update_from`].
+    ///
+    /// Returns an error if a column has different types in different summaries.
+    pub fn try_merge<'a>(
+        summaries: impl IntoIterator<Item = &'a Self>,
+    ) -> Result<Self, SchemaMergeError> {
+        let mut merged = Self::default();
+        for summary in summaries {
+            for col in &summary.columns {
+                if let Some(existing) = merged.column(&col.name) {
+                    if existing.influxdb_type != col.influxdb_type
+                        || existing.stats.type_name() != col.stats.type_name()
+                    {
+                        return ConflictingColumnTypeSnafu {
+                            column: &col.name,
+                            existing_influxdb_type: existing.influxdb_type,
+                            existing_type: existing.stats.type_name(),
+                            new_influxdb_type: col.influxdb_type,
+                            new_type: col.stats.type_name(),
+                        }
+                        .fail();
+                    }
+                }
+            }
+            merged.update_from(summary);
+        }
+        Ok(merged)
+    }
+
     /// Total size of all ColumnSummaries that belong to this table which include
     /// column names and their stats
     pub fn size(&self) -> usize {
@@ -2160,6 +2302,129 @@ impl TableSummary {
             }
         })
     }
+
+    /// Returns true if `other` describes the same columns, with the same
+    /// statistics, as this summary.
+    ///
+    /// Unlike the derived [`PartialEq`], columns are compared irrespective of
+    /// their order, and NaN float statistics are considered equal to each other.
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        self.columns.len() == other.columns.len()
+            && self.columns.iter().all(|col| {
+                other.column(&col.name).map_or(false, |other_col| {
+                    col.influxdb_type == other_col.influxdb_type
+                        && stats_semantically_eq(&col.stats, &other_col.stats)
+                })
+            })
+    }
+
+    /// Returns a fingerprint of the column names and types of this summary.
+    ///
+    /// The fingerprint is independent of the order of the columns and of the
+    /// statistics values, so two summaries of the same schema produce the same
+    /// fingerprint irrespective of the data they describe. It is stable
+    /// across processes and releases.
+    pub fn schema_fingerprint(&self) -> u64 {
+        // 64-bit FNV-1a, chosen over the std hashers as their output is not
+        // guaranteed to be stable across releases.
+        const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const PRIME: u64 = 0x100000001b3;
+
+        let mut columns = self
+            .columns
+            .iter()
+            .map(|c| {
+                let influxdb_type = match c.influxdb_type {
+                    InfluxDbType::Tag => "Tag",
+                    InfluxDbType::Field => "Field",
+                    InfluxDbType::Timestamp => "Timestamp",
+                };
+                (c.name.as_str(), influxdb_type, c.stats.type_name())
+            })
+            .collect::<Vec<_>>();
+        columns.sort_unstable();
+
+        columns
+            .into_iter()
+            .flat_map(|(name, influxdb_type, stats_type)| {
+                // Terminate each component so that adjacent values cannot
+                // be confused, e.g. ("ab", "c") and ("a", "bc").
+                [name, influxdb_type, stats_type]
+                    .into_iter()
+                    .flat_map(|v| v.bytes().chain(std::iter::once(0)))
+            })
+            .fold(OFFSET_BASIS, |hash, b| {
+                (hash ^ b as u64).wrapping_mul(PRIME)
+            })
+    }
+
+    /// Returns the statistics of this summary as a JSON array, with one flat
+    /// object per column, for display.
+    ///
+    /// The columns are ordered by name, so that summaries of the same columns
+    /// serialize identically, irrespective of the order in which their columns
+    /// were added, such as by [`Self::update_from`].
+    ///
+    /// Each object has the keys `name`, `type`, `min`, `max`, `null_count`
+    /// and `total_count`, each with a scalar value. `min` and `max` are null
+    /// when not known, such as for a column containing only nulls, as is
+    /// `null_count`.
+    pub fn to_flat_json(&self) -> serde_json::Value {
+        use serde_json::{json, Value};
+
+        fn min_max<T: Into<Value> + Clone>(s: &StatValues<T>) -> (Value, Value) {
+            (
+                s.min.clone().map_or(Value::Null, Into::into),
+                s.max.clone().map_or(Value::Null, Into::into),
+            )
+        }
+
+        let mut columns = self.columns.iter().collect::<Vec<_>>();
+        columns.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+        columns
+            .into_iter()
+            .map(|c| {
+                let (min, max) = match &c.stats {
+                    Statistics::I64(s) => min_max(s),
+                    Statistics::U64(s) => min_max(s),
+                    Statistics::F64(s) => min_max(s),
+                    Statistics::Bool(s) => min_max(s),
+                    Statistics::String(s) => min_max(s),
+                };
+
+                json!({
+                    "name": c.name,
+                    "type": c.stats.type_name(),
+                    "min": min,
+                    "max": max,
+                    "null_count": c.stats.null_count(),
+                    "total_count": c.stats.total_count(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Compares two [`Statistics`], considering NaN float values equal.
+fn stats_semantically_eq(a: &Statistics, b: &Statistics) -> bool {
+    fn f64_eq(a: &Option<f64>, b: &Option<f64>) -> bool {
+        match (a, b) {
+            (Some(a), Some(b)) => a == b || (a.is_nan() && b.is_nan()),
+            (a, b) => a == b,
+        }
+    }
+
+    match (a, b) {
+        (Statistics::F64(a), Statistics::F64(b)) => {
+            f64_eq(&a.min, &b.min)
+                && f64_eq(&a.max, &b.max)
+                && a.total_count == b.total_count
+                && a.null_count == b.null_count
+                && a.distinct_count == b.distinct_count
+        }
+        (a, b) => a == b,
+    }
 }
 
 /// Shard index plus offset
@@ -2271,6 +2536,41 @@ impl TimestampRange {
     pub fn start(&self) -> i64 {
         self.start
     }
+
+    /// Returns the portion of this range within `bounds`, or `None` if no
+    /// timestamp of this range is within `bounds`.
+    pub fn clamp(&self, bounds: &Self) -> Option<Self> {
+        let start = self.start.max(bounds.start);
+        let end = self.end.min(bounds.end);
+        (start < end).then(|| Self { start, end })
+    }
+}
+
+/// Returns the sub-ranges of `within` not covered by any of `ranges`, in
+/// ascending order.
+///
+/// `ranges` may be unordered and overlapping. An empty result means `within`
+/// is fully covered.
+pub fn coverage_gaps(ranges: &[TimestampRange], within: &TimestampRange) -> Vec<TimestampRange> {
+    let mut covered = ranges
+        .iter()
+        .filter_map(|r| r.clamp(within))
+        .collect::<Vec<_>>();
+    covered.sort_unstable();
+
+    let mut gaps = vec![];
+    let mut start = within.start;
+    for r in covered {
+        if r.start > start {
+            gaps.push(TimestampRange::new(start, r.start));
+        }
+        start = start.max(r.end);
+    }
+    if start < within.end {
+        gaps.push(TimestampRange::new(start, within.end));
+    }
+
+    gaps
 }
 
 /// Specifies a min/max timestamp value.
@@ -2335,6 +2635,33 @@ mod tests {
         assert_ne!(ChunkId::new_test(1), ChunkId::new_test(2));
     }
 
+    #[test]
+    fn test_chunk_id_from_sequence() {
+        let first = ChunkId::from_sequence(1);
+        let second = ChunkId::from_sequence(2);
+        let last = ChunkId::from_sequence(u64::MAX);
+        assert_eq!(first.sequence(), Some(1));
+        assert_eq!(last.sequence(), Some(u64::MAX));
+
+        // Sequence-created IDs sort by sequence
+        let mut ids = vec![last, second, first];
+        ids.sort();
+        assert_eq!(ids, vec![first, second, last]);
+
+        // and always before random IDs
+        for _ in 0..100 {
+            let random = ChunkId::new();
+            assert_eq!(random.sequence(), None);
+            assert!(last < random);
+
+            let mut ids = vec![random, second, first];
+            ids.sort();
+            assert_eq!(ids, vec![first, second, random]);
+        }
+
+        assert_eq!(format!("{}", second), "ChunkId(2)");
+    }
+
     #[test]
     fn test_chunk_id_debug_and_display() {
         // Random chunk IDs use UUID-format
@@ -3147,6 +3474,343 @@ mod tests {
         assert_eq!(&table.column("int").unwrap().stats, &expected_int_stats);
     }
 
+    #[test]
+    fn table_semantically_eq() {
+        // The constructors reject NaN, but values deserialized from elsewhere
+        // may still contain it.
+        let float_stats = |min, max| {
+            Statistics::F64(StatValues {
+                min: Some(min),
+                max: Some(max),
+                total_count: 2,
+                null_count: Some(0),
+                distinct_count: None,
+            })
+        };
+
+        let string_col = ColumnSummary {
+            name: "string".to_string(),
+            influxdb_type: InfluxDbType::Tag,
+            stats: Statistics::String(StatValues::new_with_value("foo".to_string())),
+        };
+        let float_col = ColumnSummary {
+            name: "float".to_string(),
+            influxdb_type: InfluxDbType::Field,
+            stats: float_stats(f64::NAN, f64::NAN),
+        };
+
+        let a = TableSummary {
+            columns: vec![string_col.clone(), float_col.clone()],
+        };
+
+        // NaN statistics defeat the derived PartialEq
+        assert_ne!(a, a.clone());
+        assert!(a.semantically_eq(&a.clone()));
+
+        // reordered columns
+        let b = TableSummary {
+            columns: vec![float_col.clone(), string_col.clone()],
+        };
+        assert!(a.semantically_eq(&b));
+        assert!(b.semantically_eq(&a));
+
+        // differing statistics
+        let mut c = b.clone();
+        c.columns[0].stats = float_stats(1.0, f64::NAN);
+        assert!(!a.semantically_eq(&c));
+
+        let mut d = b.clone();
+        d.columns[1].stats = Statistics::String(StatValues::new_with_value("bar".to_string()));
+        assert!(!a.semantically_eq(&d));
+
+        // differing columns
+        let e = TableSummary {
+            columns: vec![float_col],
+        };
+        assert!(!a.semantically_eq(&e));
+        assert!(!e.semantically_eq(&a));
+
+        let mut f = b;
+        f.columns[1].influxdb_type = InfluxDbType::Field;
+        assert!(!a.semantically_eq(&f));
+    }
+
+    #[test]
+    fn table_schema_fingerprint() {
+        let table = |int_stats: StatValues<i64>| TableSummary {
+            columns: vec![
+                ColumnSummary {
+                    name: "string".to_string(),
+                    influxdb_type: InfluxDbType::Tag,
+                    stats: Statistics::String(StatValues::new_with_value("foo".to_string())),
+                },
+                ColumnSummary {
+                    name: "int".to_string(),
+                    influxdb_type: InfluxDbType::Field,
+                    stats: Statistics::I64(int_stats),
+                },
+            ],
+        };
+
+        let a = table(StatValues::new_with_value(1));
+        let fingerprint = a.schema_fingerprint();
+
+        // stable across calls
+        assert_eq!(fingerprint, a.schema_fingerprint());
+
+        // independent of the statistics values
+        let mut int_stats = StatValues::new_with_value(3);
+        int_stats.update(&9);
+        let b = table(int_stats);
+        assert_ne!(a, b);
+        assert_eq!(fingerprint, b.schema_fingerprint());
+
+        // independent of the column order
+        let mut c = a.clone();
+        c.columns.reverse();
+        assert_eq!(fingerprint, c.schema_fingerprint());
+
+        // a type change alters the fingerprint
+        let d = TableSummary {
+            columns: vec![
+                a.columns[0].clone(),
+                ColumnSummary {
+                    name: "int".to_string(),
+                    influxdb_type: InfluxDbType::Field,
+                    stats: Statistics::U64(StatValues::new_with_value(1)),
+                },
+            ],
+        };
+        assert_ne!(fingerprint, d.schema_fingerprint());
+
+        let mut e = a.clone();
+        e.columns[0].influxdb_type = InfluxDbType::Field;
+        assert_ne!(fingerprint, e.schema_fingerprint());
+
+        // as does renaming a column
+        let mut f = a;
+        f.columns[0].name = "str".to_string();
+        assert_ne!(fingerprint, f.schema_fingerprint());
+    }
+
+    #[test]
+    fn table_to_flat_json() {
+        let table = TableSummary {
+            columns: vec![
+                ColumnSummary {
+                    name: "host".to_string(),
+                    influxdb_type: InfluxDbType::Tag,
+                    stats: Statistics::String(StatValues::new(
+                        Some("a".to_string()),
+                        Some("b".to_string()),
+                        3,
+                        Some(1),
+                    )),
+                },
+                ColumnSummary {
+                    name: "usage".to_string(),
+                    influxdb_type: InfluxDbType::Field,
+                    stats: Statistics::F64(StatValues::new_non_null(Some(0.5), Some(99.5), 3)),
+                },
+                ColumnSummary {
+                    name: "up".to_string(),
+                    influxdb_type: InfluxDbType::Field,
+                    stats: Statistics::Bool(StatValues::new_all_null(3, None)),
+                },
+                ColumnSummary {
+                    name: "time".to_string(),
+                    influxdb_type: InfluxDbType::Timestamp,
+                    stats: Statistics::I64(StatValues::new(Some(10), Some(30), 3, None)),
+                },
+            ],
+        };
+
+        assert_eq!(
+            table.to_flat_json(),
+            serde_json::json!([
+                {
+                    "name": "host",
+                    "type": "String",
+                    "min": "a",
+                    "max": "b",
+                    "null_count": 1,
+                    "total_count": 3,
+                },
+                {
+                    "name": "time",
+                    "type": "I64",
+                    "min": 10,
+                    "max": 30,
+                    "null_count": null,
+                    "total_count": 3,
+                },
+                {
+                    "name": "up",
+                    "type": "Bool",
+                    "min": null,
+                    "max": null,
+                    "null_count": 3,
+                    "total_count": 3,
+                },
+                {
+                    "name": "usage",
+                    "type": "F64",
+                    "min": 0.5,
+                    "max": 99.5,
+                    "null_count": 0,
+                    "total_count": 3,
+                },
+            ])
+        );
+
+        assert_eq!(
+            TableSummary::default().to_flat_json(),
+            serde_json::json!([])
+        );
+    }
+
+    #[test]
+    fn table_to_flat_json_deterministic() {
+        let columns = vec![
+            ColumnSummary {
+                name: "usage".to_string(),
+                influxdb_type: InfluxDbType::Field,
+                stats: Statistics::F64(StatValues::new_non_null(Some(0.5), Some(99.5), 3)),
+            },
+            ColumnSummary {
+                name: "host".to_string(),
+                influxdb_type: InfluxDbType::Tag,
+                stats: Statistics::String(StatValues::new_non_null(
+                    Some("a".to_string()),
+                    Some("b".to_string()),
+                    3,
+                )),
+            },
+            ColumnSummary {
+                name: "time".to_string(),
+                influxdb_type: InfluxDbType::Timestamp,
+                stats: Statistics::I64(StatValues::new_non_null(Some(10), Some(30), 3)),
+            },
+        ];
+        let a = TableSummary {
+            columns: columns.clone(),
+        };
+        let b = TableSummary {
+            columns: columns.into_iter().rev().collect(),
+        };
+
+        let json = a.to_flat_json().to_string();
+        assert_eq!(json, a.to_flat_json().to_string());
+        assert_eq!(json, b.to_flat_json().to_string());
+    }
+
+    #[test]
+    fn shard_index_serde() {
+        let shard_index = ShardIndex::new(42);
+        let json = serde_json::to_string(&shard_index).unwrap();
+        assert_eq!(json, "42");
+        assert_eq!(
+            serde_json::from_str::<ShardIndex>(&json).unwrap(),
+            shard_index
+        );
+
+        // Shard indexes are ordered by their value
+        let mut indexes = vec![ShardIndex::new(10), ShardIndex::new(-1), ShardIndex::new(2)];
+        indexes.sort();
+        assert_eq!(
+            indexes,
+            vec![ShardIndex::new(-1), ShardIndex::new(2), ShardIndex::new(10)]
+        );
+
+        assert_eq!(shard_index.to_string(), "42");
+        assert_eq!("42".parse::<ShardIndex>().unwrap(), shard_index);
+    }
+
+    #[test]
+    fn table_try_merge() {
+        let chunk_a = TableSummary {
+            columns: vec![
+                ColumnSummary {
+                    name: "host".to_string(),
+                    influxdb_type: InfluxDbType::Tag,
+                    stats: Statistics::String(StatValues::new_non_null(
+                        Some("a".to_string()),
+                        Some("c".to_string()),
+                        2,
+                    )),
+                },
+                ColumnSummary {
+                    name: "usage".to_string(),
+                    influxdb_type: InfluxDbType::Field,
+                    stats: Statistics::F64(StatValues::new_non_null(Some(0.5), Some(10.0), 2)),
+                },
+            ],
+        };
+        let chunk_b = TableSummary {
+            columns: vec![
+                ColumnSummary {
+                    name: "host".to_string(),
+                    influxdb_type: InfluxDbType::Tag,
+                    stats: Statistics::String(StatValues::new_non_null(
+                        Some("b".to_string()),
+                        Some("d".to_string()),
+                        3,
+                    )),
+                },
+                ColumnSummary {
+                    name: "temp".to_string(),
+                    influxdb_type: InfluxDbType::Field,
+                    stats: Statistics::I64(StatValues::new_non_null(Some(-5), Some(5), 3)),
+                },
+            ],
+        };
+
+        let merged = TableSummary::try_merge([&chunk_a, &chunk_b]).unwrap();
+        assert_eq!(merged.total_count(), 5);
+
+        // Overlapping columns are combined
+        assert_eq!(
+            merged.column("host").unwrap().stats,
+            Statistics::String(StatValues::new_non_null(
+                Some("a".to_string()),
+                Some("d".to_string()),
+                5,
+            ))
+        );
+
+        // Disjoint columns are padded with NULLs for the rows of other chunks
+        assert_eq!(
+            merged.column("usage").unwrap().stats,
+            Statistics::F64(StatValues::new(Some(0.5), Some(10.0), 5, Some(3)))
+        );
+        assert_eq!(
+            merged.column("temp").unwrap().stats,
+            Statistics::I64(StatValues::new(Some(-5), Some(5), 5, Some(2)))
+        );
+
+        assert_eq!(
+            TableSummary::try_merge(std::iter::empty()).unwrap(),
+            TableSummary::default()
+        );
+
+        // A column with a different type in each chunk is an error
+        let mut chunk_c = chunk_b.clone();
+        chunk_c.columns[0].stats = Statistics::I64(StatValues::new_non_null(Some(1), Some(2), 3));
+        let err = TableSummary::try_merge([&chunk_a, &chunk_c]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "column 'host' has conflicting types: Tag(String) and Tag(I64)"
+        );
+
+        let mut chunk_d = chunk_b;
+        chunk_d.columns[0].influxdb_type = InfluxDbType::Field;
+        let err = TableSummary::try_merge([&chunk_a, &chunk_d]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "column 'host' has conflicting types: Tag(String) and Field(String)"
+        );
+    }
+
     #[test]
     fn column_update_from_boolean() {
         let bool_false = ColumnSummary {
@@ -3324,6 +3988,103 @@ mod tests {
         assert!(!range.contains(201));
     }
 
+    #[test]
+    fn test_timestamp_range_clamp() {
+        let bounds = TimestampRange::new(100, 200);
+
+        // Fully inside
+        assert_eq!(
+            TimestampRange::new(120, 180).clamp(&bounds),
+            Some(TimestampRange::new(120, 180))
+        );
+        assert_eq!(bounds.clamp(&bounds), Some(bounds));
+
+        // Partially inside
+        assert_eq!(
+            TimestampRange::new(50, 150).clamp(&bounds),
+            Some(TimestampRange::new(100, 150))
+        );
+        assert_eq!(
+            TimestampRange::new(150, 250).clamp(&bounds),
+            Some(TimestampRange::new(150, 200))
+        );
+        assert_eq!(
+            TimestampRange::new(MIN_NANO_TIME, i64::MAX).clamp(&bounds),
+            Some(bounds)
+        );
+
+        // Outside, including ranges that only meet the exclusive end
+        assert_eq!(TimestampRange::new(0, 100).clamp(&bounds), None);
+        assert_eq!(TimestampRange::new(200, 300).clamp(&bounds), None);
+        assert_eq!(TimestampRange::new(150, 150).clamp(&bounds), None);
+    }
+
+    #[test]
+    fn test_partition_key_time_bucket_start() {
+        let template = |parts: Vec<TemplatePart>| PartitionTemplate { parts };
+        let day = template(vec![
+            TemplatePart::Table,
+            TemplatePart::TimeFormat("%Y-%m-%d".to_string()),
+            TemplatePart::Column("region".to_string()),
+        ]);
+
+        // The day is found among parts that also contain "-"
+        let key = PartitionKey::from("my-table-2022-10-16-us-west");
+        assert_eq!(key.time_bucket_start(&day), Some(1_665_878_400_000_000_000));
+
+        // Components absent from the format take their earliest value
+        let hour = template(vec![TemplatePart::TimeFormat("%Y-%m-%d %H".to_string())]);
+        let key = PartitionKey::from("2022-10-16 13");
+        assert_eq!(
+            key.time_bucket_start(&hour),
+            Some(1_665_878_400_000_000_000 + 13 * 3_600_000_000_000)
+        );
+
+        // A key that does not contain a formatted time
+        let key = PartitionKey::from("cpu-bananas-region");
+        assert_eq!(key.time_bucket_start(&day), None);
+
+        // A template without a time part
+        let no_time = template(vec![
+            TemplatePart::Table,
+            TemplatePart::Column("region".to_string()),
+        ]);
+        let key = PartitionKey::from("cpu-2022-10-16");
+        assert_eq!(key.time_bucket_start(&no_time), None);
+    }
+
+    #[test]
+    fn test_coverage_gaps() {
+        let within = TimestampRange::new(100, 200);
+        let gaps = |ranges: &[(i64, i64)]| {
+            let ranges = ranges
+                .iter()
+                .map(|&(start, end)| TimestampRange::new(start, end))
+                .collect::<Vec<_>>();
+            coverage_gaps(&ranges, &within)
+                .into_iter()
+                .map(|r| (r.start(), r.end()))
+                .collect::<Vec<_>>()
+        };
+
+        // Fully covered by unordered, overlapping and adjacent ranges
+        assert!(gaps(&[(150, 250), (0, 120), (110, 160)]).is_empty());
+        assert!(gaps(&[(100, 150), (150, 200)]).is_empty());
+        assert!(gaps(&[(0, 300)]).is_empty());
+
+        // A single interior gap
+        assert_eq!(gaps(&[(160, 200), (100, 130), (110, 140)]), [(140, 160)]);
+
+        // Gaps at the start and end
+        assert_eq!(gaps(&[(120, 180)]), [(100, 120), (180, 200)]);
+        assert_eq!(gaps(&[(0, 150)]), [(150, 200)]);
+        assert_eq!(gaps(&[(150, 300)]), [(100, 150)]);
+
+        // Ranges outside of `within` cover nothing
+        assert_eq!(gaps(&[(0, 100), (200, 300)]), [(100, 200)]);
+        assert_eq!(gaps(&[]), [(100, 200)]);
+    }
+
     #[test]
     fn test_timestamp_range_overlaps() {
         let range = TimestampRange::new(100, 200);
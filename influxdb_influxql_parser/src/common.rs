@@ -2,7 +2,7 @@
 
 use crate::expression::conditional::{conditional_expression, ConditionalExpression};
 use crate::identifier::{identifier, Identifier};
-use crate::internal::{expect, verify, ParseResult};
+use crate::internal::{expect, verify, within_deadline, ParseResult};
 use crate::keywords::{keyword, Token};
 use crate::literal::unsigned_integer;
 use crate::string::{regex, Regex};
@@ -18,6 +18,7 @@ use std::ops::{Deref, DerefMut};
 
 /// Represents a measurement name as either an identifier or a regular expression.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MeasurementName {
     /// A measurement name expressed as an [`Identifier`].
     Name(Identifier),
@@ -47,6 +48,7 @@ impl Display for MeasurementName {
 
 /// Represents a fully-qualified, 3-part measurement name.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QualifiedMeasurementName {
     /// An optional database name.
     pub database: Option<Identifier>,
@@ -166,6 +168,7 @@ macro_rules! impl_tuple_clause {
 
 /// Represents the value for a `LIMIT` clause.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LimitClause(pub(crate) u64);
 
 impl_tuple_clause!(LimitClause, u64);
@@ -189,6 +192,7 @@ pub(crate) fn limit_clause(i: &str) -> ParseResult<&str, LimitClause> {
 
 /// Represents the value for a `OFFSET` clause.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OffsetClause(pub(crate) u64);
 
 impl_tuple_clause!(OffsetClause, u64);
@@ -217,6 +221,7 @@ pub(crate) fn statement_terminator(i: &str) -> ParseResult<&str, ()> {
 
 /// Represents the `WHERE` clause of a statement.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WhereClause(pub(crate) ConditionalExpression);
 
 impl WhereClause {
@@ -256,6 +261,7 @@ pub(crate) fn where_clause(i: &str) -> ParseResult<&str, WhereClause> {
 
 /// Represents an InfluxQL `ORDER BY` clause.
 #[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OrderByClause {
     /// Signals the `ORDER BY` is in ascending order.
     #[default]
@@ -325,7 +331,7 @@ pub(crate) fn order_by_clause(i: &str) -> ParseResult<&str, OrderByClause> {
                         preceded(
                             multispace1,
                             verify("invalid ORDER BY, expected TIME column", identifier, |v| {
-                                Token(&v.0) == Token("time")
+                                Token(v.as_str()) == Token("time")
                             }),
                         ),
                         opt(order()),
@@ -377,6 +383,61 @@ impl<T> OneOrMore<T> {
     pub fn len(&self) -> usize {
         self.contents.len()
     }
+
+    /// Returns an iterator over all elements.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.contents.iter()
+    }
+
+    /// Returns an iterator that allows modifying each element.
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.contents.iter_mut()
+    }
+
+    /// Returns a `OneOrMore<U>` by applying `f` to each element.
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> OneOrMore<U> {
+        OneOrMore {
+            contents: self.contents.into_iter().map(f).collect(),
+        }
+    }
+
+    /// Returns a `OneOrMore<U>` by applying the fallible function `f` to each element,
+    /// stopping at the first error.
+    pub fn try_map<U, E>(self, f: impl FnMut(T) -> Result<U, E>) -> Result<OneOrMore<U>, E> {
+        Ok(OneOrMore {
+            contents: self.contents.into_iter().map(f).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl<'a, T> IntoIterator for &'a OneOrMore<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A `OneOrMore<T>` is serialized as a sequence of its elements.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for OneOrMore<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.contents, serializer)
+    }
+}
+
+/// Deserializing an empty sequence is an error, as a `OneOrMore<T>` requires
+/// at least one element.
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for OneOrMore<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let contents = <Vec<T> as serde::Deserialize>::deserialize(deserializer)?;
+        if contents.is_empty() {
+            return Err(serde::de::Error::invalid_length(0, &"at least one element"));
+        }
+        Ok(Self { contents })
+    }
 }
 
 impl<T: Parser> OneOrMore<T> {
@@ -392,7 +453,7 @@ impl<T: Parser> OneOrMore<T> {
                     msg,
                     separated_list1(
                         preceded(multispace0, char(',')),
-                        preceded(multispace0, T::parse),
+                        preceded(multispace0, within_deadline(T::parse)),
                     ),
                 ),
                 Self::new,
@@ -510,6 +571,45 @@ mod tests {
                 name: Regex("diskio".into()),
             }
         );
+
+        // A quoted name containing a dot is a single measurement
+        let (_, got) = qualified_measurement_name("\"cpu.load\"").unwrap();
+        assert_eq!(
+            got,
+            QualifiedMeasurementName {
+                database: None,
+                retention_policy: None,
+                name: Name("cpu.load".into()),
+            }
+        );
+        assert_eq!(got.to_string(), "\"cpu.load\"");
+
+        // whereas an unquoted name is qualified by a retention policy
+        let (_, got) = qualified_measurement_name("cpu.load").unwrap();
+        assert_eq!(
+            got,
+            QualifiedMeasurementName {
+                database: None,
+                retention_policy: Some("cpu".into()),
+                name: Name("load".into()),
+            }
+        );
+        assert_eq!(got.to_string(), "cpu.load");
+
+        let (_, got) =
+            qualified_measurement_name("\"telegraf.prod\".\"one.week\".\"cpu.load\"").unwrap();
+        assert_eq!(
+            got,
+            QualifiedMeasurementName {
+                database: Some("telegraf.prod".into()),
+                retention_policy: Some("one.week".into()),
+                name: Name("cpu.load".into()),
+            }
+        );
+        assert_eq!(
+            got.to_string(),
+            "\"telegraf.prod\".\"one.week\".\"cpu.load\""
+        );
     }
 
     #[test]
@@ -693,4 +793,45 @@ mod tests {
         // should panic
         OneOrMoreString::new(vec![]);
     }
+
+    #[test]
+    fn test_one_or_more_map() {
+        use crate::expression::arithmetic::Expr;
+        use crate::select::{Field, FieldList};
+
+        let (_, got) = OneOrMoreString::separated_list1("Expects one or more")("foo, bar").unwrap();
+
+        let got: FieldList = got.map(|name| Field {
+            expr: Expr::VarRef {
+                name: name.into(),
+                data_type: None,
+            },
+            alias: None,
+        });
+        assert_eq!(got.len(), 2);
+        assert_eq!(format!("{}", got), "foo, bar");
+
+        let mut got = got.map(|field| Field {
+            alias: Some("alias".into()),
+            ..field
+        });
+        assert_eq!(got.len(), 2);
+        assert_eq!(format!("{}", got), "foo AS alias, bar AS alias");
+
+        for field in got.iter_mut() {
+            field.alias = None;
+        }
+        assert!(got.iter().all(|field| field.alias.is_none()));
+
+        let res: Result<FieldList, &str> = got.clone().try_map(Ok);
+        assert_eq!(res.unwrap(), got);
+
+        // Fallible cases
+
+        let res: Result<FieldList, &str> = got.try_map(|field| match field.expr {
+            Expr::VarRef { ref name, .. } if name.as_str() == "bar" => Err("bar"),
+            _ => Ok(field),
+        });
+        assert_eq!(res.unwrap_err(), "bar");
+    }
 }
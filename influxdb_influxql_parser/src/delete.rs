@@ -88,9 +88,29 @@ mod test {
         let (_, got) = delete_statement("DELETE FROM /foo/").unwrap();
         assert_eq!(format!("{}", got), "DELETE FROM /foo/");
 
+        let (_, got) = delete_statement("DELETE FROM /^cpu|mem$/ WHERE host = 'a'").unwrap();
+        assert_eq!(
+            format!("{}", got),
+            "DELETE FROM /^cpu|mem$/ WHERE host = 'a'"
+        );
+
         let (_, got) = delete_statement("DELETE FROM foo WHERE time > 10").unwrap();
         assert_eq!(format!("{}", got), "DELETE FROM foo WHERE time > 10");
 
+        let (_, got) = delete_statement("DELETE FROM cpu WHERE time < now() - 1h").unwrap();
+        assert_eq!(
+            format!("{}", got),
+            "DELETE FROM cpu WHERE time < now() - 1h"
+        );
+
+        // Tag predicates are valid InfluxQL, and are validated when converted
+        // to a delete predicate
+        let (_, got) = delete_statement("DELETE FROM cpu WHERE host = 'a' OR time < 10").unwrap();
+        assert_eq!(
+            format!("{}", got),
+            "DELETE FROM cpu WHERE host = 'a' OR time < 10"
+        );
+
         let (_, got) = delete_statement("DELETE WHERE time > 10").unwrap();
         assert_eq!(format!("{}", got), "DELETE WHERE time > 10");
 
@@ -104,5 +124,16 @@ mod test {
             delete_statement("DELETE FOO"),
             "invalid DELETE statement, expected FROM or WHERE"
         );
+
+        // Only a single measurement or regex may be specified
+        assert_expect_error!(
+            delete_statement("DELETE FROM foo, bar WHERE time > 10"),
+            "DELETE targets a single measurement or regex"
+        );
+
+        assert_expect_error!(
+            delete_statement("DELETE FROM /foo/, bar"),
+            "DELETE targets a single measurement or regex"
+        );
     }
 }
@@ -0,0 +1,324 @@
+//! Conversion of the condition of a [`DELETE`][crate::delete::DeleteStatement]
+//! statement into a [`DeletePredicate`].
+
+use crate::expression::arithmetic::{Expr, UnaryOperator};
+use crate::expression::conditional::{ConditionalExpression, ConditionalOperator};
+use crate::literal::Literal;
+use crate::select::eval_timestamp;
+use chrono::DateTime;
+use data_types::{DeleteExpr, DeletePredicate, Op, Scalar, TimestampRange, MIN_NANO_TIME};
+use std::fmt::{self, Display, Formatter};
+
+/// An error returned when a `DELETE` condition cannot be represented as a
+/// [`DeletePredicate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedPredicate(&'static str);
+
+impl Display for UnsupportedPredicate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl std::error::Error for UnsupportedPredicate {}
+
+/// Extends [`DeletePredicate`] with a conversion from the condition of an
+/// InfluxQL `DELETE` statement.
+pub trait DeletePredicateExt: Sized {
+    /// Converts the `condition` of a `DELETE` from `measurement` into a
+    /// [`DeletePredicate`], resolving `now()` to the nanosecond timestamp
+    /// `now`.
+    ///
+    /// The condition must be a conjunction of comparisons. Comparisons of
+    /// `time` with integer, duration or RFC3339 string timestamps, which may
+    /// be relative to `now()`, form the range of the predicate, which is
+    /// unbounded without them and must not be empty. Other columns may only
+    /// be compared with a literal using `=` or `!=`. A comparison of
+    /// `_measurement` with `measurement` is redundant, so is dropped.
+    fn from_influxql(
+        measurement: &str,
+        condition: Option<&ConditionalExpression>,
+        now: i64,
+    ) -> Result<Self, UnsupportedPredicate>;
+}
+
+impl DeletePredicateExt for DeletePredicate {
+    fn from_influxql(
+        measurement: &str,
+        condition: Option<&ConditionalExpression>,
+        now: i64,
+    ) -> Result<Self, UnsupportedPredicate> {
+        let mut converter = PredicateConverter {
+            measurement,
+            now,
+            start: MIN_NANO_TIME,
+            end: i64::MAX,
+            exprs: vec![],
+        };
+        if let Some(cond) = condition {
+            converter.conjunction(cond)?;
+        }
+
+        if converter.start >= converter.end {
+            return Err(UnsupportedPredicate(
+                "time range of DELETE predicate is empty",
+            ));
+        }
+
+        Ok(Self {
+            range: TimestampRange::new(converter.start, converter.end),
+            exprs: converter.exprs,
+        })
+    }
+}
+
+/// Accumulates the range and expressions of a [`DeletePredicate`].
+struct PredicateConverter<'a> {
+    measurement: &'a str,
+    now: i64,
+    start: i64,
+    end: i64,
+    exprs: Vec<DeleteExpr>,
+}
+
+impl<'a> PredicateConverter<'a> {
+    fn conjunction(&mut self, cond: &ConditionalExpression) -> Result<(), UnsupportedPredicate> {
+        match cond {
+            ConditionalExpression::Grouped(cond) => self.conjunction(cond),
+            ConditionalExpression::Binary {
+                lhs,
+                op: ConditionalOperator::And,
+                rhs,
+            } => {
+                self.conjunction(lhs)?;
+                self.conjunction(rhs)
+            }
+            ConditionalExpression::Binary {
+                op: ConditionalOperator::Or,
+                ..
+            } => Err(UnsupportedPredicate(
+                "OR is not supported in DELETE predicates",
+            )),
+            ConditionalExpression::Binary { lhs, op, rhs } => match (expr(lhs), expr(rhs)) {
+                (Some(Expr::VarRef { name, .. }), Some(value)) => self.comparison(name, *op, value),
+                // Reverse the comparison, so that the column is on the left
+                (Some(value), Some(Expr::VarRef { name, .. })) => {
+                    let op = match op {
+                        ConditionalOperator::Gt => ConditionalOperator::Lt,
+                        ConditionalOperator::GtEq => ConditionalOperator::LtEq,
+                        ConditionalOperator::Lt => ConditionalOperator::Gt,
+                        ConditionalOperator::LtEq => ConditionalOperator::GtEq,
+                        op => *op,
+                    };
+                    self.comparison(name, op, value)
+                }
+                _ => Err(UnsupportedPredicate(
+                    "DELETE predicates must compare a column with a value",
+                )),
+            },
+            ConditionalExpression::Expr(_) => Err(UnsupportedPredicate(
+                "DELETE predicates must compare a column with a value",
+            )),
+        }
+    }
+
+    fn comparison(
+        &mut self,
+        column: &str,
+        op: ConditionalOperator,
+        value: &Expr,
+    ) -> Result<(), UnsupportedPredicate> {
+        use ConditionalOperator::*;
+
+        if matches!(op, EqRegex | NotEqRegex) {
+            return Err(UnsupportedPredicate(
+                "regular expressions are not supported in DELETE predicates",
+            ));
+        }
+
+        if column.eq_ignore_ascii_case("time") {
+            let v = timestamp(value, self.now).ok_or(UnsupportedPredicate(
+                "time must be compared with an integer, duration or RFC3339 timestamp",
+            ))?;
+            let (start, end) = match op {
+                Gt => (v.saturating_add(1), i64::MAX),
+                GtEq => (v, i64::MAX),
+                Lt => (MIN_NANO_TIME, v),
+                LtEq => (MIN_NANO_TIME, v.saturating_add(1)),
+                Eq => (v, v.saturating_add(1)),
+                _ => {
+                    return Err(UnsupportedPredicate(
+                        "time may only be compared using =, <, <=, > or >=",
+                    ))
+                }
+            };
+            self.start = self.start.max(start);
+            self.end = self.end.min(end);
+            return Ok(());
+        }
+
+        let op = match op {
+            Eq => Op::Eq,
+            NotEq => Op::Ne,
+            _ => {
+                return Err(UnsupportedPredicate(
+                    "columns other than time may only be compared using = or !=",
+                ))
+            }
+        };
+        let scalar = scalar(value).ok_or(UnsupportedPredicate(
+            "columns must be compared with a literal value",
+        ))?;
+
+        if column == "_measurement" {
+            return match (op, &scalar) {
+                (Op::Eq, Scalar::String(name)) if name == self.measurement => Ok(()),
+                _ => Err(UnsupportedPredicate(
+                    "_measurement may only equal the measurement being deleted",
+                )),
+            };
+        }
+
+        self.exprs
+            .push(DeleteExpr::new(column.to_owned(), op, scalar));
+        Ok(())
+    }
+}
+
+/// Returns the arithmetic expression of `cond`, if it is not a conditional
+/// expression.
+fn expr(cond: &ConditionalExpression) -> Option<&Expr> {
+    match cond {
+        ConditionalExpression::Expr(expr) => Some(expr.as_ref()),
+        _ => None,
+    }
+}
+
+/// Evaluates `expr` to a timestamp in nanoseconds, resolving `now()` to
+/// `now`, if it is a constant or an RFC3339 string.
+fn timestamp(expr: &Expr, now: i64) -> Option<i64> {
+    match expr {
+        Expr::Literal(Literal::String(v)) => {
+            let t = DateTime::parse_from_rfc3339(v).ok()?;
+            t.timestamp()
+                .checked_mul(1_000_000_000)?
+                .checked_add(t.timestamp_subsec_nanos().into())
+        }
+        Expr::Nested(expr) => timestamp(expr, now),
+        _ => eval_timestamp(expr, now),
+    }
+}
+
+/// Converts the literal `expr` to a [`Scalar`].
+fn scalar(expr: &Expr) -> Option<Scalar> {
+    match expr {
+        Expr::Literal(Literal::Unsigned(v)) => i64::try_from(*v).ok().map(Scalar::I64),
+        Expr::Literal(Literal::Float(v)) => Some(Scalar::F64((*v).into())),
+        Expr::Literal(Literal::String(v)) => Some(Scalar::String(v.clone())),
+        Expr::Literal(Literal::Boolean(v)) => Some(Scalar::Bool(*v)),
+        Expr::Nested(expr) | Expr::UnaryOp(UnaryOperator::Plus, expr) => scalar(expr),
+        Expr::UnaryOp(UnaryOperator::Minus, expr) => match scalar(expr)? {
+            Scalar::I64(v) => Some(Scalar::I64(-v)),
+            Scalar::F64(v) => Some(Scalar::F64(-v)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::expression::conditional::conditional_expression;
+
+    #[test]
+    fn test_delete_predicate() {
+        const HOUR: i64 = 3_600_000_000_000;
+
+        let convert = |cond: &str| {
+            let (_, cond) = conditional_expression(cond).unwrap();
+            DeletePredicate::from_influxql("cpu", Some(&cond), 100 * HOUR)
+        };
+
+        // No condition deletes all time
+        let got = DeletePredicate::from_influxql("cpu", None, 100 * HOUR).unwrap();
+        assert_eq!(got.range, TimestampRange::new(MIN_NANO_TIME, i64::MAX));
+        assert!(got.exprs.is_empty());
+
+        // Time only
+        let got = convert("time >= 10 AND time < 20").unwrap();
+        assert_eq!(got.range, TimestampRange::new(10, 20));
+        assert!(got.exprs.is_empty());
+
+        let got = convert("(20 >= time) AND time > 1s").unwrap();
+        assert_eq!(got.range, TimestampRange::new(1_000_000_001, 21));
+
+        // Times relative to now()
+        let got = convert("time < now() - 1h").unwrap();
+        assert_eq!(got.range, TimestampRange::new(MIN_NANO_TIME, 99 * HOUR));
+
+        let got = convert("time >= now() - 2h AND time <= now() + 30m").unwrap();
+        assert_eq!(
+            got.range,
+            TimestampRange::new(98 * HOUR, 100 * HOUR + HOUR / 2 + 1)
+        );
+
+        // RFC3339 timestamps
+        let got = convert("time > '2022-01-01T00:00:00Z' AND time < '2022-01-01T00:00:01.5+00:00'")
+            .unwrap();
+        assert_eq!(
+            got.range,
+            TimestampRange::new(1_640_995_200_000_000_001, 1_640_995_201_500_000_000)
+        );
+
+        // Time and tags
+        let got = convert("host = 'a' AND time > 10 AND (region != 'us' AND _measurement = 'cpu')")
+            .unwrap();
+        assert_eq!(got.range, TimestampRange::new(11, i64::MAX));
+        assert_eq!(
+            got.exprs,
+            vec![
+                DeleteExpr::new("host".to_string(), Op::Eq, Scalar::String("a".to_string())),
+                DeleteExpr::new(
+                    "region".to_string(),
+                    Op::Ne,
+                    Scalar::String("us".to_string())
+                ),
+            ]
+        );
+
+        // Unsupported constructs
+        assert_eq!(
+            convert("time > 10 OR host = 'a'").unwrap_err(),
+            UnsupportedPredicate("OR is not supported in DELETE predicates")
+        );
+        assert_eq!(
+            convert("usage =~ /a/").unwrap_err(),
+            UnsupportedPredicate("regular expressions are not supported in DELETE predicates")
+        );
+        assert_eq!(
+            convert("usage > 5").unwrap_err(),
+            UnsupportedPredicate("columns other than time may only be compared using = or !=")
+        );
+        assert_eq!(
+            convert("time > 'yesterday'").unwrap_err(),
+            UnsupportedPredicate(
+                "time must be compared with an integer, duration or RFC3339 timestamp"
+            )
+        );
+        assert_eq!(
+            convert("time > now() - host").unwrap_err(),
+            UnsupportedPredicate(
+                "time must be compared with an integer, duration or RFC3339 timestamp"
+            )
+        );
+        assert_eq!(
+            convert("time > 20 AND time < 10").unwrap_err(),
+            UnsupportedPredicate("time range of DELETE predicate is empty")
+        );
+        assert_eq!(
+            convert("_measurement = 'mem'").unwrap_err(),
+            UnsupportedPredicate("_measurement may only equal the measurement being deleted")
+        );
+    }
+}
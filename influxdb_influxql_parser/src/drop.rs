@@ -1,12 +1,18 @@
-//! Types and parsers for the [`DROP MEASUREMENT`][sql] statement.
+//! Types and parsers for the [`DROP MEASUREMENT`][sql] and [`DROP SERIES`][series]
+//! statements.
 //!
 //! [sql]: https://docs.influxdata.com/influxdb/v1.8/query_language/manage-database/#delete-measurements-with-drop-measurement
+//! [series]: https://docs.influxdata.com/influxdb/v1.8/query_language/manage-database/#drop-series-from-the-index-with-drop-series
 
+use crate::common::{where_clause, WhereClause};
 use crate::identifier::{identifier, Identifier};
 use crate::internal::{expect, ParseResult};
 use crate::keywords::keyword;
-use nom::character::complete::multispace1;
-use nom::combinator::map;
+use crate::simple_from_clause::{show_from_clause, ShowFromClause};
+use crate::statement::Statement;
+use nom::branch::alt;
+use nom::character::complete::{multispace0, multispace1};
+use nom::combinator::{map, opt};
 use nom::sequence::{pair, preceded};
 use std::fmt::{Display, Formatter};
 
@@ -23,12 +29,55 @@ impl Display for DropMeasurementStatement {
     }
 }
 
-pub(crate) fn drop_statement(i: &str) -> ParseResult<&str, DropMeasurementStatement> {
+/// Represents a `DROP SERIES` statement.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DropSeriesStatement {
+    /// A `DROP SERIES` with a `FROM` clause specifying one or more
+    /// measurements and an optional `WHERE` clause to restrict which
+    /// series are dropped.
+    FromWhere {
+        /// Represents the `FROM` clause.
+        from: ShowFromClause,
+
+        /// Represents the optional `WHERE` clause.
+        condition: Option<WhereClause>,
+    },
+
+    /// A `DROP SERIES` with a `WHERE` clause to restrict which series
+    /// are dropped.
+    Where(WhereClause),
+}
+
+impl Display for DropSeriesStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DROP SERIES")?;
+
+        match self {
+            Self::FromWhere { from, condition } => {
+                write!(f, " {}", from)?;
+                if let Some(where_clause) = condition {
+                    write!(f, " {}", where_clause)?;
+                }
+            }
+            Self::Where(where_clause) => write!(f, " {}", where_clause)?,
+        };
+
+        Ok(())
+    }
+}
+
+/// Parse a `DROP MEASUREMENT` or `DROP SERIES` statement.
+pub(crate) fn drop_statement(i: &str) -> ParseResult<&str, Statement> {
     preceded(
         pair(keyword("DROP"), multispace1),
         expect(
-            "invalid DROP statement, expected MEASUREMENT",
-            drop_measurement,
+            "invalid DROP statement, expected MEASUREMENT or SERIES",
+            alt((
+                map(drop_measurement, |s| {
+                    Statement::DropMeasurement(Box::new(s))
+                }),
+                map(drop_series, |s| Statement::DropSeries(Box::new(s))),
+            )),
         ),
     )(i)
 }
@@ -46,19 +95,41 @@ fn drop_measurement(i: &str) -> ParseResult<&str, DropMeasurementStatement> {
     )(i)
 }
 
+fn drop_series(i: &str) -> ParseResult<&str, DropSeriesStatement> {
+    // drop_series ::= "DROP" "SERIES" ( from_clause where_clause? | where_clause )
+    preceded(
+        keyword("SERIES"),
+        expect(
+            "invalid DROP SERIES statement, expected FROM or WHERE",
+            preceded(
+                multispace1,
+                alt((
+                    map(
+                        pair(show_from_clause, opt(preceded(multispace0, where_clause))),
+                        |(from, condition)| DropSeriesStatement::FromWhere { from, condition },
+                    ),
+                    map(where_clause, DropSeriesStatement::Where),
+                )),
+            ),
+        ),
+    )(i)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::assert_expect_error;
+    use assert_matches::assert_matches;
 
     #[test]
     fn test_drop_statement() {
         drop_statement("DROP MEASUREMENT foo").unwrap();
+        drop_statement("DROP SERIES FROM foo").unwrap();
 
         // Fallible cases
         assert_expect_error!(
             drop_statement("DROP foo"),
-            "invalid DROP statement, expected MEASUREMENT"
+            "invalid DROP statement, expected MEASUREMENT or SERIES"
         );
     }
 
@@ -75,4 +146,39 @@ mod test {
             "invalid DROP MEASUREMENT statement, expected identifier"
         );
     }
+
+    #[test]
+    fn test_drop_series() {
+        // Validate via the Display trait, as the contents of the FROM and
+        // WHERE clauses are tested in their own modules.
+
+        let (_, got) = drop_series("SERIES FROM foo").unwrap();
+        assert_eq!(format!("{}", got), "DROP SERIES FROM foo");
+
+        let (_, got) = drop_series("SERIES FROM foo, /^bar/, db.rp.baz").unwrap();
+        assert_eq!(
+            format!("{}", got),
+            "DROP SERIES FROM foo, /^bar/, db.rp.baz"
+        );
+
+        let (_, got) = drop_series("SERIES FROM foo WHERE host = 'a'").unwrap();
+        assert_eq!(format!("{}", got), "DROP SERIES FROM foo WHERE host = 'a'");
+
+        let (_, got) = drop_series("SERIES WHERE host = 'a'").unwrap();
+        assert_matches!(got, DropSeriesStatement::Where(_));
+        assert_eq!(format!("{}", got), "DROP SERIES WHERE host = 'a'");
+
+        // Fallible cases
+
+        // A FROM or WHERE clause is required
+        assert_expect_error!(
+            drop_series("SERIES"),
+            "invalid DROP SERIES statement, expected FROM or WHERE"
+        );
+
+        assert_expect_error!(
+            drop_series("SERIES foo"),
+            "invalid DROP SERIES statement, expected FROM or WHERE"
+        );
+    }
 }
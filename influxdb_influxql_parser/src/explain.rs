@@ -49,6 +49,25 @@ pub struct ExplainStatement {
     pub select: Box<SelectStatement>,
 }
 
+impl ExplainStatement {
+    /// Returns true if the statement is to be executed and its plan
+    /// annotated with runtime metrics, as specified by `ANALYZE`.
+    pub fn analyze(&self) -> bool {
+        matches!(
+            self.options,
+            Some(ExplainOption::Analyze | ExplainOption::AnalyzeVerbose)
+        )
+    }
+
+    /// Returns true if additional detail is requested, as specified by `VERBOSE`.
+    pub fn verbose(&self) -> bool {
+        matches!(
+            self.options,
+            Some(ExplainOption::Verbose | ExplainOption::AnalyzeVerbose)
+        )
+    }
+}
+
 impl Display for ExplainStatement {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_str("EXPLAIN ")?;
@@ -105,22 +124,26 @@ mod test {
         let (remain, got) = explain_statement("EXPLAIN SELECT val from temp").unwrap();
         assert_eq!(remain, ""); // assert that all input was consumed
         assert_matches!(got.options, None);
+        assert!(!got.analyze() && !got.verbose());
         assert_eq!(format!("{}", got), "EXPLAIN SELECT val FROM temp");
 
         let (remain, got) = explain_statement("EXPLAIN VERBOSE SELECT val from temp").unwrap();
         assert_eq!(remain, "");
         assert_matches!(&got.options, Some(o) if *o == ExplainOption::Verbose);
+        assert!(!got.analyze() && got.verbose());
         assert_eq!(format!("{}", got), "EXPLAIN VERBOSE SELECT val FROM temp");
 
         let (remain, got) = explain_statement("EXPLAIN ANALYZE SELECT val from temp").unwrap();
         assert_eq!(remain, "");
         assert_matches!(&got.options, Some(o) if *o == ExplainOption::Analyze);
+        assert!(got.analyze() && !got.verbose());
         assert_eq!(format!("{}", got), "EXPLAIN ANALYZE SELECT val FROM temp");
 
         let (remain, got) =
             explain_statement("EXPLAIN ANALYZE VERBOSE SELECT val from temp").unwrap();
         assert_eq!(remain, "");
         assert_matches!(&got.options, Some(o) if *o == ExplainOption::AnalyzeVerbose);
+        assert!(got.analyze() && got.verbose());
         assert_eq!(
             format!("{}", got),
             "EXPLAIN ANALYZE VERBOSE SELECT val FROM temp"
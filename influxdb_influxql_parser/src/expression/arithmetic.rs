@@ -1,5 +1,5 @@
 use crate::identifier::unquoted_identifier;
-use crate::internal::{expect, ParseResult};
+use crate::internal::{expect, nested, nested_or_backtrack, within_deadline, ParseResult};
 use crate::keywords::keyword;
 use crate::literal::literal_regex;
 use crate::{
@@ -17,6 +17,7 @@ use std::fmt::{Display, Formatter, Write};
 
 /// An InfluxQL arithmetic expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expr {
     /// Reference to a tag or field key.
     VarRef {
@@ -62,6 +63,16 @@ pub enum Expr {
     /// A literal wildcard (`*`) with an optional data type selection.
     Wildcard(Option<WildcardType>),
 
+    /// A wildcard qualified by a measurement, such as `cpu.*`, with an optional
+    /// data type selection, which selects the columns of that measurement only.
+    QualifiedWildcard {
+        /// The name of the measurement.
+        measurement: Identifier,
+
+        /// An optional data type selection specified using the `::` operator.
+        data_type: Option<WildcardType>,
+    },
+
     /// A DISTINCT <identifier> expression.
     Distinct(Identifier),
 
@@ -142,6 +153,14 @@ impl Display for Expr {
             }
             Self::Wildcard(Some(dt)) => write!(f, "*::{}", dt)?,
             Self::Wildcard(None) => f.write_char('*')?,
+            Self::QualifiedWildcard {
+                measurement,
+                data_type: Some(dt),
+            } => write!(f, "{}.*::{}", measurement, dt)?,
+            Self::QualifiedWildcard {
+                measurement,
+                data_type: None,
+            } => write!(f, "{}.*", measurement)?,
             Self::Distinct(ident) => write!(f, "DISTINCT {}", ident)?,
         }
 
@@ -151,6 +170,7 @@ impl Display for Expr {
 
 /// Specifies the data type of a wildcard (`*`) when using the `::` operator.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WildcardType {
     /// Indicates the wildcard refers to tags only.
     Tag,
@@ -175,6 +195,7 @@ impl Display for WildcardType {
 ///
 /// [cast]: https://docs.influxdata.com/influxdb/v1.8/query_language/explore-data/#cast-operations
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VarRefDataType {
     /// Represents a 64-bit float.
     Float,
@@ -205,6 +226,7 @@ impl Display for VarRefDataType {
 
 /// An InfluxQL unary operator.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnaryOperator {
     /// Represents the unary `+` operator.
     Plus,
@@ -223,6 +245,7 @@ impl Display for UnaryOperator {
 
 /// An InfluxQL binary operators.
 #[derive(Clone, Debug, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinaryOperator {
     /// Represents the `+` operator.
     Add,
@@ -270,7 +293,7 @@ where
         )),
     )(i)?;
 
-    let (i, e) = factor::<T>(i)?;
+    let (i, e) = nested("expression nesting too deep", factor::<T>)(i)?;
 
     Ok((i, Expr::UnaryOp(op, e.into())))
 }
@@ -282,7 +305,9 @@ where
 {
     delimited(
         preceded(multispace0, char('(')),
-        map(arithmetic::<T>, |e| Expr::Nested(e.into())),
+        // Exceeding the maximum nesting depth is recoverable, so that a conditional
+        // expression may report the error in its own terms
+        nested_or_backtrack(map(arithmetic::<T>, |e| Expr::Nested(e.into()))),
         preceded(multispace0, char(')')),
     )(i)
 }
@@ -317,6 +342,9 @@ where
 }
 
 /// Parse a variable reference, which is an identifier followed by an optional cast expression.
+///
+/// A double-quoted identifier is a single field name, even if it contains
+/// operators or whitespace, such as `"field-name"`.
 pub(crate) fn var_ref(i: &str) -> ParseResult<&str, Expr> {
     map(
         pair(
@@ -347,7 +375,7 @@ fn factor<T>(i: &str) -> ParseResult<&str, Expr>
 where
     T: ArithmeticParsers,
 {
-    alt((unary::<T>, parens::<T>, T::operand))(i)
+    within_deadline(alt((unary::<T>, parens::<T>, T::operand)))(i)
 }
 
 /// Parse arithmetic, precedence priority 2 operators.
@@ -524,6 +552,32 @@ mod test {
         assert_expect_error!(var_ref("foo::invalid"), "invalid data type for tag or field reference, expected float, integer, string, boolean, tag or field");
     }
 
+    #[test]
+    fn test_var_ref_special_characters() {
+        // Operators and whitespace within a double-quoted name are part of the name
+        let (rem, got) = arithmetic_expression(r#""field-name""#).unwrap();
+        assert_eq!(rem, "");
+        assert_eq!(got, var_ref!("field-name"));
+        let (_, got) = arithmetic_expression(r#""a+b c"::float * 2"#).unwrap();
+        assert_eq!(got, binary_op!(var_ref!("a+b c", Float), Mul, 2));
+
+        // The name is quoted again when displayed
+        assert_eq!(got.to_string(), r#""a+b c"::float * 2"#);
+
+        // Unquoted, the operator separates two variable references
+        let (_, got) = arithmetic_expression("a-b").unwrap();
+        assert_eq!(got, binary_op!(var_ref!("a"), Sub, var_ref!("b")));
+
+        // An escaped quote is part of the name
+        let (_, got) = var_ref(r#""say \"hi\"""#).unwrap();
+        assert_eq!(got, var_ref!(r#"say "hi""#));
+
+        // A doubled quote is not an escape, and terminates the name
+        let (rem, got) = var_ref(r#""a""b""#).unwrap();
+        assert_eq!(got, var_ref!("a"));
+        assert_eq!(rem, r#""b""#);
+    }
+
     #[test]
     fn test_spacing_and_remaining_input() {
         // Validate that the remaining input is returned
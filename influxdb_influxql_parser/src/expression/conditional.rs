@@ -1,7 +1,7 @@
 use crate::expression::arithmetic::{
     arithmetic, call_expression, var_ref, ArithmeticParsers, Expr,
 };
-use crate::internal::{expect, verify, ParseResult};
+use crate::internal::{expect, nested, verify, ParseResult};
 use crate::keywords::keyword;
 use crate::literal::{literal_no_regex, literal_regex, Literal};
 use crate::parameter::parameter;
@@ -16,6 +16,7 @@ use std::fmt::{Display, Formatter, Write};
 
 /// Represents on of the conditional operators supported by [`ConditionalExpression::Binary`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConditionalOperator {
     /// Represents the `=` operator.
     Eq,
@@ -61,6 +62,7 @@ impl Display for ConditionalOperator {
 
 /// Represents a conditional expression.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConditionalExpression {
     /// Represents an arithmetic expression.
     Expr(Box<Expr>),
@@ -99,9 +101,12 @@ impl From<Literal> for ConditionalExpression {
 fn parens(i: &str) -> ParseResult<&str, ConditionalExpression> {
     delimited(
         preceded(multispace0, char('(')),
-        map(conditional_expression, |e| {
-            ConditionalExpression::Grouped(e.into())
-        }),
+        nested(
+            "condition nesting too deep",
+            map(conditional_expression, |e| {
+                ConditionalExpression::Grouped(e.into())
+            }),
+        ),
         preceded(multispace0, char(')')),
     )(i)
 }
@@ -350,6 +355,29 @@ mod test {
         assert_failure!(conditional_expression("5 = /regex/"));
     }
 
+    #[test]
+    fn test_conditional_expression_nesting() {
+        let nest = |depth: usize| format!("{}foo = 1{}", "(".repeat(depth), ")".repeat(depth));
+
+        let max_depth = crate::ParseLimits::default().max_nesting_depth;
+        let input = nest(max_depth);
+        let (_, got) = conditional_expression(&input).unwrap();
+        assert_eq!(got.to_string(), input);
+
+        // Fallible cases
+
+        assert_expect_error!(
+            conditional_expression(&nest(max_depth + 1)),
+            "condition nesting too deep"
+        );
+
+        // pathological nesting fails gracefully rather than overflowing the stack
+        assert_expect_error!(
+            conditional_expression(&nest(100_000)),
+            "condition nesting too deep"
+        );
+    }
+
     #[test]
     fn test_logical_expression() {
         let (_, got) = conditional_expression("5 AND 6").unwrap();
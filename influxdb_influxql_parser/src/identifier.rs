@@ -14,15 +14,22 @@
 use crate::internal::ParseResult;
 use crate::keywords::sql_keyword;
 use crate::string::double_quoted_string;
-use crate::{impl_tuple_clause, write_quoted_string};
+use crate::write_quoted_string;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::{alpha1, alphanumeric1};
 use nom::combinator::{map, not, recognize};
 use nom::multi::many0_count;
 use nom::sequence::{pair, preceded};
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::fmt;
-use std::fmt::{Display, Formatter, Write};
+use std::fmt::{Debug, Display, Formatter, Write};
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
 
 /// Parse an unquoted InfluxQL identifier.
 pub(crate) fn unquoted_identifier(i: &str) -> ParseResult<&str, &str> {
@@ -36,20 +43,216 @@ pub(crate) fn unquoted_identifier(i: &str) -> ParseResult<&str, &str> {
 }
 
 /// A type that represents an InfluxQL identifier.
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-pub struct Identifier(pub(crate) String);
+///
+/// An identifier either owns its name or, when parsed with an [`Interner`],
+/// shares it with other occurrences of the same name. Both representations
+/// are equivalent for the purposes of comparison and hashing.
+#[derive(Clone)]
+pub struct Identifier(pub(crate) Name);
 
-impl_tuple_clause!(Identifier, String);
+/// The storage of the name of an [`Identifier`].
+#[derive(Clone)]
+pub(crate) enum Name {
+    Owned(String),
+    Shared(Arc<String>),
+}
+
+impl Identifier {
+    /// Create a new instance with the specified value.
+    pub fn new(value: String) -> Self {
+        Self(Name::Owned(value))
+    }
+
+    /// Returns true if the name of this identifier is shared with other
+    /// identifiers, and therefore refers to the same storage.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Name::Shared(a), Name::Shared(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
+    /// Create an identifier from `s`, sharing its name with other identifiers
+    /// if an [`Interner`] is active for the current parse.
+    fn intern(s: &str) -> Self {
+        INTERNER.with(|v| match v.borrow_mut().as_mut() {
+            Some(interner) => interner.intern(s),
+            None => s.into(),
+        })
+    }
+}
+
+impl Deref for Identifier {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        match &self.0 {
+            Name::Owned(s) => s,
+            Name::Shared(s) => s,
+        }
+    }
+}
+
+impl DerefMut for Identifier {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match &mut self.0 {
+            Name::Owned(s) => s,
+            // Copies the name, if it is shared with other identifiers
+            Name::Shared(s) => Arc::make_mut(s),
+        }
+    }
+}
+
+impl From<String> for Identifier {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
 
 impl From<&str> for Identifier {
     fn from(s: &str) -> Self {
-        Self(s.to_string())
+        Self::new(s.to_string())
+    }
+}
+
+impl PartialEq for Identifier {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Identifier {}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for Identifier {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl Debug for Identifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Identifier").field(self.deref()).finish()
+    }
+}
+
+/// Identifiers are serialized as their name, regardless of whether it is shared.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Identifier {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Identifier {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <String as serde::Deserialize>::deserialize(deserializer).map(Self::new)
+    }
+}
+
+/// Deduplicates the names of the identifiers of parsed statements, so that
+/// repeated occurrences of a name, such as in a large `IN` list or wide field
+/// list, share a single allocation.
+///
+/// The memory used by the interner is bounded by `max_bytes`. Once the bound is
+/// reached, names not already interned are owned by their identifiers, as if
+/// parsed without an interner.
+#[derive(Debug, Default)]
+pub struct Interner {
+    names: HashSet<InternedName>,
+    bytes: usize,
+    max_bytes: usize,
+}
+
+impl Interner {
+    /// Create an interner that retains at most `max_bytes` of names.
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            names: HashSet::new(),
+            bytes: 0,
+            max_bytes,
+        }
+    }
+
+    /// Returns the number of distinct names retained by the interner.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns true if the interner retains no names.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    fn intern(&mut self, s: &str) -> Identifier {
+        if let Some(name) = self.names.get(s) {
+            return Identifier(Name::Shared(Arc::clone(&name.0)));
+        }
+
+        if self.bytes + s.len() > self.max_bytes {
+            return s.into();
+        }
+
+        let name = Arc::new(s.to_string());
+        self.bytes += s.len();
+        self.names.insert(InternedName(Arc::clone(&name)));
+        Identifier(Name::Shared(name))
+    }
+}
+
+/// A name retained by an [`Interner`], which may be looked up by `&str`.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct InternedName(Arc<String>);
+
+impl Borrow<str> for InternedName {
+    fn borrow(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+thread_local! {
+    /// The interner of the statements currently being parsed, if any.
+    static INTERNER: RefCell<Option<Interner>> = RefCell::new(None);
+}
+
+/// Calls `f` with `interner` active for any identifiers parsed, restoring the
+/// previous interner on completion, including if `f` panics.
+pub(crate) fn with_interner<R>(interner: &mut Interner, f: impl FnOnce() -> R) -> R {
+    let prev = INTERNER.with(|v| v.replace(Some(std::mem::take(interner))));
+    let _guard = InternerGuard { interner, prev };
+    f()
+}
+
+/// Returns the active interner to its owner and restores the previous
+/// interner when dropped.
+struct InternerGuard<'a> {
+    interner: &'a mut Interner,
+    prev: Option<Interner>,
+}
+
+impl Drop for InternerGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(interner) = INTERNER.with(|v| v.replace(self.prev.take())) {
+            *self.interner = interner;
+        }
     }
 }
 
 impl Display for Identifier {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write_quoted_string!(f, '"', self.0.as_str(), unquoted_identifier, '\n' => "\\n", '\\' => "\\\\", '"' => "\\\"");
+        write_quoted_string!(f, '"', self.as_str(), unquoted_identifier, '\n' => "\\n", '\\' => "\\\\", '"' => "\\\"");
         Ok(())
     }
 }
@@ -58,8 +261,8 @@ impl Display for Identifier {
 pub(crate) fn identifier(i: &str) -> ParseResult<&str, Identifier> {
     // See: https://github.com/influxdata/influxql/blob/df51a45762be9c1b578f01718fa92d286a843fe9/scanner.go#L358-L362
     alt((
-        map(unquoted_identifier, Into::into),
-        map(double_quoted_string, Into::into),
+        map(unquoted_identifier, Identifier::intern),
+        map(double_quoted_string, |s| Identifier::intern(&s)),
     ))(i)
 }
 
@@ -103,14 +306,67 @@ mod test {
         assert_eq!(got, "quick_draw".into());
     }
 
+    #[test]
+    fn test_identifier_interned() {
+        let mut interner = Interner::new(1024);
+        let (a, b) = with_interner(&mut interner, || {
+            let (_, a) = identifier("cpu").unwrap();
+            let (_, b) = identifier("\"cpu\"").unwrap();
+            (a, b)
+        });
+        assert!(a.ptr_eq(&b));
+        assert_eq!(interner.len(), 1);
+
+        // Without an interner, each occurrence owns its name
+        let (_, c) = identifier("cpu").unwrap();
+        assert!(!a.ptr_eq(&c));
+        assert_eq!(a, c);
+
+        // Modifying a shared name does not affect other occurrences
+        let mut b = b;
+        b.push_str("_v2");
+        assert_eq!(a, "cpu".into());
+        assert_eq!(b, "cpu_v2".into());
+
+        // Names beyond the bound are not interned
+        let mut interner = Interner::new(3);
+        let (a, b) = with_interner(&mut interner, || {
+            let (_, a) = identifier("mem").unwrap();
+            let (_, b) = identifier("disk").unwrap();
+            (a, b)
+        });
+        assert_eq!((a.as_str(), b.as_str()), ("mem", "disk"));
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn test_identifier_interned_panic() {
+        let mut interner = Interner::new(1024);
+        let got = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            with_interner(&mut interner, || {
+                identifier("cpu").unwrap();
+                panic!("parser panicked");
+            })
+        }));
+        assert!(got.is_err());
+
+        // The names interned before the panic are returned to the caller
+        assert_eq!(interner.len(), 1);
+
+        // and the interner is no longer active
+        let (_, a) = identifier("cpu").unwrap();
+        let (_, b) = identifier("cpu").unwrap();
+        assert!(!a.ptr_eq(&b));
+    }
+
     #[test]
     fn test_identifier_display() {
         // Identifier properly escapes specific characters and quotes output
-        let got = format!("{}", Identifier("quick\n\t\\\"'draw \u{1f47d}".into()));
+        let got = format!("{}", Identifier::from("quick\n\t\\\"'draw \u{1f47d}"));
         assert_eq!(got, r#""quick\n	\\\"'draw 👽""#);
 
         // Identifier displays unquoted output
-        let got = format!("{}", Identifier("quick_draw".into()));
+        let got = format!("{}", Identifier::from("quick_draw"));
         assert_eq!(got, "quick_draw");
     }
 }
@@ -1,9 +1,13 @@
 //! Internal result and error types used to build InfluxQL parsers
 //!
+use crate::ParseLimits;
 use nom::error::{ErrorKind as NomErrorKind, ParseError as NomParseError};
 use nom::Parser;
-use std::borrow::Borrow;
+use std::borrow::{Borrow, Cow};
+use std::cell::Cell;
 use std::fmt::{Display, Formatter};
+use std::thread::LocalKey;
+use std::time::Instant;
 
 /// This trait must be implemented in order to use the [`map_fail`] and
 /// [`expect`] functions for generating user-friendly error messages.
@@ -14,7 +18,12 @@ pub trait ParseError<'a>: NomParseError<&'a str> + Sized {
 /// An internal error type used to build InfluxQL parsers.
 #[derive(Debug, PartialEq, Eq)]
 pub enum Error<I> {
-    Syntax { input: I, message: &'static str },
+    /// A syntax error, whose message may be formatted to describe the
+    /// offending input, such as an unknown time zone.
+    Syntax {
+        input: I,
+        message: Cow<'static, str>,
+    },
     Nom(I, NomErrorKind),
 }
 
@@ -31,7 +40,10 @@ impl<I: Display> Display for Error<I> {
 
 impl<'a> ParseError<'a> for Error<&'a str> {
     fn from_message(input: &'a str, message: &'static str) -> Self {
-        Self::Syntax { input, message }
+        Self::Syntax {
+            input,
+            message: message.into(),
+        }
     }
 }
 
@@ -97,6 +109,134 @@ where
     }
 }
 
+thread_local! {
+    /// The nesting depth of the expression currently being parsed.
+    static NESTING_DEPTH: Cell<usize> = Cell::new(0);
+
+    /// The nesting depth of the subquery currently being parsed.
+    static SUBQUERY_DEPTH: Cell<usize> = Cell::new(0);
+
+    /// The limits applied to the input currently being parsed.
+    static LIMITS: Cell<ParseLimits> = Cell::new(ParseLimits::default());
+}
+
+/// Calls `f` with the parsing limits set to `limits`, restoring the previous
+/// limits on completion, including if `f` panics.
+pub(crate) fn with_limits<R>(limits: ParseLimits, f: impl FnOnce() -> R) -> R {
+    let _guard = LimitsGuard(LIMITS.with(|v| v.replace(limits)));
+    f()
+}
+
+/// Restores the parsing limits it holds when dropped.
+struct LimitsGuard(ParseLimits);
+
+impl Drop for LimitsGuard {
+    fn drop(&mut self) {
+        LIMITS.with(|v| v.set(self.0));
+    }
+}
+
+/// Returns the limits applied to the input currently being parsed.
+pub(crate) fn limits() -> ParseLimits {
+    LIMITS.with(|v| v.get())
+}
+
+/// Tracks a single level of nesting of the `depth` counter for its lifetime.
+struct NestingGuard {
+    depth: &'static LocalKey<Cell<usize>>,
+}
+
+impl NestingGuard {
+    /// Enters the next level of nesting, returning `None` if that would
+    /// exceed `max_depth`.
+    fn enter(depth: &'static LocalKey<Cell<usize>>, max_depth: usize) -> Option<Self> {
+        let next = depth.with(|v| v.get()) + 1;
+        if next > max_depth {
+            return None;
+        }
+        depth.with(|v| v.set(next));
+        Some(Self { depth })
+    }
+}
+
+impl Drop for NestingGuard {
+    fn drop(&mut self) {
+        self.depth.with(|v| v.set(v.get() - 1));
+    }
+}
+
+/// Applies `f` one level deeper in the nesting of the input. If the maximum
+/// nesting depth is exceeded, returns a [`nom::Err::Failure`] with the specified
+/// `message`.
+pub fn nested<'a, E: ParseError<'a>, F, O>(
+    message: &'static str,
+    mut f: F,
+) -> impl FnMut(&'a str) -> ParseResult<&'a str, O, E>
+where
+    F: Parser<&'a str, O, E>,
+{
+    move |i| match NestingGuard::enter(&NESTING_DEPTH, limits().max_nesting_depth) {
+        Some(_guard) => f.parse(i),
+        None => Err(nom::Err::Failure(E::from_message(i, message))),
+    }
+}
+
+/// Applies `f` to parse a subquery, one level deeper in the nesting of
+/// subqueries. If the maximum subquery depth is exceeded, returns a
+/// [`nom::Err::Failure`] with the specified `message`.
+pub fn subquery<'a, E: ParseError<'a>, F, O>(
+    message: &'static str,
+    mut f: F,
+) -> impl FnMut(&'a str) -> ParseResult<&'a str, O, E>
+where
+    F: Parser<&'a str, O, E>,
+{
+    move |i| match NestingGuard::enter(&SUBQUERY_DEPTH, limits().max_subquery_depth) {
+        Some(_guard) => f.parse(i),
+        None => Err(nom::Err::Failure(E::from_message(i, message))),
+    }
+}
+
+/// Applies `f` one level deeper in the nesting of the input. Unlike [`nested`],
+/// exceeding the maximum nesting depth returns a recoverable [`nom::Err::Error`],
+/// so that the caller may try an alternative parser.
+pub fn nested_or_backtrack<'a, E: ParseError<'a>, F, O>(
+    mut f: F,
+) -> impl FnMut(&'a str) -> ParseResult<&'a str, O, E>
+where
+    F: Parser<&'a str, O, E>,
+{
+    move |i| match NestingGuard::enter(&NESTING_DEPTH, limits().max_nesting_depth) {
+        Some(_guard) => f.parse(i),
+        None => Err(nom::Err::Error(E::from_error_kind(
+            i,
+            NomErrorKind::TooLarge,
+        ))),
+    }
+}
+
+/// The message of the error returned when parsing exceeds its deadline.
+const DEADLINE_EXCEEDED: &str = "parse timed out";
+
+/// Applies `f` if the parsing deadline has not passed; otherwise, returns a
+/// [`nom::Err::Failure`] with the message [`DEADLINE_EXCEEDED`].
+///
+/// This should wrap parsers that are applied repeatedly for large inputs,
+/// such as the elements of lists and the operands of expressions.
+pub fn within_deadline<'a, E: ParseError<'a>, F, O>(
+    mut f: F,
+) -> impl FnMut(&'a str) -> ParseResult<&'a str, O, E>
+where
+    F: Parser<&'a str, O, E>,
+{
+    move |i| match limits().deadline {
+        Some(deadline) if Instant::now() >= deadline => {
+            Err(nom::Err::Failure(E::from_message(i, DEADLINE_EXCEEDED)))
+        }
+        _ => f.parse(i),
+    }
+}
+
 impl<I> NomParseError<I> for Error<I> {
     fn from_error_kind(input: I, kind: NomErrorKind) -> Self {
         Self::Nom(input, kind)
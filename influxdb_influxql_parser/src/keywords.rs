@@ -143,10 +143,15 @@ static KEYWORDS: Lazy<HashSet<Token<'static>>> = Lazy::new(|| {
     ])
 });
 
+/// Returns true if `s` is an InfluxQL reserved keyword, ignoring case.
+pub(crate) fn is_keyword(s: &str) -> bool {
+    KEYWORDS.contains(&Token(s))
+}
+
 /// Matches any InfluxQL reserved keyword.
 pub(crate) fn sql_keyword(i: &str) -> ParseResult<&str, &str> {
     verify(terminated(alpha1, keyword_follow_char), |tok: &str| {
-        KEYWORDS.contains(&Token(tok))
+        is_keyword(tok)
     })(i)
 }
 
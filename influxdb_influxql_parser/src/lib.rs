@@ -15,18 +15,23 @@
 )]
 
 use crate::common::statement_terminator;
+use crate::identifier::Interner;
 use crate::internal::Error as InternalError;
+use crate::select::SelectStatement;
 use crate::statement::{statement, Statement};
 use nom::character::complete::multispace0;
 use nom::combinator::eof;
 use nom::Offset;
 use std::fmt::{Debug, Display, Formatter};
+use std::time::Instant;
 
 #[cfg(test)]
 mod test_util;
 
 pub mod common;
 pub mod delete;
+#[cfg(feature = "data_types")]
+pub mod delete_predicate;
 pub mod drop;
 pub mod explain;
 pub mod expression;
@@ -35,17 +40,22 @@ mod internal;
 mod keywords;
 pub mod literal;
 pub mod parameter;
+#[cfg(feature = "data_types")]
+pub mod schema;
 pub mod select;
 pub mod show;
 pub mod show_field_keys;
 pub mod show_measurements;
 pub mod show_retention_policies;
+pub mod show_series;
 pub mod show_tag_keys;
 pub mod show_tag_values;
 pub mod simple_from_clause;
 pub mod statement;
 pub mod string;
+pub mod tokenizer;
 pub mod visit;
+pub mod visit_mut;
 
 /// A error returned when parsing an InfluxQL query using
 /// [`parse_statements`] fails.
@@ -53,6 +63,42 @@ pub mod visit;
 pub struct ParseError {
     message: String,
     pos: usize,
+    line: usize,
+    column: usize,
+}
+
+impl ParseError {
+    fn new(message: impl Into<String>, input: &str, remaining: &str) -> Self {
+        let (line, column, pos) = locate_error(input, remaining);
+        Self {
+            message: message.into(),
+            pos,
+            line,
+            column,
+        }
+    }
+
+    /// Returns the message describing the cause of the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the byte offset of the error in the input.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the line of the error in the input, starting from 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column of the error within its line, starting from 1.
+    ///
+    /// The column is measured in characters, rather than bytes.
+    pub fn column(&self) -> usize {
+        self.column
+    }
 }
 
 impl Display for ParseError {
@@ -61,14 +107,144 @@ impl Display for ParseError {
     }
 }
 
+/// Returns the location of `remaining`, a suffix of `original`, as the
+/// line, column and byte offset at which it starts.
+///
+/// Lines and columns start from 1, and columns are measured in characters.
+///
+/// # Panics
+///
+/// Panics if `remaining` is not a suffix of `original`.
+pub fn locate_error(original: &str, remaining: &str) -> (usize, usize, usize) {
+    let offset = original.offset(remaining);
+    let consumed = &original[..offset];
+    let line_start = consumed.rfind('\n').map_or(0, |i| i + 1);
+    let line = consumed.matches('\n').count() + 1;
+    let column = consumed[line_start..].chars().count() + 1;
+    (line, column, offset)
+}
+
 /// ParseResult is type that represents the success or failure of parsing
 /// a given input into a set of InfluxQL statements.
 ///
 /// Errors are human-readable messages indicating the cause of the parse failure.
 pub type ParseResult = Result<Vec<Statement>, ParseError>;
 
-/// Parse the input into a set of InfluxQL statements.
+/// Limits applied when parsing InfluxQL, to guard against pathological input.
+///
+/// All the bounds on parsing are configured here, and applied by a [`Parser`]
+/// created using [`Parser::with_limits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// The maximum depth of nested, parenthesised expressions, such as
+    /// `((foo = 'bar'))`.
+    pub max_nesting_depth: usize,
+
+    /// The maximum depth of nested subqueries in the `FROM` clause of a
+    /// `SELECT` statement, such as `SELECT a FROM (SELECT a FROM b)`.
+    pub max_subquery_depth: usize,
+
+    /// The maximum number of statements in the input. Empty statements, such
+    /// as `;;`, are not counted.
+    pub max_statements: usize,
+
+    /// The maximum number of fields in the projection of a `SELECT` statement.
+    ///
+    /// Unlimited by default.
+    pub max_fields: usize,
+
+    /// The maximum length, in bytes, of a numeric literal, such as `123` or
+    /// `1.5e10`, excluding its sign.
+    ///
+    /// Unlimited by default.
+    pub max_number_length: usize,
+
+    /// The time by which parsing must complete, if any.
+    ///
+    /// The deadline is checked as each list element and expression operand is
+    /// parsed, so parsing may overrun it by the time taken to parse one of those.
+    pub deadline: Option<Instant>,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_nesting_depth: 64,
+            max_subquery_depth: 30,
+            max_statements: 100,
+            max_fields: usize::MAX,
+            max_number_length: usize::MAX,
+            deadline: None,
+        }
+    }
+}
+
+/// Parses InfluxQL statements, applying its [`ParseLimits`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Parser {
+    limits: ParseLimits,
+}
+
+impl Parser {
+    /// Returns a parser that applies the specified `limits`.
+    pub fn with_limits(limits: ParseLimits) -> Self {
+        Self { limits }
+    }
+
+    /// Returns the limits applied by the parser.
+    pub fn limits(&self) -> ParseLimits {
+        self.limits
+    }
+
+    /// Parse the input into a set of InfluxQL statements.
+    pub fn parse(&self, input: &str) -> ParseResult {
+        internal::with_limits(self.limits, || {
+            parse_input(input, self.limits.max_statements)
+        })
+    }
+}
+
+/// Parse the input into a set of InfluxQL statements, applying the default
+/// [`ParseLimits`].
 pub fn parse_statements(input: &str) -> ParseResult {
+    Parser::default().parse(input)
+}
+
+/// Parse the input into a set of InfluxQL statements, applying the specified `limits`,
+/// and sharing the names of repeated identifiers using `interner`.
+///
+/// The `interner` may be reused across calls, to share names between the
+/// statements of each.
+pub fn parse_statements_with_interner(
+    input: &str,
+    limits: ParseLimits,
+    interner: &mut Interner,
+) -> ParseResult {
+    identifier::with_interner(interner, || Parser::with_limits(limits).parse(input))
+}
+
+/// Parse the input into a single InfluxQL `SELECT` statement, failing with
+/// "parse timed out" if parsing has not completed by `deadline`.
+///
+/// The default [`ParseLimits`] are otherwise applied. A deadline may be applied
+/// to the parsing of any statements by setting [`ParseLimits::deadline`] of a
+/// [`Parser`].
+pub fn parse_select_with_deadline(
+    input: &str,
+    deadline: Instant,
+) -> Result<SelectStatement, ParseError> {
+    let parser = Parser::with_limits(ParseLimits {
+        max_statements: 1,
+        deadline: Some(deadline),
+        ..Default::default()
+    });
+    match parser.parse(input)?.pop() {
+        Some(Statement::Select(stmt)) => Ok(*stmt),
+        _ => Err(ParseError::new("expected SELECT statement", input, input)),
+    }
+}
+
+fn parse_input(input: &str, max_statements: usize) -> ParseResult {
     let mut res = Vec::new();
     let mut i: &str = input;
 
@@ -88,6 +264,10 @@ pub fn parse_statements(input: &str) -> ParseResult {
             continue;
         }
 
+        if res.len() == max_statements {
+            return Err(ParseError::new("too many statements in request", input, i));
+        }
+
         match statement(i) {
             Ok((i1, o)) => {
                 res.push(o);
@@ -96,26 +276,24 @@ pub fn parse_statements(input: &str) -> ParseResult {
             Err(nom::Err::Failure(InternalError::Syntax {
                 input: pos,
                 message,
-            })) => {
-                return Err(ParseError {
-                    message: message.into(),
-                    pos: input.offset(pos),
-                })
-            }
+            })) => return Err(ParseError::new(message, input, pos)),
             // any other error indicates an invalid statement
-            Err(_) => {
-                return Err(ParseError {
-                    message: "invalid SQL statement".into(),
-                    pos: input.offset(i),
-                })
-            }
+            Err(_) => return Err(ParseError::new("invalid SQL statement", input, i)),
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::parse_statements;
+    use crate::expression::arithmetic::Expr;
+    use crate::identifier::Interner;
+    use crate::statement::Statement;
+    use crate::{
+        locate_error, parse_select_with_deadline, parse_statements, parse_statements_with_interner,
+        ParseLimits, Parser,
+    };
+    use assert_matches::assert_matches;
+    use std::time::{Duration, Instant};
 
     /// Validates that the [`parse_statements`] function
     /// handles statement terminators and errors.
@@ -144,6 +322,17 @@ mod test {
         );
         assert_eq!(format!("{}", got[1]), "SHOW DATABASES");
 
+        // Nor is a terminator in a string literal or regular expression
+        let got =
+            parse_statements("SELECT a FROM b WHERE c = 'x;y' AND d =~ /;/; SELECT e FROM /f;g/;")
+                .unwrap();
+        assert_eq!(got.len(), 2);
+        assert_eq!(
+            format!("{}", got[0]),
+            "SELECT a FROM b WHERE c = 'x;y' AND d =~ /;/"
+        );
+        assert_eq!(format!("{}", got[1]), "SELECT e FROM /f;g/");
+
         // Returns error for invalid statement
         let got = parse_statements("BAD SQL").unwrap_err();
         assert_eq!(format!("{}", got), "invalid SQL statement at pos 0");
@@ -152,4 +341,257 @@ mod test {
         let got = parse_statements("SHOW MEASUREMENTS;BAD SQL").unwrap_err();
         assert_eq!(format!("{}", got), "invalid SQL statement at pos 18");
     }
+
+    /// Validates that [`parse_statements`] reports the line and column of errors.
+    #[test]
+    fn test_parse_statements_error_location() {
+        let got = parse_statements("BAD SQL").unwrap_err();
+        assert_eq!((got.line(), got.column(), got.pos()), (1, 1, 0));
+
+        let input = "SELECT a\nFROM b\nWHERE c = 1 GROUP time(1m)";
+        let got = parse_statements(input).unwrap_err();
+        assert_eq!(got.message(), "invalid GROUP BY clause, expected BY");
+        assert_eq!((got.line(), got.column(), got.pos()), (3, 19, 34));
+        assert_eq!(&input[got.pos()..], "time(1m)");
+
+        // Columns are measured in characters
+        let input = "SHOW DATABASES;\nSHOW DATABASES;\nSELECT \"µs\" FROM cpu GROUP x";
+        let got = parse_statements(input).unwrap_err();
+        assert_eq!(
+            (got.line(), got.column(), got.pos()),
+            (3, 28, input.len() - 1)
+        );
+    }
+
+    #[test]
+    fn test_locate_error() {
+        let input = "SELECT a\nFROM b\n\nWHERE c = 1";
+        assert_eq!(locate_error(input, input), (1, 1, 0));
+        assert_eq!(locate_error(input, &input[7..]), (1, 8, 7));
+        assert_eq!(locate_error(input, &input[9..]), (2, 1, 9));
+        assert_eq!(locate_error(input, &input[16..]), (3, 1, 16));
+        assert_eq!(locate_error(input, &input[input.len()..]), (4, 12, 28));
+    }
+
+    /// Validates that [`Parser::with_limits`] applies the specified limits.
+    #[test]
+    fn test_parser_with_limits() {
+        let limits = ParseLimits {
+            max_nesting_depth: 2,
+            max_statements: 2,
+            ..Default::default()
+        };
+
+        let got = Parser::with_limits(limits)
+            .parse("SELECT a FROM b WHERE ((a = 1))")
+            .unwrap();
+        assert_eq!(format!("{}", got[0]), "SELECT a FROM b WHERE ((a = 1))");
+
+        // Fallible cases
+
+        let got = Parser::with_limits(limits)
+            .parse("SELECT a FROM b WHERE (((a = 1)))")
+            .unwrap_err();
+        assert_eq!(format!("{}", got), "condition nesting too deep at pos 25");
+    }
+
+    /// Validates that [`Parser::with_limits`] applies several tightened limits
+    /// at once.
+    #[test]
+    fn test_parser_with_custom_limits() {
+        let limits = ParseLimits {
+            max_nesting_depth: 1,
+            max_fields: 2,
+            ..Default::default()
+        };
+
+        Parser::with_limits(limits)
+            .parse("SELECT a, b FROM c WHERE (a = 1)")
+            .unwrap();
+
+        // Fallible cases
+
+        let got = Parser::with_limits(limits)
+            .parse("SELECT a, b, c FROM d")
+            .unwrap_err();
+        assert_eq!(
+            format!("{}", got),
+            "too many fields in SELECT statement at pos 7"
+        );
+
+        let got = Parser::with_limits(limits)
+            .parse("SELECT a, b FROM c WHERE ((a = 1))")
+            .unwrap_err();
+        assert_eq!(format!("{}", got), "condition nesting too deep at pos 27");
+
+        // The limits only apply to the call they were passed to
+        parse_statements("SELECT a, b, c FROM d WHERE ((a = 1))").unwrap();
+
+        let limits = ParseLimits {
+            max_subquery_depth: 1,
+            ..Default::default()
+        };
+
+        Parser::with_limits(limits)
+            .parse("SELECT a FROM (SELECT a FROM b)")
+            .unwrap();
+
+        let got = Parser::with_limits(limits)
+            .parse("SELECT a FROM (SELECT a FROM (SELECT a FROM b))")
+            .unwrap_err();
+        assert_eq!(format!("{}", got), "subquery nesting too deep at pos 30");
+
+        let limits = ParseLimits {
+            max_nesting_depth: 2,
+            max_number_length: 3,
+            ..Default::default()
+        };
+        let parser = Parser::with_limits(limits);
+
+        parser.parse("SELECT --a FROM b LIMIT 100").unwrap();
+        parser.parse("SELECT a FROM b WHERE a = 1.5").unwrap();
+
+        let got = parser.parse("SELECT ---a FROM b").unwrap_err();
+        assert_eq!(got.message(), "expression nesting too deep");
+
+        let got = parser.parse("SELECT a FROM b LIMIT 1000").unwrap_err();
+        assert_eq!(format!("{}", got), "numeric literal too long at pos 22");
+
+        let got = parser.parse("SELECT a FROM b WHERE a = -1.25").unwrap_err();
+        assert_eq!(format!("{}", got), "numeric literal too long at pos 27");
+    }
+
+    /// Validates that the nesting of unary operators is limited by the default
+    /// [`ParseLimits`], rather than overflowing the stack.
+    #[test]
+    fn test_parse_statements_nested_unary() {
+        let input = format!("SELECT {}a FROM b", "-".repeat(100_000));
+        let got = parse_statements(&input).unwrap_err();
+        assert_eq!(got.message(), "expression nesting too deep");
+    }
+
+    /// Validates that the limits of a [`Parser`] are restored if parsing panics,
+    /// such as when the panic is caught by a task runtime and the thread reused.
+    #[test]
+    fn test_with_limits_panic() {
+        let limits = ParseLimits {
+            max_fields: 1,
+            ..Default::default()
+        };
+
+        let got = std::panic::catch_unwind(|| {
+            crate::internal::with_limits(limits, || panic!("parser panicked"))
+        });
+        assert!(got.is_err());
+        assert_eq!(crate::internal::limits(), ParseLimits::default());
+    }
+
+    /// Validates that the default [`ParseLimits`] do not limit the number of
+    /// fields or the length of numeric literals.
+    #[test]
+    fn test_parse_statements_default_limits() {
+        let fields = vec!["a"; 1001].join(", ");
+        let got = parse_statements(&format!("SELECT {} FROM b", fields)).unwrap();
+        assert_matches!(&got[0], Statement::Select(s) if s.fields.len() == 1001);
+
+        let input = format!("SELECT a FROM b WHERE a = 0.{}1", "0".repeat(100));
+        parse_statements(&input).unwrap();
+    }
+
+    /// Validates that [`Parser::with_limits`] limits the number of statements.
+    #[test]
+    fn test_parse_statements_max_statements() {
+        let limits = ParseLimits {
+            max_statements: 2,
+            ..Default::default()
+        };
+
+        let got = Parser::with_limits(limits)
+            .parse("SHOW DATABASES; SHOW MEASUREMENTS")
+            .unwrap();
+        assert_eq!(got.len(), 2);
+
+        // Empty statements are not counted
+        let got = Parser::with_limits(limits)
+            .parse(";SHOW DATABASES;; ;SHOW MEASUREMENTS;;")
+            .unwrap();
+        assert_eq!(got.len(), 2);
+
+        // Fallible cases
+
+        let got = Parser::with_limits(limits)
+            .parse("SHOW DATABASES; SHOW MEASUREMENTS; SHOW DATABASES")
+            .unwrap_err();
+        assert_eq!(
+            format!("{}", got),
+            "too many statements in request at pos 35"
+        );
+
+        // The default limit
+        let input = "SHOW DATABASES;".repeat(101);
+        parse_statements(&input[..input.len() - 1]).unwrap_err();
+        parse_statements(&input[..input.len() - 16]).unwrap();
+    }
+
+    /// Validates that [`parse_statements_with_interner`] shares the names of
+    /// repeated identifiers, without otherwise affecting the result.
+    #[test]
+    fn test_parse_statements_with_interner() {
+        let input = "SELECT usage, usage * 2 FROM cpu WHERE host = 'a' OR host = 'b'";
+        let mut interner = Interner::new(1024);
+        let got =
+            parse_statements_with_interner(input, ParseLimits::default(), &mut interner).unwrap();
+        assert_eq!(got, parse_statements(input).unwrap());
+        assert_eq!(interner.len(), 3);
+
+        let stmt = match &got[0] {
+            Statement::Select(stmt) => stmt,
+            _ => panic!("expected SELECT statement"),
+        };
+        let name = |expr: &Expr| match expr {
+            Expr::VarRef { name, .. } => name.clone(),
+            Expr::Binary { lhs, .. } => match lhs.as_ref() {
+                Expr::VarRef { name, .. } => name.clone(),
+                _ => panic!("expected variable reference"),
+            },
+            _ => panic!("expected variable reference"),
+        };
+        assert!(name(&stmt.fields.first().expr).ptr_eq(&name(&stmt.fields.rest()[0].expr)));
+    }
+
+    /// Validates that [`parse_select_with_deadline`] aborts parsing once the
+    /// deadline has passed.
+    #[test]
+    fn test_parse_select_with_deadline() {
+        let input = format!(
+            "SELECT {} FROM cpu WHERE {}",
+            vec!["usage"; 1000].join(" + "),
+            vec!["host = 'a'"; 1000].join(" OR "),
+        );
+
+        let err = parse_select_with_deadline(&input, Instant::now()).unwrap_err();
+        assert_eq!(err.message, "parse timed out");
+
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let got = parse_select_with_deadline(&input, deadline).unwrap();
+        assert_eq!(
+            vec![Statement::Select(Box::new(got))],
+            parse_statements(&input).unwrap()
+        );
+
+        // The deadline only applies to the call it was passed to
+        parse_statements(&input).unwrap();
+
+        // Fallible cases
+
+        let err = parse_select_with_deadline("SHOW DATABASES", deadline).unwrap_err();
+        assert_eq!(format!("{}", err), "expected SELECT statement at pos 0");
+
+        let err =
+            parse_select_with_deadline("SELECT a FROM b; SELECT c FROM d", deadline).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "too many statements in request at pos 17"
+        );
+    }
 }
@@ -1,15 +1,15 @@
 //! Types and parsers for literals.
 
-use crate::internal::{map_fail, ParseResult};
+use crate::internal::{limits, map_fail, verify, ParseResult};
 use crate::keywords::keyword;
 use crate::string::{regex, single_quoted_string, Regex};
 use crate::{impl_tuple_clause, write_escaped};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::{char, digit0, digit1, multispace0};
+use nom::character::complete::{char, digit0, digit1, multispace0, one_of};
 use nom::combinator::{map, opt, recognize, value};
 use nom::multi::fold_many1;
-use nom::sequence::{pair, preceded, separated_pair};
+use nom::sequence::{pair, preceded, separated_pair, tuple};
 use std::fmt;
 use std::fmt::{Display, Formatter, Write};
 
@@ -28,8 +28,12 @@ const NANOS_PER_DAY: i64 = 24 * NANOS_PER_HOUR;
 /// Number of nanoseconds in a week.
 const NANOS_PER_WEEK: i64 = 7 * NANOS_PER_DAY;
 
+/// Error message returned when duration arithmetic overflows 64-bit nanoseconds.
+pub(crate) const DURATION_OVERFLOW: &str = "duration arithmetic overflow";
+
 /// Primitive InfluxQL literal values, such as strings and regular expressions.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Literal {
     /// Unsigned integer literal.
     Unsigned(u64),
@@ -90,7 +94,7 @@ impl Display for Literal {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Unsigned(v) => write!(f, "{}", v),
-            Self::Float(v) => write!(f, "{}", v),
+            Self::Float(v) => write_float(f, *v),
             Self::String(v) => {
                 f.write_char('\'')?;
                 write_escaped!(f, v, '\n' => "\\n", '\\' => "\\\\", '\'' => "\\'", '"' => "\\\"");
@@ -111,7 +115,11 @@ impl Display for Literal {
 /// INTEGER ::= [0-9]+
 /// ```
 fn integer(i: &str) -> ParseResult<&str, i64> {
-    map_fail("unable to parse integer", digit1, &str::parse)(i)
+    map_fail(
+        "unable to parse integer",
+        number_literal(digit1),
+        &str::parse,
+    )(i)
 }
 
 /// Parse an unsigned InfluxQL integer.
@@ -122,7 +130,11 @@ fn integer(i: &str) -> ParseResult<&str, i64> {
 /// INTEGER ::= [0-9]+
 /// ```
 pub(crate) fn unsigned_integer(i: &str) -> ParseResult<&str, u64> {
-    map_fail("unable to parse unsigned integer", digit1, &str::parse)(i)
+    map_fail(
+        "unable to parse unsigned integer",
+        number_literal(digit1),
+        &str::parse,
+    )(i)
 }
 
 /// Parse an unsigned InfluxQL floating point number.
@@ -130,19 +142,49 @@ pub(crate) fn unsigned_integer(i: &str) -> ParseResult<&str, u64> {
 /// InfluxQL defines a floating point number as follows
 ///
 /// ```text
-/// float   ::= INTEGER "." INTEGER
-/// INTEGER ::= [0-9]+
+/// float    ::= INTEGER? "." INTEGER exponent? | INTEGER exponent
+/// exponent ::= [eE] [+-]? INTEGER
+/// INTEGER  ::= [0-9]+
 /// ```
+///
+/// A number too large to be represented by an `f64` is an error, rather
+/// than infinity.
 fn float(i: &str) -> ParseResult<&str, f64> {
-    map_fail(
-        "unable to parse float",
-        recognize(separated_pair(digit0, tag("."), digit1)),
-        &str::parse,
+    verify(
+        "float literal out of range",
+        map_fail(
+            "unable to parse float",
+            number_literal(alt((
+                recognize(pair(
+                    separated_pair(digit0, tag("."), digit1),
+                    opt(exponent),
+                )),
+                recognize(pair(digit1, exponent)),
+            ))),
+            &str::parse,
+        ),
+        |v: &f64| v.is_finite(),
     )(i)
 }
 
+/// Applies `f` to recognise a numeric literal, failing if it is longer than
+/// [`crate::ParseLimits::max_number_length`].
+fn number_literal<'a>(
+    f: impl FnMut(&'a str) -> ParseResult<&'a str, &'a str>,
+) -> impl FnMut(&'a str) -> ParseResult<&'a str, &'a str> {
+    verify("numeric literal too long", f, |v: &str| {
+        v.len() <= limits().max_number_length
+    })
+}
+
+/// Parse the exponent of a floating point number.
+fn exponent(i: &str) -> ParseResult<&str, &str> {
+    recognize(tuple((one_of("eE"), opt(one_of("+-")), digit1)))(i)
+}
+
 /// Represents any signed number.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Number {
     /// Contains a 64-bit integer.
     Integer(i64),
@@ -154,11 +196,20 @@ impl Display for Number {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Integer(v) => fmt::Display::fmt(v, f),
-            Self::Float(v) => fmt::Display::fmt(v, f),
+            Self::Float(v) => write_float(f, *v),
         }
     }
 }
 
+/// Writes `v` such that it is parsed as the same float, rather than an
+/// integer, when the output is parsed again.
+///
+/// The Debug representation always includes a fractional part or exponent,
+/// such as `-0.0` or `1e300`, unlike the Display representation.
+fn write_float(f: &mut Formatter<'_>, v: f64) -> fmt::Result {
+    fmt::Debug::fmt(&v, f)
+}
+
 impl From<f64> for Number {
     fn from(v: f64) -> Self {
         Self::Float(v)
@@ -172,16 +223,20 @@ impl From<i64> for Number {
 }
 
 /// Parse a signed [`Number`].
+///
+/// The sign may be separated from the number by whitespace, such as `- 5`.
+/// A negative zero float, `-0.0`, is preserved.
 pub(crate) fn number(i: &str) -> ParseResult<&str, Number> {
     let (remaining, sign) = opt(alt((char('-'), char('+'))))(i)?;
+    let negative = sign == Some('-');
     preceded(
         multispace0,
         alt((
-            map(float, move |v| {
-                Number::Float(v * if let Some('-') = sign { -1.0 } else { 1.0 })
-            }),
-            map(integer, move |v| {
-                Number::Integer(v * if let Some('-') = sign { -1 } else { 1 })
+            map(float, move |v| Number::Float(if negative { -v } else { v })),
+            // Negate before converting to an i64, to accept i64::MIN
+            map_fail("unable to parse integer", unsigned_integer, move |v| {
+                let v = i128::from(v);
+                i64::try_from(if negative { -v } else { v }).map(Number::Integer)
             }),
         )),
     )(remaining)
@@ -206,10 +261,33 @@ enum DurationUnit {
 
 /// Represents an InfluxQL duration in nanoseconds.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Duration(pub(crate) i64);
 
 impl_tuple_clause!(Duration, i64);
 
+impl Duration {
+    /// Returns the sum of `self` and `rhs`, or [`None`] if the result overflows.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+
+    /// Returns the difference of `self` and `rhs`, or [`None`] if the result overflows.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+
+    /// Returns `self` multiplied by `rhs`, or [`None`] if the result overflows.
+    pub fn checked_mul(self, rhs: i64) -> Option<Self> {
+        self.0.checked_mul(rhs).map(Self)
+    }
+
+    /// Returns `self` divided by `rhs`, or [`None`] if `rhs` is zero or the result overflows.
+    pub fn checked_div(self, rhs: i64) -> Option<Self> {
+        self.0.checked_div(rhs).map(Self)
+    }
+}
+
 static DIVISORS: [(i64, &str); 8] = [
     (NANOS_PER_WEEK, "w"),
     (NANOS_PER_DAY, "d"),
@@ -245,7 +323,8 @@ impl Display for Duration {
 fn single_duration(i: &str) -> ParseResult<&str, i64> {
     use DurationUnit::*;
 
-    map(
+    map_fail(
+        DURATION_OVERFLOW,
         pair(
             integer,
             alt((
@@ -260,24 +339,34 @@ fn single_duration(i: &str) -> ParseResult<&str, i64> {
                 value(Week, tag("w")),          // weeks
             )),
         ),
-        |(v, unit)| match unit {
-            Nanosecond => v,
-            Microsecond => v * NANOS_PER_MICRO,
-            Millisecond => v * NANOS_PER_MILLI,
-            Second => v * NANOS_PER_SEC,
-            Minute => v * NANOS_PER_MIN,
-            Hour => v * NANOS_PER_HOUR,
-            Day => v * NANOS_PER_DAY,
-            Week => v * NANOS_PER_WEEK,
+        |(v, unit)| {
+            v.checked_mul(match unit {
+                Nanosecond => 1,
+                Microsecond => NANOS_PER_MICRO,
+                Millisecond => NANOS_PER_MILLI,
+                Second => NANOS_PER_SEC,
+                Minute => NANOS_PER_MIN,
+                Hour => NANOS_PER_HOUR,
+                Day => NANOS_PER_DAY,
+                Week => NANOS_PER_WEEK,
+            })
+            .ok_or(())
         },
     )(i)
 }
 
 /// Parse the input for an InfluxQL duration.
+///
+/// The sum of the fragments must not overflow 64-bit nanoseconds.
 pub(crate) fn duration(i: &str) -> ParseResult<&str, Duration> {
-    map(
-        fold_many1(single_duration, || 0, |acc, fragment| acc + fragment),
-        Duration,
+    map_fail(
+        DURATION_OVERFLOW,
+        fold_many1(
+            single_duration,
+            || Some(0_i64),
+            |acc, fragment| acc.and_then(|acc| acc.checked_add(fragment)),
+        ),
+        |v| v.map(Duration).ok_or(()),
     )(i)
 }
 
@@ -308,6 +397,7 @@ pub(crate) fn literal_regex(i: &str) -> ParseResult<&str, Literal> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::assert_expect_error;
     use assert_matches::assert_matches;
 
     #[test]
@@ -318,6 +408,12 @@ mod test {
         let (_, got) = literal_no_regex("42.69").unwrap();
         assert_matches!(got, Literal::Float(v) if v == 42.69);
 
+        let (_, got) = literal_no_regex("1.5e10").unwrap();
+        assert_matches!(got, Literal::Float(v) if v == 1.5e10);
+
+        let (_, got) = literal_no_regex("1e-3").unwrap();
+        assert_matches!(got, Literal::Float(v) if v == 0.001);
+
         let (_, got) = literal_no_regex("'quick draw'").unwrap();
         assert_matches!(got, Literal::String(v) if v == "quick draw");
 
@@ -334,6 +430,22 @@ mod test {
         literal_no_regex("/foo/").unwrap_err();
     }
 
+    #[test]
+    fn test_literal_float_display() {
+        assert_eq!(Literal::Float(1.5e10).to_string(), "15000000000.0");
+        assert_eq!(Literal::Float(1e300).to_string(), "1e300");
+        assert_eq!(Literal::Float(1e-300).to_string(), "1e-300");
+        assert_eq!(Literal::Float(0.0).to_string(), "0.0");
+
+        // Printed floats are parsed as the same float
+        for v in ["1.5e10", "1e300", "1e-300", "0.0", "42.69"] {
+            let (_, want) = literal_no_regex(v).unwrap();
+            let (_, got) = literal_no_regex(&want.to_string()).unwrap();
+            assert_matches!((&want, &got), (Literal::Float(_), Literal::Float(_)));
+            assert_eq!(got, want, "{}", v);
+        }
+    }
+
     #[test]
     fn test_literal() {
         let (_, got) = literal("/^(match|this)$/").unwrap();
@@ -390,6 +502,42 @@ mod test {
 
         // missing decimal
         float("41").unwrap_err();
+
+        // missing exponent digits
+        let (rem, got) = float("41.5e").unwrap();
+        assert_eq!(got, 41.5);
+        assert_eq!(rem, "e");
+        float("41e").unwrap_err();
+        float("41e+").unwrap_err();
+
+        // overflows f64
+        assert_expect_error!(float("1e309"), "float literal out of range");
+        assert_expect_error!(float("1.5E+400"), "float literal out of range");
+    }
+
+    #[test]
+    fn test_float_exponent() {
+        let (_, got) = float("1.5e10").unwrap();
+        assert_eq!(got, 1.5e10);
+
+        let (_, got) = float("1e3").unwrap();
+        assert_eq!(got, 1000.0);
+
+        let (_, got) = float("2.5E+3").unwrap();
+        assert_eq!(got, 2500.0);
+
+        let (_, got) = float(".5e-3").unwrap();
+        assert_eq!(got, 0.0005);
+
+        let (_, got) = float("123456789e-9").unwrap();
+        assert_eq!(got, 0.123456789);
+
+        // The value is rounded to the nearest f64
+        let (_, got) = float("1.7976931348623157e308").unwrap();
+        assert_eq!(got, f64::MAX);
+
+        let (_, got) = float("4.9e-324").unwrap();
+        assert_eq!(got, f64::MIN_POSITIVE * f64::EPSILON);
     }
 
     #[test]
@@ -444,6 +592,41 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_duration_overflow() {
+        // 15250w is the largest whole number of weeks that fits in i64 nanoseconds
+        let (_, got) = duration("15250w").unwrap();
+        assert_eq!(got, Duration(15250 * NANOS_PER_WEEK));
+
+        // Summing fragments below the boundary
+        let (_, got) = duration("52w52w").unwrap();
+        assert_eq!(got, Duration(104 * NANOS_PER_WEEK));
+
+        // Fallible cases
+
+        // a single fragment that overflows
+        assert_expect_error!(duration("15251w"), "duration arithmetic overflow");
+
+        // the sum of the fragments overflows
+        assert_expect_error!(duration("15250w15250w"), "duration arithmetic overflow");
+    }
+
+    #[test]
+    fn test_duration_checked_arithmetic() {
+        let d = Duration(52 * NANOS_PER_WEEK);
+        assert_eq!(d.checked_add(d), Some(Duration(104 * NANOS_PER_WEEK)));
+        assert_eq!(d.checked_sub(d), Some(Duration(0)));
+        assert_eq!(d.checked_mul(2), Some(Duration(104 * NANOS_PER_WEEK)));
+        assert_eq!(d.checked_div(4), Some(Duration(13 * NANOS_PER_WEEK)));
+
+        // Fallible cases
+
+        assert_eq!(Duration(i64::MAX).checked_add(Duration(1)), None);
+        assert_eq!(Duration(i64::MIN).checked_sub(Duration(1)), None);
+        assert_eq!(Duration(15250 * NANOS_PER_WEEK).checked_mul(2), None);
+        assert_eq!(d.checked_div(0), None);
+    }
+
     #[test]
     fn test_display_duration() {
         let (_, d) = duration("3w2h15ms").unwrap();
@@ -490,6 +673,12 @@ mod test {
         let (_, got) = number("+ 33.1").unwrap();
         assert_matches!(got, Number::Float(v) if v == 33.1);
 
+        let (_, got) = number("-1.5e10").unwrap();
+        assert_matches!(got, Number::Float(v) if v == -1.5e10);
+
+        let (_, got) = number("2e-3").unwrap();
+        assert_matches!(got, Number::Float(v) if v == 0.002);
+
         // Test integers
         let (_, got) = number("42").unwrap();
         assert_matches!(got, Number::Integer(v) if v == 42);
@@ -505,5 +694,39 @@ mod test {
 
         let (_, got) = number("+ 501").unwrap();
         assert_matches!(got, Number::Integer(v) if v == 501);
+
+        let (_, got) = number("-9223372036854775808").unwrap();
+        assert_matches!(got, Number::Integer(v) if v == i64::MIN);
+
+        // Negative zero is preserved
+        let (_, got) = number("-0.0").unwrap();
+        assert_matches!(got, Number::Float(v) if v == 0.0 && v.is_sign_negative());
+
+        let (_, got) = number("- 0.0").unwrap();
+        assert_matches!(got, Number::Float(v) if v == 0.0 && v.is_sign_negative());
+
+        let (_, got) = number("-0").unwrap();
+        assert_matches!(got, Number::Integer(0));
+
+        // Fallible cases
+
+        assert_expect_error!(number("9223372036854775808"), "unable to parse integer");
+        assert_expect_error!(number("-9223372036854775809"), "unable to parse integer");
+    }
+
+    #[test]
+    fn test_number_display() {
+        assert_eq!(Number::Integer(-5).to_string(), "-5");
+        assert_eq!(Number::Float(-18.9).to_string(), "-18.9");
+
+        // Floats are distinguished from integers
+        assert_eq!(Number::Float(0.0).to_string(), "0.0");
+        assert_eq!(Number::Float(-0.0).to_string(), "-0.0");
+        assert_eq!(Number::Float(1e300).to_string(), "1e300");
+
+        for v in ["-5", "-18.9", "0.0", "-0.0", "1e300", "1.5e-10"] {
+            let (_, got) = number(v).unwrap();
+            assert_eq!(got.to_string(), v);
+        }
     }
 }
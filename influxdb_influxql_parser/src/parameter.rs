@@ -26,6 +26,7 @@ fn unquoted_parameter(i: &str) -> ParseResult<&str, &str> {
 
 /// A type that represents an InfluxQL bind parameter.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BindParameter(pub(crate) String);
 
 impl_tuple_clause!(BindParameter, String);
@@ -0,0 +1,476 @@
+//! Validation of a [`SelectStatement`] against the schemas of the tables it
+//! selects from.
+
+use crate::expression::arithmetic::{Expr, VarRefDataType};
+use crate::expression::conditional::{ConditionalExpression, ConditionalOperator};
+use crate::literal::Literal;
+use crate::select::{Dimension, SelectStatement};
+use data_types::{ColumnSummary, InfluxDbType, Statistics, TableSummary};
+use std::fmt::{self, Display, Formatter};
+
+/// An inconsistency between a [`SelectStatement`] and the schema of the
+/// tables it selects from, as reported by [`validate_against_schema`] and
+/// [`validate_unambiguous_columns`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    /// The statement references a column that does not exist in the table.
+    UnknownColumn(String),
+
+    /// The statement compares a column with a value of an incompatible type.
+    TypeMismatch {
+        /// The name of the column.
+        column: String,
+        /// The type of the column.
+        column_type: VarRefDataType,
+        /// The type of the value the column is compared with.
+        value_type: VarRefDataType,
+    },
+
+    /// The statement references an unqualified column that exists in more
+    /// than one of the tables it selects from.
+    AmbiguousColumn {
+        /// The name of the column.
+        name: String,
+        /// The names of the tables containing the column.
+        sources: Vec<String>,
+    },
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownColumn(name) => write!(f, "unknown column {}", name),
+            Self::TypeMismatch {
+                column,
+                column_type,
+                value_type,
+            } => write!(
+                f,
+                "cannot compare {} column {} with a {} value",
+                column_type, column, value_type
+            ),
+            Self::AmbiguousColumn { name, sources } => write!(
+                f,
+                "column {} is ambiguous between {}",
+                name,
+                sources.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// Validates that every column referenced by the field list, `WHERE` and
+/// `GROUP BY` clauses of `stmt` exists in `schema`, and that columns in the
+/// `WHERE` clause are only compared with literals of a compatible type.
+///
+/// All errors are returned, rather than only the first. Wildcards, regular
+/// expressions selecting fields or tags, `time` and subqueries are not
+/// validated.
+pub fn validate_against_schema(
+    stmt: &SelectStatement,
+    schema: &TableSummary,
+) -> Result<(), Vec<SchemaError>> {
+    let mut validator = SchemaValidator {
+        schema,
+        errors: vec![],
+    };
+
+    for field in stmt.fields.iter() {
+        validator.expr(&field.expr);
+    }
+
+    if let Some(condition) = &stmt.condition {
+        validator.conditional(condition);
+    }
+
+    for dim in stmt.group_by.iter().flatten() {
+        if let Dimension::Tag(name) = dim {
+            validator.column(name);
+        }
+    }
+
+    if validator.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(validator.errors)
+    }
+}
+
+/// Validates that every unqualified column referenced by the field list,
+/// `WHERE` and `GROUP BY` clauses of `stmt` resolves to at most one of the
+/// tables in `sources`, given as pairs of measurement name and schema.
+///
+/// A reference is qualified when its name is `<measurement>.<column>`, as in
+/// `"cpu.usage"`, where `<measurement>` is one of `sources` containing
+/// `<column>` and the full name is not itself a column of any source. Such
+/// references, and all references of a statement with a single source, are
+/// never ambiguous. Columns that exist in none of the sources are left to
+/// [`validate_against_schema`].
+pub fn validate_unambiguous_columns(
+    stmt: &SelectStatement,
+    sources: &[(&str, &TableSummary)],
+) -> Result<(), Vec<SchemaError>> {
+    if sources.len() < 2 {
+        return Ok(());
+    }
+
+    let mut validator = AmbiguityValidator {
+        sources,
+        errors: vec![],
+    };
+
+    for field in stmt.fields.iter() {
+        validator.expr(&field.expr);
+    }
+
+    if let Some(condition) = &stmt.condition {
+        validator.conditional(condition);
+    }
+
+    for dim in stmt.group_by.iter().flatten() {
+        if let Dimension::Tag(name) = dim {
+            validator.column(name);
+        }
+    }
+
+    if validator.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(validator.errors)
+    }
+}
+
+/// Accumulates the [`SchemaError::AmbiguousColumn`]s found by
+/// [`validate_unambiguous_columns`].
+struct AmbiguityValidator<'a> {
+    sources: &'a [(&'a str, &'a TableSummary)],
+    errors: Vec<SchemaError>,
+}
+
+impl<'a> AmbiguityValidator<'a> {
+    /// Returns the names of the sources containing the column `name`.
+    fn sources_of(&self, name: &str) -> Vec<String> {
+        self.sources
+            .iter()
+            .filter(|(_, schema)| schema.column(name).is_some())
+            .map(|(source, _)| source.to_string())
+            .collect()
+    }
+
+    /// Returns `true` if `name` is a column qualified by the measurement
+    /// containing it.
+    fn is_qualified(&self, name: &str) -> bool {
+        self.sources.iter().any(|(source, schema)| {
+            name.strip_prefix(source)
+                .and_then(|rest| rest.strip_prefix('.'))
+                .map_or(false, |column| schema.column(column).is_some())
+        })
+    }
+
+    fn column(&mut self, name: &str) {
+        if name.eq_ignore_ascii_case("time") {
+            return;
+        }
+
+        let sources = self.sources_of(name);
+        if sources.is_empty() && self.is_qualified(name) {
+            return;
+        }
+
+        if sources.len() > 1 {
+            let err = SchemaError::AmbiguousColumn {
+                name: name.to_owned(),
+                sources,
+            };
+            if !self.errors.contains(&err) {
+                self.errors.push(err);
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::VarRef { name, .. } | Expr::Distinct(name) => self.column(name),
+            Expr::Call { args, .. } => args.iter().for_each(|arg| self.expr(arg)),
+            Expr::UnaryOp(_, expr) | Expr::Nested(expr) => self.expr(expr),
+            Expr::Binary { lhs, rhs, .. } => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+            Expr::BindParameter(_)
+            | Expr::Literal(_)
+            | Expr::Wildcard(_)
+            | Expr::QualifiedWildcard { .. } => {}
+        }
+    }
+
+    fn conditional(&mut self, cond: &ConditionalExpression) {
+        match cond {
+            ConditionalExpression::Expr(expr) => self.expr(expr),
+            ConditionalExpression::Grouped(cond) => self.conditional(cond),
+            ConditionalExpression::Binary { lhs, rhs, .. } => {
+                self.conditional(lhs);
+                self.conditional(rhs);
+            }
+        }
+    }
+}
+
+/// Accumulates the [`SchemaError`]s found by [`validate_against_schema`].
+struct SchemaValidator<'a> {
+    schema: &'a TableSummary,
+    errors: Vec<SchemaError>,
+}
+
+impl<'a> SchemaValidator<'a> {
+    fn push(&mut self, err: SchemaError) {
+        if !self.errors.contains(&err) {
+            self.errors.push(err);
+        }
+    }
+
+    /// Returns the column `name`, recording an error if it does not exist.
+    fn column(&mut self, name: &str) -> Option<&'a ColumnSummary> {
+        if name.eq_ignore_ascii_case("time") {
+            return None;
+        }
+
+        let column = self.schema.column(name);
+        if column.is_none() {
+            self.push(SchemaError::UnknownColumn(name.to_owned()));
+        }
+        column
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::VarRef { name, .. } | Expr::Distinct(name) => {
+                self.column(name);
+            }
+            Expr::Call { args, .. } => args.iter().for_each(|arg| self.expr(arg)),
+            Expr::UnaryOp(_, expr) | Expr::Nested(expr) => self.expr(expr),
+            Expr::Binary { lhs, rhs, .. } => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+            Expr::BindParameter(_)
+            | Expr::Literal(_)
+            | Expr::Wildcard(_)
+            | Expr::QualifiedWildcard { .. } => {}
+        }
+    }
+
+    fn conditional(&mut self, cond: &ConditionalExpression) {
+        match cond {
+            ConditionalExpression::Expr(expr) => self.expr(expr),
+            ConditionalExpression::Grouped(cond) => self.conditional(cond),
+            ConditionalExpression::Binary { lhs, op, rhs } => {
+                if !matches!(op, ConditionalOperator::And | ConditionalOperator::Or) {
+                    self.comparison(lhs, rhs);
+                    self.comparison(rhs, lhs);
+                }
+                self.conditional(lhs);
+                self.conditional(rhs);
+            }
+        }
+    }
+
+    /// Records a [`SchemaError::TypeMismatch`] if `lhs` is a variable
+    /// reference to a column that cannot be compared with the literal `rhs`.
+    fn comparison(&mut self, lhs: &ConditionalExpression, rhs: &ConditionalExpression) {
+        let (name, literal) = match (lhs, rhs) {
+            (ConditionalExpression::Expr(lhs), ConditionalExpression::Expr(rhs)) => {
+                match (lhs.as_ref(), rhs.as_ref()) {
+                    (Expr::VarRef { name, .. }, Expr::Literal(literal)) => (name, literal),
+                    _ => return,
+                }
+            }
+            _ => return,
+        };
+
+        let value_type = match literal {
+            Literal::Unsigned(_) => VarRefDataType::Integer,
+            Literal::Float(_) => VarRefDataType::Float,
+            Literal::String(_) | Literal::Regex(_) => VarRefDataType::String,
+            Literal::Boolean(_) => VarRefDataType::Boolean,
+            Literal::Duration(_) => return,
+        };
+
+        let column = match self.schema.column(name) {
+            Some(column) if !name.eq_ignore_ascii_case("time") => column,
+            _ => return,
+        };
+        let column_type = column_data_type(column);
+
+        let compatible = matches!(
+            (column_type, value_type),
+            (
+                VarRefDataType::Tag | VarRefDataType::String,
+                VarRefDataType::String
+            ) | (
+                VarRefDataType::Integer | VarRefDataType::Float,
+                VarRefDataType::Integer | VarRefDataType::Float
+            ) | (VarRefDataType::Boolean, VarRefDataType::Boolean)
+        );
+        if !compatible {
+            self.push(SchemaError::TypeMismatch {
+                column: column.name.clone(),
+                column_type,
+                value_type,
+            });
+        }
+    }
+}
+
+/// Returns the data type of the values of `column`.
+fn column_data_type(column: &ColumnSummary) -> VarRefDataType {
+    match (column.influxdb_type, &column.stats) {
+        (InfluxDbType::Tag, _) => VarRefDataType::Tag,
+        (_, Statistics::I64(_) | Statistics::U64(_)) => VarRefDataType::Integer,
+        (_, Statistics::F64(_)) => VarRefDataType::Float,
+        (_, Statistics::Bool(_)) => VarRefDataType::Boolean,
+        (_, Statistics::String(_)) => VarRefDataType::String,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::select::select_statement;
+    use data_types::StatValues;
+
+    #[test]
+    fn test_validate_against_schema() {
+        let column = |name: &str, influxdb_type, stats| ColumnSummary {
+            name: name.to_string(),
+            influxdb_type,
+            stats,
+        };
+        let schema = TableSummary {
+            columns: vec![
+                column(
+                    "host",
+                    InfluxDbType::Tag,
+                    Statistics::String(StatValues::default()),
+                ),
+                column(
+                    "usage",
+                    InfluxDbType::Field,
+                    Statistics::F64(StatValues::default()),
+                ),
+                column(
+                    "status",
+                    InfluxDbType::Field,
+                    Statistics::String(StatValues::default()),
+                ),
+                column(
+                    "time",
+                    InfluxDbType::Timestamp,
+                    Statistics::I64(StatValues::default()),
+                ),
+            ],
+        };
+        let validate = |q: &str| {
+            let (_, got) = select_statement(q).unwrap();
+            validate_against_schema(&got, &schema)
+        };
+
+        // Valid statements
+        validate("SELECT usage, status FROM cpu").unwrap();
+        validate(
+            "SELECT mean(usage) FROM cpu WHERE host = 'a' AND usage > 5 AND time > now() - 1h \
+             GROUP BY time(1m), host",
+        )
+        .unwrap();
+        validate("SELECT * FROM cpu WHERE host =~ /a/ GROUP BY *").unwrap();
+        validate("SELECT usage FROM cpu WHERE 5 < usage AND status != 'ok'").unwrap();
+
+        // Unknown fields
+        assert_eq!(
+            validate("SELECT usage, idle FROM cpu").unwrap_err(),
+            vec![SchemaError::UnknownColumn("idle".to_string())]
+        );
+
+        // Type-mismatched comparisons
+        assert_eq!(
+            validate("SELECT usage FROM cpu WHERE usage = 'high'").unwrap_err(),
+            vec![SchemaError::TypeMismatch {
+                column: "usage".to_string(),
+                column_type: VarRefDataType::Float,
+                value_type: VarRefDataType::String,
+            }]
+        );
+        assert_eq!(
+            validate("SELECT usage FROM cpu WHERE 5 = host").unwrap_err(),
+            vec![SchemaError::TypeMismatch {
+                column: "host".to_string(),
+                column_type: VarRefDataType::Tag,
+                value_type: VarRefDataType::Integer,
+            }]
+        );
+
+        // All errors are collected
+        assert_eq!(
+            validate(
+                "SELECT mean(idle) FROM cpu WHERE (region = 'us' OR status = true) \
+                 GROUP BY dc"
+            )
+            .unwrap_err(),
+            vec![
+                SchemaError::UnknownColumn("idle".to_string()),
+                SchemaError::UnknownColumn("region".to_string()),
+                SchemaError::TypeMismatch {
+                    column: "status".to_string(),
+                    column_type: VarRefDataType::String,
+                    value_type: VarRefDataType::Boolean,
+                },
+                SchemaError::UnknownColumn("dc".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_unambiguous_columns() {
+        let schema = |columns: &[&str]| TableSummary {
+            columns: columns
+                .iter()
+                .map(|name| ColumnSummary {
+                    name: name.to_string(),
+                    influxdb_type: InfluxDbType::Field,
+                    stats: Statistics::F64(StatValues::default()),
+                })
+                .collect(),
+        };
+        let cpu = schema(&["usage", "idle", "time"]);
+        let mem = schema(&["usage", "free", "time"]);
+        let validate = |q: &str, sources: &[(&str, &TableSummary)]| {
+            let (_, got) = select_statement(q).unwrap();
+            validate_unambiguous_columns(&got, sources)
+        };
+
+        // A column present in both sources is ambiguous
+        assert_eq!(
+            validate(
+                "SELECT mean(usage), idle FROM cpu, mem WHERE usage > 5 AND time > now() - 1h",
+                &[("cpu", &cpu), ("mem", &mem)]
+            )
+            .unwrap_err(),
+            vec![SchemaError::AmbiguousColumn {
+                name: "usage".to_string(),
+                sources: vec!["cpu".to_string(), "mem".to_string()],
+            }]
+        );
+
+        // Qualifying the column with its measurement disambiguates it
+        validate(
+            r#"SELECT mean("cpu.usage"), idle, free FROM cpu, mem WHERE "mem.usage" > 5"#,
+            &[("cpu", &cpu), ("mem", &mem)],
+        )
+        .unwrap();
+
+        // A single source is never ambiguous
+        validate("SELECT usage FROM cpu", &[("cpu", &cpu)]).unwrap();
+    }
+}
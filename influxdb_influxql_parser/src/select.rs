@@ -4,36 +4,53 @@
 
 use crate::common::{
     limit_clause, offset_clause, order_by_clause, qualified_measurement_name, where_clause,
-    LimitClause, OffsetClause, OneOrMore, OrderByClause, Parser, QualifiedMeasurementName,
-    WhereClause,
+    LimitClause, MeasurementName, OffsetClause, OneOrMore, OrderByClause, Parser,
+    QualifiedMeasurementName, WhereClause,
 };
 use crate::expression::arithmetic::Expr::Wildcard;
 use crate::expression::arithmetic::{
-    arithmetic, call_expression, var_ref, ArithmeticParsers, Expr, WildcardType,
+    arithmetic, call_expression, var_ref, ArithmeticParsers, BinaryOperator, Expr, UnaryOperator,
+    VarRefDataType, WildcardType,
+};
+use crate::expression::conditional::{
+    is_valid_now_call, ConditionalExpression, ConditionalOperator,
 };
-use crate::expression::conditional::is_valid_now_call;
 use crate::identifier::{identifier, Identifier};
-use crate::internal::{expect, verify, ParseResult};
+use crate::internal::{
+    expect, limits, map_fail, subquery, verify, Error as InternalError, ParseError, ParseResult,
+};
 use crate::keywords::keyword;
 use crate::literal::{duration, literal, number, unsigned_integer, Literal, Number};
-use crate::parameter::parameter;
+use crate::parameter::{parameter, BindParameter};
 use crate::select::MeasurementSelection::Subquery;
 use crate::string::{regex, single_quoted_string, Regex};
+use crate::visit::{Recursion, Visitable, Visitor, VisitorResult};
+use crate::visit_mut::{
+    Recursion as MutRecursion, VisitableMut, VisitorMut, VisitorResult as MutVisitorResult,
+};
 use crate::{impl_tuple_clause, write_escaped};
+use chrono_tz::Tz;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::{char, multispace0, multispace1};
 use nom::combinator::{map, opt, value};
-use nom::sequence::{delimited, pair, preceded, tuple};
+use nom::sequence::{delimited, pair, preceded, terminated, tuple};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::fmt::{Display, Formatter, Write};
+use std::ops::Bound;
+use std::str::FromStr;
 
 /// Represents a `SELECT` statement.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectStatement {
     /// Expressions returned by the selection.
     pub fields: FieldList,
 
+    /// The measurement the results of the selection are written to.
+    pub into: Option<IntoClause>,
+
     /// A list of measurements or subqueries used as the source data for the selection.
     pub from: FromMeasurementClause,
 
@@ -43,10 +60,11 @@ pub struct SelectStatement {
     /// Expressions used for grouping the selection.
     pub group_by: Option<GroupByClause>,
 
-    /// The [fill clause] specifies the fill behaviour for the selection. If the value is [`None`],
-    /// it is the same behavior as `fill(none)`.
+    /// The [fill clause] specifies the fill behaviour for the selection. The value is [`None`]
+    /// only when the statement has no `FILL` clause, in which case the default, `fill(null)`,
+    /// applies. An explicit `FILL(NONE)` is `Some(FillClause::None)`.
     ///
-    /// [fill]: https://docs.influxdata.com/influxdb/v1.8/query_language/explore-data/#group-by-time-intervals-and-fill
+    /// [fill clause]: https://docs.influxdata.com/influxdb/v1.8/query_language/explore-data/#group-by-time-intervals-and-fill
     pub fill: Option<FillClause>,
 
     /// Configures the ordering of the selection by time.
@@ -72,7 +90,13 @@ pub struct SelectStatement {
 
 impl Display for SelectStatement {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "SELECT {} {}", self.fields, self.from)?;
+        write!(f, "SELECT {}", self.fields)?;
+
+        if let Some(into) = &self.into {
+            write!(f, " {}", into)?;
+        }
+
+        write!(f, " {}", self.from)?;
 
         if let Some(where_clause) = &self.condition {
             write!(f, " {}", where_clause)?;
@@ -114,666 +138,3945 @@ impl Display for SelectStatement {
     }
 }
 
-pub(crate) fn select_statement(i: &str) -> ParseResult<&str, SelectStatement> {
-    let (
-        remaining,
-        (
-            _, // SELECT
-            _, // whitespace
-            fields,
-            from,
-            condition,
-            group_by,
-            fill,
-            order_by,
-            limit,
-            offset,
-            series_limit,
-            series_offset,
-            timezone,
-        ),
-    ) = tuple((
-        keyword("SELECT"),
-        multispace0,
-        field_list,
-        preceded(multispace0, from_clause),
-        opt(preceded(multispace0, where_clause)),
-        opt(preceded(multispace0, group_by_clause)),
-        opt(preceded(multispace0, fill_clause)),
-        opt(preceded(multispace0, order_by_clause)),
-        opt(preceded(multispace0, limit_clause)),
-        opt(preceded(multispace0, offset_clause)),
-        opt(preceded(multispace0, slimit_clause)),
-        opt(preceded(multispace0, soffset_clause)),
-        opt(preceded(multispace0, timezone_clause)),
-    ))(i)?;
+impl SelectStatement {
+    /// Returns the effective ordering of the selection by time, which is
+    /// [`OrderByClause::Ascending`] when no `ORDER BY` clause is specified.
+    pub fn order_direction(&self) -> OrderByClause {
+        self.order_by.unwrap_or_default()
+    }
 
-    Ok((
-        remaining,
-        SelectStatement {
-            fields,
-            from,
-            condition,
-            group_by,
-            fill,
-            order_by,
-            limit,
-            offset,
-            series_limit,
-            series_offset,
-            timezone,
-        },
-    ))
-}
+    /// Returns the names of the columns produced by the selection.
+    ///
+    /// The first column is always the timestamp column, named `time`. A field
+    /// that projects `time`, such as `SELECT time, value FROM m`, refers to
+    /// this column, and its alias, if any, names the column rather than adding
+    /// another.
+    ///
+    /// Other columns are named by their alias, or else by the variable
+    /// references and functions of their expression, such as `value` or
+    /// `mean`, joined with `_`. Conflicting names are given a numeric suffix,
+    /// such as `mean_1`. Wildcards and regular expressions are only expanded
+    /// against a schema, so do not produce a column.
+    pub fn output_columns(&self) -> Vec<String> {
+        let mut time = None;
+        let mut fields = Vec::new();
+        for field in self.fields.iter() {
+            match &field.expr {
+                Expr::VarRef { name, .. } if name.eq_ignore_ascii_case("time") => {
+                    time = time.or_else(|| field.alias.as_ref().map(|a| a.as_str().to_owned()));
+                }
+                Expr::Wildcard(_) | Expr::QualifiedWildcard { .. } => {}
+                Expr::Call { args, .. }
+                    if matches!(args.first(), Some(Expr::Literal(Literal::Regex(_)))) => {}
+                _ => fields.push(field),
+            }
+        }
+        let time = time.unwrap_or_else(|| "time".to_owned());
+
+        // Aliases are resolved first, so that generated names do not take them
+        let mut seen: HashMap<String, usize> = std::iter::once(time.clone())
+            .chain(
+                fields
+                    .iter()
+                    .filter_map(|f| f.alias.as_ref().map(|a| a.as_str().to_owned())),
+            )
+            .map(|name| (name, 1))
+            .collect();
+
+        let mut columns = vec![time];
+        for field in fields {
+            columns.push(match &field.alias {
+                Some(alias) => alias.as_str().to_owned(),
+                None => unique_column_name(field_name(&field.expr), &mut seen),
+            });
+        }
+        columns
+    }
 
-/// Represents a single measurement selection for a `FROM` clause.
-#[derive(Clone, Debug, PartialEq)]
-pub enum MeasurementSelection {
-    /// The measurement selection is measurement name or regular expression.
-    Name(QualifiedMeasurementName),
+    /// Returns true if the selection aggregates rows, that is, if any field calls
+    /// an aggregate or selector function, such as `MEAN` or `LAST`, or if the
+    /// selection is grouped by time.
+    pub fn is_aggregate(&self) -> bool {
+        self.fields.iter().any(|f| contains_aggregate_call(&f.expr))
+            || self
+                .group_by
+                .iter()
+                .flatten()
+                .any(|dim| matches!(dim, Dimension::Time { .. }))
+    }
 
-    /// The measurement selection is a subquery.
-    Subquery(Box<SelectStatement>),
-}
+    /// Returns the interval of the `TIME` dimension of the `GROUP BY` clause in
+    /// nanoseconds, or `None` if the selection is not grouped by time.
+    ///
+    /// The interval may be an arithmetic expression of durations and integers,
+    /// such as `1h + 30m` or `5m * 10`, which is evaluated to a constant. An
+    /// error is returned if the expression does not evaluate to a positive
+    /// duration.
+    pub fn group_by_interval(&self) -> Result<Option<i64>, IntervalError> {
+        self.time_dimension()
+            .map(Dimension::time_interval_nanos)
+            .transpose()
+    }
 
-impl Display for MeasurementSelection {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Name(ref name) => fmt::Display::fmt(name, f),
-            Self::Subquery(ref subquery) => write!(f, "({})", subquery),
+    /// Returns the windows of the `TIME` dimension of the `GROUP BY` clause
+    /// that intersect `range`, in ascending order.
+    ///
+    /// Windows are aligned to the Unix epoch, shifted by the offset argument of
+    /// the `TIME` dimension, if any. The first and last windows are not clipped
+    /// to `range`, so may extend beyond it.
+    ///
+    /// An error is returned if the selection is not grouped by time, if the
+    /// interval or offset do not evaluate to a constant duration, or if there
+    /// would be more than [`MAX_GROUP_WINDOWS`] windows.
+    pub fn group_windows(&self, range: &TimeRange) -> Result<Vec<TimeRange>, IntervalError> {
+        let dim = self
+            .time_dimension()
+            .ok_or(IntervalError("SELECT statement is not grouped by time"))?;
+
+        let interval = dim.time_interval_nanos()?;
+
+        let offset = dim
+            .time_offset_nanos()?
+            .map_or(0, |offset| offset.rem_euclid(interval));
+
+        // Widen to avoid overflow when the range starts near the minimum timestamp
+        let rem = (i128::from(range.start()) - i128::from(offset)).rem_euclid(i128::from(interval));
+        let mut start = range.start().saturating_sub(rem as i64);
+
+        let span = (i128::from(range.end()) - i128::from(start)).max(0);
+        let count = (span + i128::from(interval) - 1) / i128::from(interval);
+        if count > MAX_GROUP_WINDOWS as i128 {
+            return Err(IntervalError(
+                "GROUP BY TIME produces too many windows over the time range",
+            ));
         }
+
+        let mut windows = Vec::with_capacity(count as usize);
+        while start < range.end() {
+            let end = start.saturating_add(interval);
+            windows.push(TimeRange::new(start, end));
+            start = end;
+        }
+
+        Ok(windows)
     }
-}
 
-impl Parser for MeasurementSelection {
-    fn parse(i: &str) -> ParseResult<&str, Self> {
-        alt((
-            map(qualified_measurement_name, MeasurementSelection::Name),
-            map(
-                delimited(
-                    preceded(multispace0, char('(')),
-                    preceded(multispace0, select_statement),
-                    preceded(multispace0, char(')')),
-                ),
-                |s| Subquery(Box::new(s)),
-            ),
-        ))(i)
+    /// Returns the `TIME` dimension of the `GROUP BY` clause, if any.
+    fn time_dimension(&self) -> Option<&Dimension> {
+        self.group_by
+            .iter()
+            .flatten()
+            .find(|dim| matches!(dim, Dimension::Time { .. }))
     }
-}
 
-/// Represents a `FROM` clause for a `SELECT` statement.
-pub type FromMeasurementClause = OneOrMore<MeasurementSelection>;
+    /// Returns the distinct set of tag keys referenced by the statement,
+    /// including any subqueries of the `FROM` clause.
+    ///
+    /// Without a schema, a variable reference cannot be definitively classified as
+    /// a tag or a field, so the following heuristic is applied. A variable reference
+    /// is considered a tag key when it:
+    ///
+    /// * is a dimension of the `GROUP BY` clause,
+    /// * is explicitly cast using `::tag`, or
+    /// * is compared to a string or regular expression literal using one of the
+    ///   `=`, `!=`, `=~` or `!~` operators, such as `host = 'server01'`
+    ///
+    /// Any other variable reference is ambiguous and is assumed to be a field.
+    /// The `time` column is never considered a tag key.
+    pub fn referenced_tag_keys(&self) -> BTreeSet<Identifier> {
+        self.accept(TagKeysVisitor::default())
+            .map(|v| v.0)
+            .unwrap_or_default()
+    }
 
-impl Display for FromMeasurementClause {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "FROM {}", self.first())?;
-        for arg in self.rest() {
-            write!(f, ", {}", arg)?;
-        }
-        Ok(())
+    /// Returns the distinct, lowercase names of the functions called by the
+    /// statement, including those nested within the arguments of other calls
+    /// and those of any subqueries of the `FROM` clause.
+    pub fn function_names(&self) -> BTreeSet<String> {
+        self.accept(FunctionNamesVisitor::default())
+            .map(|v| v.0)
+            .unwrap_or_default()
     }
-}
 
-fn from_clause(i: &str) -> ParseResult<&str, FromMeasurementClause> {
-    preceded(
-        pair(keyword("FROM"), multispace0),
-        FromMeasurementClause::separated_list1(
-            "invalid FROM clause, expected identifier, regular expression or subquery",
-        ),
-    )(i)
-}
+    /// Returns the measurements of the `FROM` clause, including those of any
+    /// subqueries, in the order they first appear.
+    ///
+    /// Measurements named by a regular expression are returned separately, in
+    /// [`Measurements::regexes`], as they may match any number of measurements.
+    pub fn measurements(&self) -> Measurements {
+        self.accept(MeasurementsVisitor::default())
+            .map(|v| v.0)
+            .unwrap_or_default()
+    }
 
-/// Represents the collection of dimensions for a `GROUP BY` clause.
-pub type GroupByClause = OneOrMore<Dimension>;
+    /// Returns a canonical rendering of the statement, such that statements
+    /// differing only in formatting render identically.
+    ///
+    /// In addition to the normalization of keyword casing, whitespace and
+    /// quoting performed by [`Display`], function names are lowercased and the
+    /// dimensions of each `GROUP BY` clause are deduplicated and ordered, with
+    /// any `TIME` dimension first, followed by tags, regular expressions and
+    /// wildcards. String and regular expression literals are unchanged.
+    pub fn to_normalized_string(&self) -> String {
+        let mut stmt = self.clone();
+        stmt.accept_mut(Normalizer)
+            .expect("normalizing a statement is infallible");
+        stmt.to_string()
+    }
 
-impl Display for GroupByClause {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "GROUP BY {}", self.first())?;
-        for arg in self.rest() {
-            write!(f, ", {}", arg)?;
+    /// Sorts the fields of the projection by their [`Display`] form, so that
+    /// statements differing only in the order of their fields render identically.
+    /// The sort is stable, and fields of subqueries are unchanged.
+    ///
+    /// Sorting reorders the columns of the result, and is refused when the
+    /// position of a field affects more than its column, that is, when a field:
+    ///
+    /// * is a wildcard, such as `*` or `cpu.*`, or a regular expression, such as
+    ///   `/usage/` or `mean(/usage/)`, which are expanded in place;
+    /// * calls the `TOP` or `BOTTOM` function, which add a column for each tag
+    ///   argument at the position of the call; or
+    /// * is given the same generated column name as another field, as these are
+    ///   made unique by a numeric suffix in order of position.
+    ///
+    /// Returns `true` if the fields were sorted, or `false`, leaving the
+    /// statement unchanged, if sorting was refused.
+    pub fn sort_fields(&mut self) -> bool {
+        let mut names = HashSet::new();
+        for field in self.fields.iter() {
+            match &field.expr {
+                Expr::Wildcard(_)
+                | Expr::QualifiedWildcard { .. }
+                | Expr::Literal(Literal::Regex(_)) => return false,
+                Expr::Call { name, args }
+                    if name.eq_ignore_ascii_case("top")
+                        || name.eq_ignore_ascii_case("bottom")
+                        || matches!(args.first(), Some(Expr::Literal(Literal::Regex(_)))) =>
+                {
+                    return false
+                }
+                _ => {}
+            }
+            if field.alias.is_none() && !names.insert(field_name(&field.expr)) {
+                return false;
+            }
         }
-        Ok(())
+
+        self.fields.contents.sort_by_cached_key(|f| f.to_string());
+        true
     }
-}
 
-/// Used to parse the interval argument of the TIME function
-struct TimeCallIntervalArgument;
+    /// Renders the statement as an indented outline, with one node per line
+    /// and the children of each node indented beneath it.
+    ///
+    /// This is intended as an aid when debugging the grammar. The output is not
+    /// stable and is not expected to round-trip to a valid statement.
+    pub fn debug_tree(&self) -> String {
+        let mut tree = DebugTree::default();
+        tree.select_statement(self);
+        tree.out
+    }
 
-impl ArithmeticParsers for TimeCallIntervalArgument {
-    fn operand(i: &str) -> ParseResult<&str, Expr> {
-        // Any literal
-        preceded(
-            multispace0,
-            map(
-                alt((
-                    map(duration, Literal::Duration),
-                    map(unsigned_integer, Literal::Unsigned),
-                )),
-                Expr::Literal,
-            ),
-        )(i)
+    /// Validates that the `SLIMIT` and `SOFFSET` clauses of the statement and any
+    /// subqueries are accompanied by a `GROUP BY` clause, without which there is
+    /// only a single series to limit.
+    ///
+    /// The parser accepts either clause in isolation, so each misuse is reported
+    /// as a [`ValidationIssue`] with the specified `severity`.
+    pub fn validate_series_limits(&self, severity: ValidationSeverity) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        if self.group_by.is_none() {
+            if self.series_limit.is_some() {
+                issues.push(ValidationIssue {
+                    severity,
+                    message: "SLIMIT requires a GROUP BY clause",
+                });
+            }
+
+            if self.series_offset.is_some() {
+                issues.push(ValidationIssue {
+                    severity,
+                    message: "SOFFSET requires a GROUP BY clause",
+                });
+            }
+        }
+
+        for selection in &self.from {
+            if let Subquery(stmt) = selection {
+                issues.extend(stmt.validate_series_limits(severity));
+            }
+        }
+
+        issues
     }
-}
 
-/// Used to parse the offset argument of the TIME function
-///
-/// The offset argument accepts either a duration, datetime-like string or `now`.
-struct TimeCallOffsetArgument;
+    /// Validates that a `FILL(linear)` or `FILL(previous)` clause is only
+    /// used when the aggregates of the field list produce numeric values.
+    ///
+    /// The type of a variable reference is taken from its cast, such as
+    /// `value::string`, or otherwise looked up in `field_types`. An aggregate
+    /// whose type cannot be determined is assumed to be numeric.
+    pub fn validate_fill(
+        &self,
+        field_types: &HashMap<String, VarRefDataType>,
+    ) -> Option<ValidationIssue> {
+        let message = match self.fill {
+            Some(FillClause::Linear) => "FILL(linear) requires numeric aggregates",
+            Some(FillClause::Previous) => "FILL(previous) requires numeric aggregates",
+            _ => return None,
+        };
+
+        self.fields
+            .iter()
+            .filter(|field| contains_aggregate_call(&field.expr))
+            .any(|field| {
+                matches!(
+                    expr_data_type(&field.expr, field_types),
+                    Some(VarRefDataType::String | VarRefDataType::Boolean)
+                )
+            })
+            .then_some(ValidationIssue {
+                severity: ValidationSeverity::Error,
+                message,
+            })
+    }
 
-impl TimeCallOffsetArgument {
-    /// Parse the `now()` function call
-    fn now_call(i: &str) -> ParseResult<&str, Expr> {
-        verify(
-            "invalid expression, the only valid function call is 'now' with no arguments",
-            call_expression::<Self>,
-            is_valid_now_call,
-        )(i)
+    /// Validates that every regular expression of the statement compiles,
+    /// including those of the `FROM`, `WHERE` and `GROUP BY` clauses and of
+    /// any subqueries.
+    ///
+    /// Returns the first regular expression that fails to compile, along with
+    /// the clause in which it appears.
+    pub fn validate_regexes(&self) -> Result<(), InvalidRegex> {
+        match self.accept(RegexValidator::default()) {
+            Ok(RegexValidator {
+                error: Some(err), ..
+            }) => Err(err),
+            _ => Ok(()),
+        }
     }
-}
 
-impl ArithmeticParsers for TimeCallOffsetArgument {
-    fn operand(i: &str) -> ParseResult<&str, Expr> {
-        preceded(
-            multispace0,
-            alt((
-                Self::now_call,
-                map(duration, |v| Expr::Literal(Literal::Duration(v))),
-                map(single_quoted_string, |v| Expr::Literal(Literal::String(v))),
-            )),
-        )(i)
+    /// Validates the arguments of each call to a built-in InfluxQL function,
+    /// such as `percentile(value, 95)`, including those of any subqueries.
+    ///
+    /// The parser accepts any number and kind of arguments to any function, so
+    /// each call with the wrong number or kind of arguments is reported as a
+    /// [`ValidationIssue`]. Calls to other functions are not validated.
+    pub fn validate_function_calls(&self) -> Vec<ValidationIssue> {
+        self.accept(FunctionCallsValidator::default())
+            .map(|v| v.0)
+            .unwrap_or_default()
     }
-}
 
-/// Represents a dimension of a `GROUP BY` clause.
-#[derive(Clone, Debug, PartialEq)]
-pub enum Dimension {
-    /// Represents a `TIME` call in a `GROUP BY` clause.
-    Time {
-        /// The first argument of the `TIME` call.
-        interval: Expr,
-        /// An optional second argument to specify the offset applied to the `TIME` call.
-        offset: Option<Expr>,
-    },
+    /// Returns true if the `WHERE` clause places a lower bound on `time`, such
+    /// as `time > now() - 1h` or `time >= '2022-10-31T00:00:00Z'`.
+    ///
+    /// Only comparisons that must hold for every row are considered, that is,
+    /// those that are not nested within an `OR` expression.
+    pub fn has_time_bound(&self) -> bool {
+        self.condition
+            .as_ref()
+            .map_or(false, |cond| has_lower_time_bound(cond))
+    }
 
-    /// Represents a literal tag reference in a `GROUP BY` clause.
-    Tag(Identifier),
+    /// Conjoins `time > now() - <default_lookback>` to the `WHERE` clause,
+    /// creating one if necessary, when the statement has no lower time bound.
+    ///
+    /// `default_lookback` is a duration in nanoseconds. A statement that
+    /// already satisfies [`Self::has_time_bound`] is left unchanged.
+    pub fn ensure_time_bound(&mut self, default_lookback: i64) {
+        if self.has_time_bound() {
+            return;
+        }
 
-    /// Represents a regular expression in a `GROUP BY` clause.
-    Regex(Regex),
+        let bound = ConditionalExpression::Binary {
+            lhs: Box::new(ConditionalExpression::Expr(Box::new(Expr::VarRef {
+                name: "time".into(),
+                data_type: None,
+            }))),
+            op: ConditionalOperator::Gt,
+            rhs: Box::new(ConditionalExpression::Expr(Box::new(Expr::Binary {
+                lhs: Box::new(Expr::Call {
+                    name: "now".into(),
+                    args: vec![],
+                }),
+                op: BinaryOperator::Sub,
+                rhs: Box::new(Expr::Literal(Literal::Duration(default_lookback.into()))),
+            }))),
+        };
+
+        self.condition = Some(WhereClause::new(match self.condition.take() {
+            Some(cond) => {
+                // Group the existing condition, so that the bound applies to
+                // all of it, irrespective of operator precedence.
+                let lhs = match cond.0 {
+                    cond @ ConditionalExpression::Binary {
+                        op: ConditionalOperator::Or,
+                        ..
+                    } => ConditionalExpression::Grouped(Box::new(cond)),
+                    cond => cond,
+                };
+                ConditionalExpression::Binary {
+                    lhs: Box::new(lhs),
+                    op: ConditionalOperator::And,
+                    rhs: Box::new(bound),
+                }
+            }
+            None => bound,
+        }));
+    }
 
-    /// Represents a wildcard in a `GROUP BY` clause.
-    Wildcard,
-}
+    /// Returns the range of time the statement scans, given the current time
+    /// `now` and the `default_range` scanned in the absence of any bounds.
+    ///
+    /// The bounds are taken from the comparisons of `time` in the `WHERE`
+    /// clause that must hold for every row, with `now()` resolved to `now`.
+    /// A missing bound falls back to that of `default_range`, and the result
+    /// is intersected with `default_range`, so is empty if they are disjoint.
+    ///
+    /// Comparisons with values that are not constant integer or duration
+    /// nanosecond timestamps, optionally relative to `now()`, are ignored.
+    pub fn scan_time_range(&self, now: i64, default_range: &TimeRange) -> TimeRange {
+        let bounds = self
+            .condition
+            .as_ref()
+            .map_or_else(TimeBounds::default, |cond| time_bounds(cond, now));
+
+        let start = bounds
+            .start
+            .map_or(default_range.start(), |v| v.max(default_range.start()));
+        let end = bounds
+            .end
+            .map_or(default_range.end(), |v| v.min(default_range.end()));
+        TimeRange::new(start, end)
+    }
 
-impl Display for Dimension {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Time {
-                interval,
-                offset: Some(offset),
-            } => write!(f, "TIME({}, {})", interval, offset),
-            Self::Time { interval, .. } => write!(f, "TIME({})", interval),
-            Self::Tag(v) => Display::fmt(v, f),
-            Self::Regex(v) => Display::fmt(v, f),
-            Self::Wildcard => f.write_char('*'),
+    /// Returns the tightest bounds on `time` implied by the `WHERE` clause,
+    /// with `now()` resolved to `now`, or `None` if it places no bound on
+    /// `time`.
+    ///
+    /// As with [`Self::scan_time_range`], only comparisons that must hold for
+    /// every row are considered, and other predicates are ignored. The bounds
+    /// are normalized, such that a lower bound is [`Bound::Included`] and an
+    /// upper bound is [`Bound::Excluded`], so `time > 10` and `time <= 20` are
+    /// returned as `(Included(11), Excluded(21))`.
+    pub fn time_range(&self, now: i64) -> Option<(Bound<i64>, Bound<i64>)> {
+        match self.condition.as_ref().map(|cond| time_bounds(cond, now)) {
+            Some(TimeBounds {
+                start: None,
+                end: None,
+            })
+            | None => None,
+            Some(TimeBounds { start, end }) => Some((
+                start.map_or(Bound::Unbounded, Bound::Included),
+                end.map_or(Bound::Unbounded, Bound::Excluded),
+            )),
         }
     }
-}
 
-impl Parser for Dimension {
-    fn parse(i: &str) -> ParseResult<&str, Self> {
-        alt((
-            // Explicitly ignore the `WildCardType`, is InfluxQL always assumes `*::tag`
-            map(wildcard, |_| Self::Wildcard),
-            time_call_expression,
-            map(regex, Self::Regex),
-            map(var_ref, |v| {
-                Self::Tag(match v {
-                    Expr::VarRef { name, .. } => name,
-                    // var_ref only returns Expr::VarRef
-                    _ => unreachable!(),
-                })
-            }),
-        ))(i)
+    /// Returns the time zone of the `TZ` clause resolved from the tz database, or
+    /// `None` if the statement has no `TZ` clause.
+    ///
+    /// The parser only accepts known time zones, so an error, which names the
+    /// offending zone, is only returned for a statement that was constructed or
+    /// modified after parsing.
+    pub fn timezone_tz(&self) -> Option<Result<Tz, String>> {
+        self.timezone.as_ref().map(|tz| Tz::from_str(tz))
     }
-}
 
-fn time_call_expression(i: &str) -> ParseResult<&str, Dimension> {
-    map(
-        preceded(
-            keyword("TIME"),
-            delimited(
-                expect(
-                    "invalid TIME call, expected 1 or 2 arguments",
-                    preceded(multispace0, char('(')),
-                ),
-                pair(
-                    expect(
-                        "invalid TIME call, expected a duration for the interval",
-                        arithmetic::<TimeCallIntervalArgument>,
-                    ),
-                    opt(preceded(
-                        preceded(multispace0, char(',')),
-                        preceded(multispace0, arithmetic::<TimeCallOffsetArgument>),
-                    )),
-                ),
-                expect(
-                    "invalid TIME call, expected ')'",
-                    preceded(multispace0, char(')')),
-                ),
-            ),
-        ),
-        |(interval, offset)| Dimension::Time { interval, offset },
-    )(i)
-}
+    /// Returns the distinct names of the bind parameters referenced by the
+    /// statement, including those of any subqueries.
+    pub fn bind_parameters(&self) -> BTreeSet<String> {
+        self.accept(ParametersVisitor::default())
+            .map(|v| v.0)
+            .unwrap_or_default()
+    }
 
-/// Parse a `GROUP BY` clause.
-///
-/// ```text
-/// group_by_clause ::= dimension ( "," dimension )*
-/// ```
-fn group_by_clause(i: &str) -> ParseResult<&str, GroupByClause> {
-    preceded(
-        tuple((
-            keyword("GROUP"),
-            multispace1,
-            expect("invalid GROUP BY clause, expected BY", keyword("BY")),
-            multispace1,
-        )),
-        GroupByClause::separated_list1(
-            "invalid GROUP BY clause, expected wildcard, TIME, identifier or regular expression",
-        ),
-    )(i)
-}
+    /// Returns a copy of the statement with each bind parameter, including
+    /// those of any subqueries, replaced by the value of the same name in
+    /// `params`.
+    ///
+    /// An error is returned for the first parameter with no value in `params`.
+    /// Values in `params` that are not referenced by the statement are ignored.
+    pub fn bind(&self, params: &HashMap<String, Literal>) -> Result<Self, UnboundParameter> {
+        let mut stmt = self.clone();
+        stmt.bind_mut(params)?;
+        Ok(stmt)
+    }
 
-/// Represents a `FILL` clause, and specifies all possible cases of the argument to the `FILL` clause.
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum FillClause {
-    /// Empty aggregate windows will contain null values and is specified as `fill(null)`
-    Null,
+    /// Returns a copy of the statement in which each literal of the `WHERE`
+    /// clause, including those of any subqueries, is replaced by a generated bind
+    /// parameter, along with the values of those parameters.
+    ///
+    /// The parameters are named `p1`, `p2` and so on, in the order the literals
+    /// are replaced, skipping any name the statement already uses. Binding the
+    /// statement to the returned values using [`Self::bind`] restores the literals.
+    ///
+    /// Regular expressions are not replaced, as the grammar does not accept a
+    /// bind parameter as the operand of `=~` or `!~`.
+    pub fn parameterize(&self) -> (Self, HashMap<String, Literal>) {
+        let mut parameterizer = Parameterizer {
+            existing: self.bind_parameters(),
+            params: HashMap::new(),
+            next: 1,
+        };
+
+        let mut stmt = self.clone();
+        stmt.parameterize_mut(&mut parameterizer);
+        (stmt, parameterizer.params)
+    }
 
-    /// Empty aggregate windows will be discarded and is specified as `fill(none)`.
-    None,
+    /// Renames the measurement `from` to `to` wherever the statement, or any of
+    /// its subqueries, references it by name, returning the number of references
+    /// that were updated.
+    ///
+    /// References are the named sources of the `FROM` clause, the measurement
+    /// of the `INTO` clause and measurement qualified wildcards of the field
+    /// list, such as `cpu.*`. Regular expression sources are left untouched,
+    /// even if they match `from`.
+    pub fn rename_measurement(&mut self, from: &str, to: &str) -> usize {
+        let mut count = 0;
+
+        for field in &mut self.fields.contents {
+            if let Expr::QualifiedWildcard { measurement, .. } = &mut field.expr {
+                if measurement.as_str() == from {
+                    *measurement = to.into();
+                    count += 1;
+                }
+            }
+        }
 
-    /// Empty aggregate windows will be filled with the specified numerical value and is specified as
-    /// `fill(<value>)`
-    Value(Number),
+        for selection in &mut self.from.contents {
+            match selection {
+                MeasurementSelection::Name(QualifiedMeasurementName {
+                    name: MeasurementName::Name(name),
+                    ..
+                }) if name.as_str() == from => {
+                    *name = to.into();
+                    count += 1;
+                }
+                MeasurementSelection::Name(_) => {}
+                Subquery(stmt) => count += stmt.rename_measurement(from, to),
+            }
+        }
 
-    /// Empty aggregate windows will be filled with the value from the previous aggregate window
-    /// and is specified as `fill(previous)`
-    Previous,
+        if let Some(IntoClause {
+            measurement: IntoMeasurement::Name(name),
+            ..
+        }) = &mut self.into
+        {
+            if name.as_str() == from {
+                *name = to.into();
+                count += 1;
+            }
+        }
 
-    /// Empty aggregate windows will be filled with a value that is the linear interpolation of
-    /// the prior two non-null window values and is specified as `fill(linear)`
-    Linear,
-}
+        count
+    }
 
-impl Display for FillClause {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.write_str("FILL(")?;
-        match self {
-            Self::Null => f.write_str("NULL")?,
-            Self::None => f.write_str("NONE")?,
-            Self::Value(v) => fmt::Display::fmt(v, f)?,
-            Self::Previous => f.write_str("PREVIOUS")?,
-            Self::Linear => f.write_str("LINEAR")?,
+    fn parameterize_mut(&mut self, parameterizer: &mut Parameterizer) {
+        if let Some(cond) = &mut self.condition {
+            parameterizer.conditional(&mut cond.0);
+        }
+
+        for selection in &mut self.from.contents {
+            if let Subquery(stmt) = selection {
+                stmt.parameterize_mut(parameterizer);
+            }
         }
-        f.write_str(")")
     }
-}
 
-/// Represents an expression specified in the projection list of a `SELECT` statement.
-#[derive(Debug, Clone, PartialEq)]
-pub struct Field {
-    /// The expression which represents the field projection.
-    pub expr: Expr,
+    fn bind_mut(&mut self, params: &HashMap<String, Literal>) -> Result<(), UnboundParameter> {
+        for field in &mut self.fields.contents {
+            bind_expr(&mut field.expr, params)?;
+        }
 
-    /// An optional alias for the field projection.
-    pub alias: Option<Identifier>,
+        for selection in &mut self.from.contents {
+            if let Subquery(stmt) = selection {
+                stmt.bind_mut(params)?;
+            }
+        }
+
+        if let Some(cond) = &mut self.condition {
+            bind_conditional(&mut cond.0, params)?;
+        }
+
+        for dim in self.group_by.iter_mut().flat_map(|v| v.contents.iter_mut()) {
+            if let Dimension::Time { interval, offset } = dim {
+                bind_expr(interval, params)?;
+                if let Some(offset) = offset {
+                    bind_expr(offset, params)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
-impl Display for Field {
+/// The error returned by [`SelectStatement::bind`] when no value is specified
+/// for a bind parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnboundParameter(pub BindParameter);
+
+impl Display for UnboundParameter {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        Display::fmt(&self.expr, f)?;
-        if let Some(alias) = &self.alias {
-            write!(f, " AS {}", alias)?;
+        write!(f, "no value specified for bind parameter {}", self.0)
+    }
+}
+
+impl std::error::Error for UnboundParameter {}
+
+/// Replaces each bind parameter of `expr` with the value of the same name
+/// in `params`.
+fn bind_expr(expr: &mut Expr, params: &HashMap<String, Literal>) -> Result<(), UnboundParameter> {
+    match expr {
+        Expr::BindParameter(param) => {
+            let value = params
+                .get(param.as_str())
+                .ok_or_else(|| UnboundParameter(param.clone()))?;
+            *expr = Expr::Literal(value.clone());
         }
-        Ok(())
+        Expr::UnaryOp(_, expr) | Expr::Nested(expr) => bind_expr(expr, params)?,
+        Expr::Call { args, .. } => {
+            for arg in args {
+                bind_expr(arg, params)?;
+            }
+        }
+        Expr::Binary { lhs, rhs, .. } => {
+            bind_expr(lhs, params)?;
+            bind_expr(rhs, params)?;
+        }
+        Expr::VarRef { .. }
+        | Expr::Literal(_)
+        | Expr::Wildcard(_)
+        | Expr::QualifiedWildcard { .. }
+        | Expr::Distinct(_) => {}
     }
+
+    Ok(())
 }
 
-impl Parser for Field {
-    /// Parse a field expression that appears in the projection list of a `SELECT` clause.
-    ///
-    /// ```text
-    /// field ::= field_expression ( "AS" identifier )?
-    /// ```
-    fn parse(i: &str) -> ParseResult<&str, Self> {
-        map(
-            pair(
-                arithmetic::<FieldExpression>,
-                opt(preceded(
-                    delimited(multispace0, keyword("AS"), multispace1),
-                    expect("invalid field alias, expected identifier", identifier),
-                )),
-            ),
-            |(expr, alias)| Self { expr, alias },
-        )(i)
+/// Replaces each bind parameter of `cond` with the value of the same name
+/// in `params`.
+fn bind_conditional(
+    cond: &mut ConditionalExpression,
+    params: &HashMap<String, Literal>,
+) -> Result<(), UnboundParameter> {
+    match cond {
+        ConditionalExpression::Expr(expr) => bind_expr(expr, params),
+        ConditionalExpression::Binary { lhs, rhs, .. } => {
+            bind_conditional(lhs, params)?;
+            bind_conditional(rhs, params)
+        }
+        ConditionalExpression::Grouped(cond) => bind_conditional(cond, params),
     }
 }
 
-/// Parse a wildcard expression.
-///
-/// wildcard ::= "*" ( "::" ("field" | "tag")?
-fn wildcard(i: &str) -> ParseResult<&str, Option<WildcardType>> {
-    preceded(
-        char('*'),
-        opt(preceded(
-            tag("::"),
-            expect(
-                "invalid wildcard type specifier, expected TAG or FIELD",
-                alt((
-                    value(WildcardType::Tag, keyword("TAG")),
-                    value(WildcardType::Field, keyword("FIELD")),
-                )),
-            ),
-        )),
-    )(i)
+/// Replaces literals with generated bind parameters, for
+/// [`SelectStatement::parameterize`].
+struct Parameterizer {
+    /// The names of the bind parameters of the statement, which must not be
+    /// generated.
+    existing: BTreeSet<String>,
+    params: HashMap<String, Literal>,
+    next: usize,
 }
 
-/// Represents the field projection list of a `SELECT` statement.
-pub type FieldList = OneOrMore<Field>;
+impl Parameterizer {
+    fn param(&mut self, value: Literal) -> BindParameter {
+        let name = loop {
+            let name = format!("p{}", self.next);
+            self.next += 1;
+            if !self.existing.contains(&name) {
+                break name;
+            }
+        };
+        self.params.insert(name.clone(), value);
+        name.into()
+    }
 
-impl Display for FieldList {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        Display::fmt(self.first(), f)?;
-        for arg in self.rest() {
-            write!(f, ", {}", arg)?;
+    fn expr(&mut self, expr: &mut Expr) {
+        match expr {
+            // A regular expression may only follow `=~` or `!~`, and not a
+            // bind parameter
+            Expr::Literal(Literal::Regex(_)) => {}
+            Expr::Literal(value) => {
+                let value = value.clone();
+                *expr = Expr::BindParameter(self.param(value));
+            }
+            Expr::UnaryOp(_, expr) | Expr::Nested(expr) => self.expr(expr),
+            Expr::Call { args, .. } => {
+                for arg in args {
+                    self.expr(arg);
+                }
+            }
+            Expr::Binary { lhs, rhs, .. } => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+            Expr::VarRef { .. }
+            | Expr::BindParameter(_)
+            | Expr::Wildcard(_)
+            | Expr::QualifiedWildcard { .. }
+            | Expr::Distinct(_) => {}
+        }
+    }
+
+    fn conditional(&mut self, cond: &mut ConditionalExpression) {
+        match cond {
+            ConditionalExpression::Expr(expr) => self.expr(expr),
+            ConditionalExpression::Binary { lhs, rhs, .. } => {
+                self.conditional(lhs);
+                self.conditional(rhs);
+            }
+            ConditionalExpression::Grouped(cond) => self.conditional(cond),
         }
-        Ok(())
     }
 }
 
-/// Parse a field expression.
-///
-/// A field expression is an arithmetic expression accepting
-/// a specific set of operands.
-struct FieldExpression;
+/// Collects the names of the bind parameters of a statement.
+#[derive(Default)]
+struct ParametersVisitor(BTreeSet<String>);
 
-impl ArithmeticParsers for FieldExpression {
-    fn operand(i: &str) -> ParseResult<&str, Expr> {
-        preceded(
-            multispace0,
-            alt((
-                // DISTINCT identifier
-                map(
-                    preceded(
-                        pair(keyword("DISTINCT"), multispace1),
-                        expect(
-                            "invalid DISTINCT expression, expected identifier",
-                            identifier,
-                        ),
-                    ),
-                    Expr::Distinct,
-                ),
-                // *
-                map(wildcard, Wildcard),
-                // Any literal
-                map(literal, Expr::Literal),
-                // A call expression
-                call_expression::<Self>,
-                // A tag or field reference
-                var_ref,
-                // A bind parameter
-                map(parameter, Expr::BindParameter),
-            )),
-        )(i)
+impl Visitor for ParametersVisitor {
+    fn pre_visit_expr(mut self, n: &Expr) -> VisitorResult<Recursion<Self>> {
+        if let Expr::BindParameter(param) = n {
+            self.0.insert(param.as_str().to_owned());
+        }
+        Ok(Recursion::Continue(self))
     }
 }
 
-/// Parse the projection list of a `SELECT` statement.
-///
-/// ```text
-/// field_list ::= field ( "," field )*
-/// ```
-fn field_list(i: &str) -> ParseResult<&str, FieldList> {
-    FieldList::separated_list1("invalid SELECT statement, expected field")(i)
+/// Collects the measurements of the `FROM` clause of a statement, for
+/// [`SelectStatement::measurements`].
+#[derive(Default)]
+struct MeasurementsVisitor(Measurements);
+
+impl Visitor for MeasurementsVisitor {
+    fn pre_visit_select_measurement_selection(
+        mut self,
+        n: &MeasurementSelection,
+    ) -> VisitorResult<Recursion<Self>> {
+        if let MeasurementSelection::Name(name) = n {
+            let names = match name.name {
+                MeasurementName::Name(_) => &mut self.0.names,
+                MeasurementName::Regex(_) => &mut self.0.regexes,
+            };
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        Ok(Recursion::Continue(self))
+    }
 }
 
-/// Parse a `FILL(option)` clause.
-///
-/// ```text
-/// fill_clause ::= "FILL" "(" fill_option ")"
-/// fill_option ::= "NULL" | "NONE" | "PREVIOUS" | "LINEAR" | number
-/// number      ::= signed_integer | signed_float
-/// ```
-fn fill_clause(i: &str) -> ParseResult<&str, FillClause> {
-    preceded(
-        keyword("FILL"),
-        delimited(
-            preceded(multispace0, char('(')),
-            expect(
-                "invalid FILL option, expected NULL, NONE, PREVIOUS, LINEAR, or a number",
-                preceded(
-                    multispace0,
-                    alt((
-                        value(FillClause::Null, keyword("NULL")),
-                        value(FillClause::None, keyword("NONE")),
-                        map(number, FillClause::Value),
-                        value(FillClause::Previous, keyword("PREVIOUS")),
-                        value(FillClause::Linear, keyword("LINEAR")),
-                    )),
-                ),
-            ),
-            preceded(multispace0, char(')')),
-        ),
-    )(i)
+/// Collects the lowercase names of the functions called by a statement.
+#[derive(Default)]
+struct FunctionNamesVisitor(BTreeSet<String>);
+
+impl Visitor for FunctionNamesVisitor {
+    fn pre_visit_expr(mut self, n: &Expr) -> VisitorResult<Recursion<Self>> {
+        if let Expr::Call { name, .. } = n {
+            self.0.insert(name.to_ascii_lowercase());
+        }
+        Ok(Recursion::Continue(self))
+    }
 }
 
-/// Represents the value for a `SLIMIT` clause.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct SLimitClause(pub(crate) u64);
+/// Compiles each regular expression of a statement, recording the first that
+/// fails, for [`SelectStatement::validate_regexes`].
+#[derive(Default)]
+struct RegexValidator {
+    /// The clauses enclosing the node being visited, innermost last.
+    clauses: Vec<&'static str>,
+    error: Option<InvalidRegex>,
+}
 
-impl_tuple_clause!(SLimitClause, u64);
+impl RegexValidator {
+    fn enter(mut self, clause: &'static str) -> VisitorResult<Recursion<Self>> {
+        self.clauses.push(clause);
+        Ok(Recursion::Continue(self))
+    }
 
-impl Display for SLimitClause {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "SLIMIT {}", self.0)
+    fn exit(mut self) -> VisitorResult<Self> {
+        self.clauses.pop();
+        Ok(self)
+    }
+
+    fn check(mut self, regex: &Regex) -> VisitorResult<Recursion<Self>> {
+        if self.error.is_some() {
+            return Ok(Recursion::Stop(self));
+        }
+
+        if let Err(source) = regex.compile() {
+            self.error = Some(InvalidRegex {
+                clause: self.clauses.last().copied().unwrap_or("SELECT statement"),
+                regex: regex.clone(),
+                source,
+            });
+        }
+        Ok(Recursion::Continue(self))
     }
 }
 
-/// Parse a series limit (`SLIMIT <n>`) clause.
-///
-/// ```text
-/// slimit_clause ::= "SLIMIT" unsigned_integer
-/// ```
-fn slimit_clause(i: &str) -> ParseResult<&str, SLimitClause> {
-    preceded(
-        pair(keyword("SLIMIT"), multispace1),
-        expect(
-            "invalid SLIMIT clause, expected unsigned integer",
-            map(unsigned_integer, SLimitClause),
-        ),
-    )(i)
+impl Visitor for RegexValidator {
+    fn pre_visit_select_field_list(self, _n: &FieldList) -> VisitorResult<Recursion<Self>> {
+        self.enter("SELECT clause")
+    }
+
+    fn post_visit_select_field_list(self, _n: &FieldList) -> VisitorResult<Self> {
+        self.exit()
+    }
+
+    fn pre_visit_select_from_clause(
+        self,
+        _n: &FromMeasurementClause,
+    ) -> VisitorResult<Recursion<Self>> {
+        self.enter("FROM clause")
+    }
+
+    fn post_visit_select_from_clause(self, _n: &FromMeasurementClause) -> VisitorResult<Self> {
+        self.exit()
+    }
+
+    fn pre_visit_where_clause(self, _n: &WhereClause) -> VisitorResult<Recursion<Self>> {
+        self.enter("WHERE clause")
+    }
+
+    fn post_visit_where_clause(self, _n: &WhereClause) -> VisitorResult<Self> {
+        self.exit()
+    }
+
+    fn pre_visit_group_by_clause(self, _n: &GroupByClause) -> VisitorResult<Recursion<Self>> {
+        self.enter("GROUP BY clause")
+    }
+
+    fn post_visit_group_by_clause(self, _n: &GroupByClause) -> VisitorResult<Self> {
+        self.exit()
+    }
+
+    fn pre_visit_measurement_name(self, n: &MeasurementName) -> VisitorResult<Recursion<Self>> {
+        match n {
+            MeasurementName::Regex(re) => self.check(re),
+            MeasurementName::Name(_) => Ok(Recursion::Continue(self)),
+        }
+    }
+
+    fn pre_visit_select_dimension(self, n: &Dimension) -> VisitorResult<Recursion<Self>> {
+        match n {
+            Dimension::Regex(re) => self.check(re),
+            _ => Ok(Recursion::Continue(self)),
+        }
+    }
+
+    fn pre_visit_expr(self, n: &Expr) -> VisitorResult<Recursion<Self>> {
+        match n {
+            Expr::Literal(Literal::Regex(re)) => self.check(re),
+            _ => Ok(Recursion::Continue(self)),
+        }
+    }
 }
 
-/// Represents the value for a `SOFFSET` clause.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct SOffsetClause(pub(crate) u64);
+/// Validates the arguments of calls to built-in functions, for
+/// [`SelectStatement::validate_function_calls`].
+#[derive(Default)]
+struct FunctionCallsValidator(Vec<ValidationIssue>);
+
+impl Visitor for FunctionCallsValidator {
+    fn pre_visit_expr(mut self, n: &Expr) -> VisitorResult<Recursion<Self>> {
+        if let Expr::Call { name, args } = n {
+            if let Some((_, kinds, message)) = FUNCTION_SIGNATURES
+                .iter()
+                .find(|(f, ..)| f.eq_ignore_ascii_case(name))
+            {
+                if !FunctionArg::matches(kinds, args) {
+                    self.0.push(ValidationIssue {
+                        severity: ValidationSeverity::Error,
+                        message: *message,
+                    });
+                }
+            }
+        }
+        Ok(Recursion::Continue(self))
+    }
+}
 
-impl_tuple_clause!(SOffsetClause, u64);
+/// Rewrites a statement to its canonical form, for
+/// [`SelectStatement::to_normalized_string`].
+struct Normalizer;
 
-impl Display for SOffsetClause {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "SOFFSET {}", self.0)
+impl VisitorMut for Normalizer {
+    fn pre_visit_expr(self, n: &mut Expr) -> MutVisitorResult<MutRecursion<Self>> {
+        if let Expr::Call { name, .. } = n {
+            name.make_ascii_lowercase();
+        }
+        Ok(MutRecursion::Continue(self))
+    }
+
+    fn post_visit_group_by_clause(self, n: &mut GroupByClause) -> MutVisitorResult<Self> {
+        n.contents.sort_by_cached_key(|d| match d {
+            Dimension::Time { .. } => (0, String::new()),
+            Dimension::Tag(tag) => (1, tag.to_string()),
+            Dimension::Regex(re) => (2, re.to_string()),
+            Dimension::Wildcard => (3, String::new()),
+        });
+        n.contents.dedup();
+        Ok(self)
     }
 }
 
-/// Parse a series offset (`SOFFSET <n>`) clause.
+/// The aggregate and selector functions of InfluxQL, which combine the values
+/// of many rows.
 ///
-/// ```text
-/// soffset_clause ::= "SOFFSET" unsigned_integer
-/// ```
-fn soffset_clause(i: &str) -> ParseResult<&str, SOffsetClause> {
-    preceded(
-        pair(keyword("SOFFSET"), multispace1),
-        expect(
-            "invalid SLIMIT clause, expected unsigned integer",
-            map(unsigned_integer, SOffsetClause),
+/// See <https://docs.influxdata.com/influxdb/v1.8/query_language/functions/>
+const AGGREGATE_FUNCTIONS: &[&str] = &[
+    // aggregates
+    "COUNT",
+    "DISTINCT",
+    "INTEGRAL",
+    "MEAN",
+    "MEDIAN",
+    "MODE",
+    "SPREAD",
+    "STDDEV",
+    "SUM",
+    // selectors
+    "BOTTOM",
+    "FIRST",
+    "LAST",
+    "MAX",
+    "MIN",
+    "PERCENTILE",
+    "SAMPLE",
+    "TOP",
+];
+
+/// Returns true if `expr` calls one of the [`AGGREGATE_FUNCTIONS`].
+fn contains_aggregate_call(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call { name, args } => {
+            AGGREGATE_FUNCTIONS
+                .iter()
+                .any(|f| f.eq_ignore_ascii_case(name))
+                || args.iter().any(contains_aggregate_call)
+        }
+        Expr::UnaryOp(_, expr) | Expr::Nested(expr) => contains_aggregate_call(expr),
+        Expr::Binary { lhs, rhs, .. } => {
+            contains_aggregate_call(lhs) || contains_aggregate_call(rhs)
+        }
+        Expr::VarRef { .. }
+        | Expr::BindParameter(_)
+        | Expr::Literal(_)
+        | Expr::Wildcard(_)
+        | Expr::QualifiedWildcard { .. }
+        | Expr::Distinct(_) => false,
+    }
+}
+
+/// The kind of an argument of a built-in function.
+#[derive(Debug, Clone, Copy)]
+enum FunctionArg {
+    /// A field, wildcard, regular expression or nested call, such as `value`
+    /// or `mean(value)`.
+    Field,
+    /// Zero or more tags, such as `host, region`.
+    Tags,
+    /// An unsigned integer literal.
+    Integer,
+    /// An integer or float literal.
+    Number,
+    /// An optional duration literal, such as the unit of `derivative`.
+    OptionalDuration,
+}
+
+impl FunctionArg {
+    /// Returns true if `args` match the sequence of `kinds`.
+    ///
+    /// A bind parameter is assumed to match any kind but [`FunctionArg::Tags`].
+    fn matches(kinds: &[Self], args: &[Expr]) -> bool {
+        let (kind, rest) = match kinds.split_first() {
+            Some(v) => v,
+            None => return args.is_empty(),
+        };
+
+        match kind {
+            Self::Tags => (0..=args.len()).any(|n| {
+                args[..n]
+                    .iter()
+                    .all(|arg| matches!(arg, Expr::VarRef { .. }))
+                    && Self::matches(rest, &args[n..])
+            }),
+            Self::OptionalDuration => {
+                Self::matches(rest, args)
+                    || args.split_first().map_or(false, |(arg, args)| {
+                        kind.accepts(arg) && Self::matches(rest, args)
+                    })
+            }
+            _ => args.split_first().map_or(false, |(arg, args)| {
+                kind.accepts(arg) && Self::matches(rest, args)
+            }),
+        }
+    }
+
+    fn accepts(self, arg: &Expr) -> bool {
+        match (self, arg) {
+            (_, Expr::BindParameter(_)) => true,
+            (
+                Self::Field,
+                Expr::VarRef { .. }
+                | Expr::Wildcard(_)
+                | Expr::Literal(Literal::Regex(_))
+                | Expr::Call { .. }
+                | Expr::Distinct(_),
+            ) => true,
+            (Self::Tags, Expr::VarRef { .. }) => true,
+            (Self::Integer, Expr::Literal(Literal::Unsigned(_))) => true,
+            (Self::Number, Expr::Literal(Literal::Unsigned(_) | Literal::Float(_))) => true,
+            (Self::OptionalDuration, Expr::Literal(Literal::Duration(_))) => true,
+            _ => false,
+        }
+    }
+}
+
+/// The built-in functions of InfluxQL that take a field, with the kinds of the
+/// arguments of each, and the error returned for any other arguments.
+///
+/// See <https://docs.influxdata.com/influxdb/v1.8/query_language/functions/>
+const FUNCTION_SIGNATURES: &[(&str, &[FunctionArg], &str)] = &[
+    // aggregates
+    (
+        "COUNT",
+        &[FunctionArg::Field],
+        "invalid COUNT function, expected a field",
+    ),
+    (
+        "DISTINCT",
+        &[FunctionArg::Field],
+        "invalid DISTINCT function, expected a field",
+    ),
+    (
+        "INTEGRAL",
+        &[FunctionArg::Field, FunctionArg::OptionalDuration],
+        "invalid INTEGRAL function, expected a field and an optional duration",
+    ),
+    (
+        "MEAN",
+        &[FunctionArg::Field],
+        "invalid MEAN function, expected a field",
+    ),
+    (
+        "MEDIAN",
+        &[FunctionArg::Field],
+        "invalid MEDIAN function, expected a field",
+    ),
+    (
+        "MODE",
+        &[FunctionArg::Field],
+        "invalid MODE function, expected a field",
+    ),
+    (
+        "SPREAD",
+        &[FunctionArg::Field],
+        "invalid SPREAD function, expected a field",
+    ),
+    (
+        "STDDEV",
+        &[FunctionArg::Field],
+        "invalid STDDEV function, expected a field",
+    ),
+    (
+        "SUM",
+        &[FunctionArg::Field],
+        "invalid SUM function, expected a field",
+    ),
+    // selectors
+    (
+        "BOTTOM",
+        &[FunctionArg::Field, FunctionArg::Tags, FunctionArg::Integer],
+        "invalid BOTTOM function, expected a field, optional tags and an integer",
+    ),
+    (
+        "FIRST",
+        &[FunctionArg::Field],
+        "invalid FIRST function, expected a field",
+    ),
+    (
+        "LAST",
+        &[FunctionArg::Field],
+        "invalid LAST function, expected a field",
+    ),
+    (
+        "MAX",
+        &[FunctionArg::Field],
+        "invalid MAX function, expected a field",
+    ),
+    (
+        "MIN",
+        &[FunctionArg::Field],
+        "invalid MIN function, expected a field",
+    ),
+    (
+        "PERCENTILE",
+        &[FunctionArg::Field, FunctionArg::Number],
+        "invalid PERCENTILE function, expected a field and a number",
+    ),
+    (
+        "SAMPLE",
+        &[FunctionArg::Field, FunctionArg::Integer],
+        "invalid SAMPLE function, expected a field and an integer",
+    ),
+    (
+        "TOP",
+        &[FunctionArg::Field, FunctionArg::Tags, FunctionArg::Integer],
+        "invalid TOP function, expected a field, optional tags and an integer",
+    ),
+    // transformations
+    (
+        "CUMULATIVE_SUM",
+        &[FunctionArg::Field],
+        "invalid CUMULATIVE_SUM function, expected a field",
+    ),
+    (
+        "DERIVATIVE",
+        &[FunctionArg::Field, FunctionArg::OptionalDuration],
+        "invalid DERIVATIVE function, expected a field and an optional duration",
+    ),
+    (
+        "DIFFERENCE",
+        &[FunctionArg::Field],
+        "invalid DIFFERENCE function, expected a field",
+    ),
+    (
+        "ELAPSED",
+        &[FunctionArg::Field, FunctionArg::OptionalDuration],
+        "invalid ELAPSED function, expected a field and an optional duration",
+    ),
+    (
+        "MOVING_AVERAGE",
+        &[FunctionArg::Field, FunctionArg::Integer],
+        "invalid MOVING_AVERAGE function, expected a field and an integer",
+    ),
+    (
+        "NON_NEGATIVE_DERIVATIVE",
+        &[FunctionArg::Field, FunctionArg::OptionalDuration],
+        "invalid NON_NEGATIVE_DERIVATIVE function, expected a field and an optional duration",
+    ),
+    (
+        "NON_NEGATIVE_DIFFERENCE",
+        &[FunctionArg::Field],
+        "invalid NON_NEGATIVE_DIFFERENCE function, expected a field",
+    ),
+    // predictors
+    (
+        "HOLT_WINTERS",
+        &[
+            FunctionArg::Field,
+            FunctionArg::Integer,
+            FunctionArg::Integer,
+        ],
+        "invalid HOLT_WINTERS function, expected a field and two integers",
+    ),
+    (
+        "HOLT_WINTERS_WITH_FIT",
+        &[
+            FunctionArg::Field,
+            FunctionArg::Integer,
+            FunctionArg::Integer,
+        ],
+        "invalid HOLT_WINTERS_WITH_FIT function, expected a field and two integers",
+    ),
+];
+
+/// Returns the data type produced by `expr`, if it can be determined.
+///
+/// Variable references without a cast are resolved using `field_types`.
+fn expr_data_type(
+    expr: &Expr,
+    field_types: &HashMap<String, VarRefDataType>,
+) -> Option<VarRefDataType> {
+    use VarRefDataType::*;
+
+    match expr {
+        Expr::VarRef { name, data_type } => match data_type {
+            Some(Tag) => Some(String),
+            Some(Field) | None => field_types.get(name.as_str()).copied(),
+            data_type => *data_type,
+        },
+        Expr::Distinct(name) => field_types.get(name.as_str()).copied(),
+        Expr::Literal(Literal::Unsigned(_)) => Some(Integer),
+        Expr::Literal(Literal::Float(_)) => Some(Float),
+        Expr::Literal(Literal::String(_)) => Some(String),
+        Expr::Literal(Literal::Boolean(_)) => Some(Boolean),
+        Expr::Call { name, args } => call_data_type(name, args, field_types),
+        Expr::UnaryOp(_, expr) | Expr::Nested(expr) => expr_data_type(expr, field_types),
+        Expr::Binary { lhs, rhs, .. } => {
+            match (
+                expr_data_type(lhs, field_types)?,
+                expr_data_type(rhs, field_types)?,
+            ) {
+                (Integer, Integer) => Some(Integer),
+                (Integer | Float, Integer | Float) => Some(Float),
+                (String, _) | (_, String) => Some(String),
+                (Boolean, _) | (_, Boolean) => Some(Boolean),
+                _ => None,
+            }
+        }
+        Expr::Literal(_)
+        | Expr::BindParameter(_)
+        | Expr::Wildcard(_)
+        | Expr::QualifiedWildcard { .. } => None,
+    }
+}
+
+/// Returns the data type produced by a call to the aggregate or selector
+/// function `name`, if it can be determined.
+fn call_data_type(
+    name: &str,
+    args: &[Expr],
+    field_types: &HashMap<String, VarRefDataType>,
+) -> Option<VarRefDataType> {
+    match name.to_ascii_uppercase().as_str() {
+        "COUNT" => Some(VarRefDataType::Integer),
+        "INTEGRAL" | "MEAN" | "MEDIAN" | "STDDEV" => Some(VarRefDataType::Float),
+        // These return values of the same type as their first argument
+        "BOTTOM" | "DISTINCT" | "FIRST" | "LAST" | "MAX" | "MIN" | "MODE" | "PERCENTILE"
+        | "SAMPLE" | "SPREAD" | "SUM" | "TOP" => expr_data_type(args.first()?, field_types),
+        _ => None,
+    }
+}
+
+/// Returns the name of the column produced by a field projecting `expr`,
+/// joining the names of its variable references and function calls with `_`.
+fn field_name(expr: &Expr) -> String {
+    match expr {
+        Expr::VarRef { name, .. } => name.as_str().to_owned(),
+        Expr::Call { name, .. } => name.to_ascii_lowercase(),
+        Expr::Distinct(_) => "distinct".to_owned(),
+        Expr::UnaryOp(_, expr) | Expr::Nested(expr) => field_name(expr),
+        Expr::Binary { lhs, rhs, .. } => {
+            let (lhs, rhs) = (field_name(lhs), field_name(rhs));
+            match (lhs.is_empty(), rhs.is_empty()) {
+                (false, false) => format!("{}_{}", lhs, rhs),
+                (false, true) => lhs,
+                _ => rhs,
+            }
+        }
+        Expr::BindParameter(_)
+        | Expr::Literal(_)
+        | Expr::Wildcard(_)
+        | Expr::QualifiedWildcard { .. } => String::new(),
+    }
+}
+
+/// Returns `name`, or if it has already been `seen`, `name` with the first
+/// numeric suffix that has not, recording the returned name in `seen`.
+fn unique_column_name(name: String, seen: &mut HashMap<String, usize>) -> String {
+    let name = match seen.get(&name).copied() {
+        None => name,
+        Some(mut count) => loop {
+            let candidate = format!("{}_{}", name, count);
+            if !seen.contains_key(&candidate) {
+                seen.insert(name, count + 1);
+                break candidate;
+            }
+            count += 1;
+        },
+    };
+    *seen.entry(name.clone()).or_default() += 1;
+    name
+}
+
+/// Returns true if `cond` requires `time` to be greater than, or equal to,
+/// some value.
+fn has_lower_time_bound(cond: &ConditionalExpression) -> bool {
+    use ConditionalOperator::*;
+
+    let is_time = |e: &ConditionalExpression| match e {
+        ConditionalExpression::Expr(e) => match e.as_ref() {
+            Expr::VarRef { name, .. } => name.eq_ignore_ascii_case("time"),
+            _ => false,
+        },
+        _ => false,
+    };
+
+    match cond {
+        ConditionalExpression::Binary { lhs, op: And, rhs } => {
+            has_lower_time_bound(lhs) || has_lower_time_bound(rhs)
+        }
+        ConditionalExpression::Binary {
+            lhs,
+            op: Gt | GtEq | Eq,
+            ..
+        } if is_time(lhs) => true,
+        ConditionalExpression::Binary {
+            op: Lt | LtEq | Eq,
+            rhs,
+            ..
+        } if is_time(rhs) => true,
+        ConditionalExpression::Grouped(cond) => has_lower_time_bound(cond),
+        _ => false,
+    }
+}
+
+/// The inclusive lower and exclusive upper bounds on `time` required by a
+/// condition.
+#[derive(Debug, Default, Clone, Copy)]
+struct TimeBounds {
+    start: Option<i64>,
+    end: Option<i64>,
+}
+
+impl TimeBounds {
+    /// Returns the bounds satisfying both `self` and `other`.
+    fn intersect(self, other: Self) -> Self {
+        Self {
+            // None orders before any value, so is the least restrictive start
+            start: self.start.max(other.start),
+            end: match (self.end, other.end) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            },
+        }
+    }
+}
+
+/// Returns the bounds on `time` required by `cond`, resolving `now()` to `now`.
+fn time_bounds(cond: &ConditionalExpression, now: i64) -> TimeBounds {
+    use ConditionalOperator::*;
+
+    let time_value = |e: &ConditionalExpression| match e {
+        ConditionalExpression::Expr(e) => eval_timestamp(e, now),
+        _ => None,
+    };
+    let is_time = |e: &ConditionalExpression| match e {
+        ConditionalExpression::Expr(e) => match e.as_ref() {
+            Expr::VarRef { name, .. } => name.eq_ignore_ascii_case("time"),
+            _ => false,
+        },
+        _ => false,
+    };
+
+    let (op, value) = match cond {
+        ConditionalExpression::Binary { lhs, op: And, rhs } => {
+            return time_bounds(lhs, now).intersect(time_bounds(rhs, now))
+        }
+        ConditionalExpression::Grouped(cond) => return time_bounds(cond, now),
+        ConditionalExpression::Binary { lhs, op, rhs } if is_time(lhs) => (*op, time_value(rhs)),
+        // Reverse the comparison, so that `time` is on the left
+        ConditionalExpression::Binary { lhs, op, rhs } if is_time(rhs) => (
+            match op {
+                Gt => Lt,
+                GtEq => LtEq,
+                Lt => Gt,
+                LtEq => GtEq,
+                op => *op,
+            },
+            time_value(lhs),
         ),
-    )(i)
+        _ => return TimeBounds::default(),
+    };
+
+    match (op, value) {
+        (Gt, Some(v)) => TimeBounds {
+            start: Some(v.saturating_add(1)),
+            end: None,
+        },
+        (GtEq, Some(v)) => TimeBounds {
+            start: Some(v),
+            end: None,
+        },
+        (Lt, Some(v)) => TimeBounds {
+            start: None,
+            end: Some(v),
+        },
+        (LtEq, Some(v)) => TimeBounds {
+            start: None,
+            end: Some(v.saturating_add(1)),
+        },
+        (Eq, Some(v)) => TimeBounds {
+            start: Some(v),
+            end: Some(v.saturating_add(1)),
+        },
+        _ => TimeBounds::default(),
+    }
 }
 
-/// Represents the value of the time zone string of a `TZ` clause.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct TimeZoneClause(pub(crate) String);
+/// Evaluates `expr` to a timestamp in nanoseconds, resolving `now()` to `now`,
+/// returning [`None`] if it is not constant or overflows.
+pub(crate) fn eval_timestamp(expr: &Expr, now: i64) -> Option<i64> {
+    match expr {
+        Expr::Call { .. } if is_valid_now_call(expr) => Some(now),
+        Expr::Literal(Literal::Duration(v)) => Some(**v),
+        Expr::Literal(Literal::Unsigned(v)) => i64::try_from(*v).ok(),
+        Expr::Nested(expr) => eval_timestamp(expr, now),
+        Expr::UnaryOp(UnaryOperator::Minus, expr) => eval_timestamp(expr, now)?.checked_neg(),
+        Expr::UnaryOp(UnaryOperator::Plus, expr) => eval_timestamp(expr, now),
+        Expr::Binary {
+            lhs,
+            op: BinaryOperator::Add,
+            rhs,
+        } => eval_timestamp(lhs, now)?.checked_add(eval_timestamp(rhs, now)?),
+        Expr::Binary {
+            lhs,
+            op: BinaryOperator::Sub,
+            rhs,
+        } => eval_timestamp(lhs, now)?.checked_sub(eval_timestamp(rhs, now)?),
+        _ => None,
+    }
+}
 
-impl_tuple_clause!(TimeZoneClause, String);
+/// A range of nanosecond timestamps, from an inclusive start to an exclusive
+/// end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    start: i64,
+    end: i64,
+}
 
-impl Display for TimeZoneClause {
+impl TimeRange {
+    /// Returns the range from `start` to `end`, which is empty if `start`
+    /// is not less than `end`.
+    pub fn new(start: i64, end: i64) -> Self {
+        Self {
+            start: start.min(end),
+            end,
+        }
+    }
+
+    /// Returns the inclusive start of the range.
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// Returns the exclusive end of the range.
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+}
+
+#[cfg(feature = "data_types")]
+impl From<TimeRange> for data_types::TimestampRange {
+    fn from(range: TimeRange) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+#[cfg(feature = "data_types")]
+impl From<data_types::TimestampRange> for TimeRange {
+    fn from(range: data_types::TimestampRange) -> Self {
+        Self::new(range.start(), range.end())
+    }
+}
+
+/// The maximum number of windows returned by [`SelectStatement::group_windows`].
+pub const MAX_GROUP_WINDOWS: usize = 100_000;
+
+/// An error returned when the interval of a `GROUP BY TIME` dimension cannot
+/// be evaluated to a constant duration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntervalError(&'static str);
+
+impl Display for IntervalError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.write_str("TZ('")?;
-        write_escaped!(f, self.0, '\n' => "\\n", '\\' => "\\\\", '\'' => "\\'", '"' => "\\\"");
-        f.write_str("')")
+        f.write_str(self.0)
     }
 }
 
-/// Parse a timezone clause.
-///
-/// ```text
-/// timezone_clause ::= "TZ" "(" single_quoted_string ")"
-/// ```
-fn timezone_clause(i: &str) -> ParseResult<&str, TimeZoneClause> {
-    preceded(
-        keyword("TZ"),
-        delimited(
-            preceded(multispace0, char('(')),
-            expect(
-                "invalid TZ clause, expected string",
-                preceded(multispace0, map(single_quoted_string, TimeZoneClause)),
+impl std::error::Error for IntervalError {}
+
+/// An error returned by [`SelectStatement::validate_regexes`] when a regular
+/// expression of a statement fails to compile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidRegex {
+    /// The clause in which the regular expression appears, such as
+    /// `WHERE clause`.
+    pub clause: &'static str,
+
+    /// The regular expression that failed to compile.
+    pub regex: Regex,
+
+    /// The reason the regular expression failed to compile.
+    pub source: regex::Error,
+}
+
+impl Display for InvalidRegex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid regular expression {} in {}: {}",
+            self.regex, self.clause, self.source
+        )
+    }
+}
+
+impl std::error::Error for InvalidRegex {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// The result of evaluating a `GROUP BY TIME` interval expression.
+#[derive(Debug, Clone, Copy)]
+enum IntervalValue {
+    Duration(Duration),
+    Unsigned(u64),
+}
+
+/// Evaluates the constant expression of a `GROUP BY TIME` interval.
+fn eval_interval(expr: &Expr) -> Result<IntervalValue, IntervalError> {
+    use IntervalValue::*;
+
+    const OVERFLOW: IntervalError = IntervalError("GROUP BY TIME interval overflows");
+    let unsigned = |v: u64| i64::try_from(v).map_err(|_| OVERFLOW);
+
+    Ok(match expr {
+        Expr::Literal(Literal::Duration(v)) => Duration(*v),
+        Expr::Literal(Literal::Unsigned(v)) => Unsigned(*v),
+        Expr::Nested(expr) => eval_interval(expr)?,
+        Expr::UnaryOp(UnaryOperator::Plus, expr) => eval_interval(expr)?,
+        Expr::UnaryOp(UnaryOperator::Minus, expr) => match eval_interval(expr)? {
+            Duration(v) => Duration(v.checked_mul(-1).ok_or(OVERFLOW)?),
+            Unsigned(_) => {
+                return Err(IntervalError(
+                    "GROUP BY TIME interval cannot negate an integer",
+                ))
+            }
+        },
+        Expr::Binary { lhs, op, rhs } => match (eval_interval(lhs)?, op, eval_interval(rhs)?) {
+            (Duration(l), BinaryOperator::Add, Duration(r)) => {
+                Duration(l.checked_add(r).ok_or(OVERFLOW)?)
+            }
+            (Duration(l), BinaryOperator::Sub, Duration(r)) => {
+                Duration(l.checked_sub(r).ok_or(OVERFLOW)?)
+            }
+            (Duration(d), BinaryOperator::Mul, Unsigned(v))
+            | (Unsigned(v), BinaryOperator::Mul, Duration(d)) => {
+                Duration(d.checked_mul(unsigned(v)?).ok_or(OVERFLOW)?)
+            }
+            (Duration(d), BinaryOperator::Div, Unsigned(v)) => Duration(
+                d.checked_div(unsigned(v)?)
+                    .ok_or(IntervalError("GROUP BY TIME interval divides by zero"))?,
             ),
-            preceded(multispace0, char(')')),
-        ),
-    )(i)
+            (Unsigned(l), BinaryOperator::Add, Unsigned(r)) => {
+                Unsigned(l.checked_add(r).ok_or(OVERFLOW)?)
+            }
+            (Unsigned(l), BinaryOperator::Sub, Unsigned(r)) => {
+                Unsigned(l.checked_sub(r).ok_or(OVERFLOW)?)
+            }
+            (Unsigned(l), BinaryOperator::Mul, Unsigned(r)) => {
+                Unsigned(l.checked_mul(r).ok_or(OVERFLOW)?)
+            }
+            _ => {
+                return Err(IntervalError(
+                    "GROUP BY TIME interval must be a constant duration",
+                ))
+            }
+        },
+        _ => {
+            return Err(IntervalError(
+                "GROUP BY TIME interval must be a constant duration",
+            ))
+        }
+    })
+}
+
+/// The severity of a [`ValidationIssue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationSeverity {
+    /// The statement is questionable but may still be executed.
+    Warning,
+
+    /// The statement must be rejected.
+    Error,
+}
+
+/// A problem found by validating a statement after it was parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// The severity of the issue.
+    pub severity: ValidationSeverity,
+
+    /// A description of the issue.
+    pub message: &'static str,
+}
+
+/// Renders an indented outline of a [`SelectStatement`].
+#[derive(Default)]
+struct DebugTree {
+    out: String,
+    depth: usize,
+}
+
+impl DebugTree {
+    fn line(&mut self, label: impl Display) {
+        writeln!(self.out, "{:indent$}{}", "", label, indent = self.depth * 2)
+            .expect("writing to a String is infallible");
+    }
+
+    fn nested(&mut self, label: impl Display, children: impl FnOnce(&mut Self)) {
+        self.line(label);
+        self.depth += 1;
+        children(self);
+        self.depth -= 1;
+    }
+
+    fn select_statement(&mut self, n: &SelectStatement) {
+        self.nested("SelectStatement", |t| {
+            t.nested("Fields", |t| {
+                for field in &n.fields {
+                    match &field.alias {
+                        Some(alias) => {
+                            t.nested(format_args!("Field AS {}", alias), |t| t.expr(&field.expr))
+                        }
+                        None => t.nested("Field", |t| t.expr(&field.expr)),
+                    }
+                }
+            });
+
+            t.nested("From", |t| {
+                for selection in &n.from {
+                    match selection {
+                        MeasurementSelection::Name(name) => {
+                            t.line(format_args!("Measurement {}", name))
+                        }
+                        Subquery(stmt) => t.nested("Subquery", |t| t.select_statement(stmt)),
+                    }
+                }
+            });
+
+            if let Some(condition) = &n.condition {
+                t.nested("Where", |t| t.conditional_expression(condition));
+            }
+
+            if let Some(group_by) = &n.group_by {
+                t.nested("GroupBy", |t| {
+                    for dimension in group_by {
+                        match dimension {
+                            Dimension::Time { interval, offset } => t.nested("Time", |t| {
+                                t.expr(interval);
+                                if let Some(offset) = offset {
+                                    t.expr(offset);
+                                }
+                            }),
+                            Dimension::Tag(name) => t.line(format_args!("Tag {}", name)),
+                            Dimension::Regex(re) => t.line(format_args!("Regex {}", re)),
+                            Dimension::Wildcard => t.line("Wildcard"),
+                        }
+                    }
+                });
+            }
+
+            if let Some(fill) = &n.fill {
+                t.line(fill);
+            }
+
+            if let Some(order_by) = &n.order_by {
+                t.line(order_by);
+            }
+
+            if let Some(limit) = &n.limit {
+                t.line(limit);
+            }
+
+            if let Some(offset) = &n.offset {
+                t.line(offset);
+            }
+
+            if let Some(series_limit) = &n.series_limit {
+                t.line(series_limit);
+            }
+
+            if let Some(series_offset) = &n.series_offset {
+                t.line(series_offset);
+            }
+
+            if let Some(timezone) = &n.timezone {
+                t.line(timezone);
+            }
+        });
+    }
+
+    fn conditional_expression(&mut self, n: &ConditionalExpression) {
+        match n {
+            ConditionalExpression::Expr(expr) => self.expr(expr),
+            ConditionalExpression::Binary { lhs, op, rhs } => {
+                self.nested(format_args!("ConditionalBinary {}", op), |t| {
+                    t.conditional_expression(lhs);
+                    t.conditional_expression(rhs);
+                })
+            }
+            ConditionalExpression::Grouped(expr) => {
+                self.nested("Grouped", |t| t.conditional_expression(expr))
+            }
+        }
+    }
+
+    fn expr(&mut self, n: &Expr) {
+        match n {
+            Expr::VarRef { .. } => self.line(format_args!("VarRef {}", n)),
+            Expr::BindParameter(_) => self.line(format_args!("BindParameter {}", n)),
+            Expr::Literal(_) => self.line(format_args!("Literal {}", n)),
+            Expr::Wildcard(_) | Expr::QualifiedWildcard { .. } => {
+                self.line(format_args!("Wildcard {}", n))
+            }
+            Expr::Distinct(_) => self.line(format_args!("Distinct {}", n)),
+            Expr::UnaryOp(op, expr) => self.nested(format_args!("Unary {}", op), |t| t.expr(expr)),
+            Expr::Call { name, args } => self.nested(format_args!("Call {}", name), |t| {
+                for arg in args {
+                    t.expr(arg);
+                }
+            }),
+            Expr::Binary { lhs, op, rhs } => self.nested(format_args!("Binary {}", op), |t| {
+                t.expr(lhs);
+                t.expr(rhs);
+            }),
+            Expr::Nested(expr) => self.nested("Nested", |t| t.expr(expr)),
+        }
+    }
+}
+
+/// Collects the tag keys referenced by a [`SelectStatement`].
+#[derive(Default)]
+struct TagKeysVisitor(BTreeSet<Identifier>);
+
+impl TagKeysVisitor {
+    fn insert(mut self, name: &Identifier) -> Self {
+        if !name.eq_ignore_ascii_case("time") {
+            self.0.insert(name.clone());
+        }
+        self
+    }
+}
+
+impl Visitor for TagKeysVisitor {
+    fn pre_visit_select_dimension(self, n: &Dimension) -> VisitorResult<Recursion<Self>> {
+        Ok(Recursion::Continue(match n {
+            Dimension::Tag(name) => self.insert(name),
+            _ => self,
+        }))
+    }
+
+    fn pre_visit_conditional_expression(
+        self,
+        n: &ConditionalExpression,
+    ) -> VisitorResult<Recursion<Self>> {
+        use ConditionalOperator::*;
+
+        let tag_key = |lhs: &ConditionalExpression, rhs: &ConditionalExpression| match (lhs, rhs) {
+            (ConditionalExpression::Expr(lhs), ConditionalExpression::Expr(rhs)) => {
+                match (lhs.as_ref(), rhs.as_ref()) {
+                    (
+                        Expr::VarRef {
+                            name,
+                            data_type: None | Some(VarRefDataType::Tag),
+                        },
+                        Expr::Literal(Literal::String(_) | Literal::Regex(_)),
+                    ) => Some(name.clone()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        Ok(Recursion::Continue(match n {
+            ConditionalExpression::Binary {
+                lhs,
+                op: Eq | NotEq | EqRegex | NotEqRegex,
+                rhs,
+            } => match tag_key(lhs, rhs).or_else(|| tag_key(rhs, lhs)) {
+                Some(name) => self.insert(&name),
+                None => self,
+            },
+            _ => self,
+        }))
+    }
+
+    fn pre_visit_expr(self, n: &Expr) -> VisitorResult<Recursion<Self>> {
+        Ok(Recursion::Continue(match n {
+            Expr::VarRef {
+                name,
+                data_type: Some(VarRefDataType::Tag),
+            } => self.insert(name),
+            _ => self,
+        }))
+    }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::{assert_expect_error, binary_op, call, distinct, regex, var_ref, wildcard};
-    use assert_matches::assert_matches;
+pub(crate) fn select_statement(i: &str) -> ParseResult<&str, SelectStatement> {
+    let (
+        remaining,
+        (
+            _, // SELECT
+            _, // whitespace
+            fields,
+            into,
+            from,
+            condition,
+            (group_by, fill),
+            order_by,
+            limit,
+            offset,
+            series_limit,
+            series_offset,
+            timezone,
+        ),
+    ) = tuple((
+        keyword("SELECT"),
+        multispace0,
+        field_list,
+        opt(preceded(multispace0, into_clause)),
+        preceded(multispace0, from_clause),
+        opt(preceded(multispace0, where_clause)),
+        group_by_and_fill_clauses,
+        opt(preceded(multispace0, order_by_clause)),
+        opt(preceded(multispace0, limit_clause)),
+        opt(preceded(multispace0, offset_clause)),
+        opt(preceded(multispace0, slimit_clause)),
+        opt(preceded(multispace0, soffset_clause)),
+        opt(preceded(multispace0, timezone_clause)),
+    ))(i)?;
+
+    Ok((
+        remaining,
+        SelectStatement {
+            fields,
+            into,
+            from,
+            condition,
+            group_by,
+            fill,
+            order_by,
+            limit,
+            offset,
+            series_limit,
+            series_offset,
+            timezone,
+        },
+    ))
+}
+
+/// Parse the optional `GROUP BY` and `FILL` clauses of a `SELECT` statement.
+///
+/// The `FILL` clause may appear either before or after the `GROUP BY` clause,
+/// but not both. In either case, it is displayed after the `GROUP BY` clause.
+fn group_by_and_fill_clauses(
+    i: &str,
+) -> ParseResult<&str, (Option<GroupByClause>, Option<FillClause>)> {
+    map_fail(
+        "invalid SELECT statement, FILL specified more than once",
+        tuple((
+            opt(preceded(multispace0, fill_clause)),
+            opt(preceded(multispace0, group_by_clause)),
+            opt(preceded(multispace0, fill_clause)),
+        )),
+        |(fill_before, group_by, fill_after)| match (fill_before, fill_after) {
+            (Some(_), Some(_)) => Err(()),
+            (fill_before, fill_after) => Ok((group_by, fill_before.or(fill_after))),
+        },
+    )(i)
+}
+
+/// The measurement written to by an `INTO` clause.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntoMeasurement {
+    /// The results are written to the named measurement.
+    Name(Identifier),
+
+    /// The results are written to measurements of the same name as those they
+    /// were selected from, specified as `:MEASUREMENT`.
+    Backreference,
+}
+
+impl Display for IntoMeasurement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => fmt::Display::fmt(name, f),
+            Self::Backreference => f.write_str(":MEASUREMENT"),
+        }
+    }
+}
+
+/// Represents an `INTO` clause of a `SELECT` statement, which specifies the
+/// fully-qualified measurement the results of the selection are written to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntoClause {
+    /// An optional database name.
+    pub database: Option<Identifier>,
+
+    /// An optional retention policy.
+    pub retention_policy: Option<Identifier>,
+
+    /// The measurement the results are written to.
+    pub measurement: IntoMeasurement,
+}
+
+impl Display for IntoClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("INTO ")?;
+        match (&self.database, &self.retention_policy) {
+            (None, None) => {}
+            (Some(db), None) => write!(f, "{}..", db)?,
+            (None, Some(rp)) => write!(f, "{}.", rp)?,
+            (Some(db), Some(rp)) => write!(f, "{}.{}.", db, rp)?,
+        }
+        write!(f, "{}", self.measurement)
+    }
+}
+
+/// Parse an `INTO` clause.
+///
+/// ```text
+/// into_clause      ::= "INTO" ( into_measurement |
+///                               ( policy_name "." into_measurement ) |
+///                               ( db_name "." policy_name? "." into_measurement ) )
+///
+/// into_measurement ::= identifier | ":MEASUREMENT"
+/// ```
+fn into_clause(i: &str) -> ParseResult<&str, IntoClause> {
+    let measurement = || {
+        alt((
+            value(
+                IntoMeasurement::Backreference,
+                preceded(char(':'), keyword("MEASUREMENT")),
+            ),
+            map(identifier, IntoMeasurement::Name),
+        ))
+    };
+
+    preceded(
+        pair(keyword("INTO"), multispace1),
+        expect(
+            "invalid INTO clause, expected measurement name or :MEASUREMENT",
+            alt((
+                // database "." retention_policy "." measurement
+                map(
+                    tuple((
+                        terminated(identifier, char('.')),
+                        terminated(identifier, char('.')),
+                        measurement(),
+                    )),
+                    |(db, rp, measurement)| IntoClause {
+                        database: Some(db),
+                        retention_policy: Some(rp),
+                        measurement,
+                    },
+                ),
+                // database ".." measurement
+                map(
+                    pair(terminated(identifier, tag("..")), measurement()),
+                    |(db, measurement)| IntoClause {
+                        database: Some(db),
+                        retention_policy: None,
+                        measurement,
+                    },
+                ),
+                // retention_policy "." measurement
+                map(
+                    pair(terminated(identifier, char('.')), measurement()),
+                    |(rp, measurement)| IntoClause {
+                        database: None,
+                        retention_policy: Some(rp),
+                        measurement,
+                    },
+                ),
+                map(measurement(), |measurement| IntoClause {
+                    database: None,
+                    retention_policy: None,
+                    measurement,
+                }),
+            )),
+        ),
+    )(i)
+}
+
+/// The measurements referenced by a [`SelectStatement`], as returned by
+/// [`SelectStatement::measurements`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Measurements {
+    /// The measurements referenced by name, such as `cpu` or `db.rp.cpu`.
+    pub names: Vec<QualifiedMeasurementName>,
+
+    /// The measurements referenced by a regular expression, such as `/^cpu/`.
+    pub regexes: Vec<QualifiedMeasurementName>,
+}
+
+/// Represents a single measurement selection for a `FROM` clause.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MeasurementSelection {
+    /// The measurement selection is measurement name or regular expression.
+    Name(QualifiedMeasurementName),
+
+    /// The measurement selection is a subquery.
+    Subquery(Box<SelectStatement>),
+}
+
+impl Display for MeasurementSelection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(ref name) => fmt::Display::fmt(name, f),
+            Self::Subquery(ref subquery) => write!(f, "({})", subquery),
+        }
+    }
+}
+
+impl Parser for MeasurementSelection {
+    fn parse(i: &str) -> ParseResult<&str, Self> {
+        alt((
+            map(qualified_measurement_name, MeasurementSelection::Name),
+            map(
+                delimited(
+                    preceded(multispace0, char('(')),
+                    subquery(
+                        "subquery nesting too deep",
+                        preceded(multispace0, select_statement),
+                    ),
+                    preceded(multispace0, char(')')),
+                ),
+                |s| Subquery(Box::new(s)),
+            ),
+        ))(i)
+    }
+}
+
+/// Represents a `FROM` clause for a `SELECT` statement.
+pub type FromMeasurementClause = OneOrMore<MeasurementSelection>;
+
+impl Display for FromMeasurementClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "FROM {}", self.first())?;
+        for arg in self.rest() {
+            write!(f, ", {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
+fn from_clause(i: &str) -> ParseResult<&str, FromMeasurementClause> {
+    preceded(
+        tuple((keyword("FROM"), multispace0, opt(negated_source))),
+        FromMeasurementClause::separated_list1(
+            "invalid FROM clause, expected identifier, regular expression or subquery",
+        ),
+    )(i)
+}
+
+/// Fails with a targeted error for a `FROM` clause that attempts to negate its
+/// sources, such as `FROM !~ /cpu/` or `FROM NOT /cpu/`, which InfluxQL does not
+/// support.
+fn negated_source(i: &str) -> ParseResult<&str, ()> {
+    alt((tag("!"), keyword("NOT")))(i)?;
+    Err(nom::Err::Failure(InternalError::from_message(
+        i,
+        "regular expression sources cannot be negated; use WHERE instead",
+    )))
+}
+
+/// Represents the collection of dimensions for a `GROUP BY` clause.
+pub type GroupByClause = OneOrMore<Dimension>;
+
+impl GroupByClause {
+    /// Returns the dimensions of `self` followed by those of `other`, omitting any
+    /// duplicate dimensions.
+    ///
+    /// A `GROUP BY` clause permits at most one `TIME` dimension. If both `self` and
+    /// `other` specify a `TIME` dimension, the dimension of `self` is preferred and
+    /// that of `other` is discarded.
+    pub fn merge(self, other: Self) -> Self {
+        let mut contents = self.contents;
+        for dimension in other.contents {
+            let is_duplicate = contents.iter().any(|d| match (d, &dimension) {
+                (Dimension::Time { .. }, Dimension::Time { .. }) => true,
+                (d, dimension) => d == dimension,
+            });
+            if !is_duplicate {
+                contents.push(dimension);
+            }
+        }
+        Self::new(contents)
+    }
+}
+
+impl Display for GroupByClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "GROUP BY {}", self.first())?;
+        for arg in self.rest() {
+            write!(f, ", {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
+/// Used to parse the interval argument of the TIME function
+struct TimeCallIntervalArgument;
+
+impl ArithmeticParsers for TimeCallIntervalArgument {
+    fn operand(i: &str) -> ParseResult<&str, Expr> {
+        // Any literal
+        preceded(
+            multispace0,
+            map(
+                alt((
+                    map(duration, Literal::Duration),
+                    map(unsigned_integer, Literal::Unsigned),
+                )),
+                Expr::Literal,
+            ),
+        )(i)
+    }
+}
+
+/// Used to parse the offset argument of the TIME function
+///
+/// The offset argument accepts either a duration, datetime-like string or `now`,
+/// or arithmetic combining them, such as `now() - 1m`.
+struct TimeCallOffsetArgument;
+
+impl TimeCallOffsetArgument {
+    /// Parse the `now()` function call
+    fn now_call(i: &str) -> ParseResult<&str, Expr> {
+        verify(
+            "invalid expression, the only valid function call is 'now' with no arguments",
+            call_expression::<Self>,
+            is_valid_now_call,
+        )(i)
+    }
+}
+
+impl ArithmeticParsers for TimeCallOffsetArgument {
+    fn operand(i: &str) -> ParseResult<&str, Expr> {
+        preceded(
+            multispace0,
+            alt((
+                Self::now_call,
+                map(duration, |v| Expr::Literal(Literal::Duration(v))),
+                map(single_quoted_string, |v| Expr::Literal(Literal::String(v))),
+            )),
+        )(i)
+    }
+}
+
+/// Represents a dimension of a `GROUP BY` clause.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dimension {
+    /// Represents a `TIME` call in a `GROUP BY` clause.
+    Time {
+        /// The first argument of the `TIME` call.
+        interval: Expr,
+        /// An optional second argument to specify the offset applied to the `TIME` call.
+        offset: Option<Expr>,
+    },
+
+    /// Represents a literal tag reference in a `GROUP BY` clause.
+    Tag(Identifier),
+
+    /// Represents a regular expression in a `GROUP BY` clause.
+    Regex(Regex),
+
+    /// Represents a wildcard in a `GROUP BY` clause.
+    Wildcard,
+}
+
+impl Dimension {
+    /// Returns the interval of a `TIME` dimension in nanoseconds.
+    ///
+    /// The interval may be an arithmetic expression of durations and integers,
+    /// such as `5m + 3s` or `5m * 10`, which is evaluated to a constant. An
+    /// error is returned if the expression does not evaluate to a positive
+    /// duration, or if this is not a `TIME` dimension.
+    pub fn time_interval_nanos(&self) -> Result<i64, IntervalError> {
+        match self {
+            Self::Time { interval, .. } => match eval_interval(interval)? {
+                IntervalValue::Duration(v) if *v > 0 => Ok(*v),
+                IntervalValue::Duration(_) => {
+                    Err(IntervalError("GROUP BY TIME interval must be positive"))
+                }
+                IntervalValue::Unsigned(_) => {
+                    Err(IntervalError("GROUP BY TIME interval must be a duration"))
+                }
+            },
+            _ => Err(IntervalError("dimension is not a TIME dimension")),
+        }
+    }
+
+    /// Returns the offset of a `TIME` dimension in nanoseconds, or `None` if
+    /// it has no offset argument.
+    ///
+    /// As with [`Self::time_interval_nanos`], the offset is evaluated to a
+    /// constant duration. An error is returned if it does not evaluate to a
+    /// duration, such as an offset of `now()`, or if this is not a `TIME`
+    /// dimension.
+    pub fn time_offset_nanos(&self) -> Result<Option<i64>, IntervalError> {
+        match self {
+            Self::Time { offset: None, .. } => Ok(None),
+            Self::Time {
+                offset: Some(offset),
+                ..
+            } => match eval_interval(offset) {
+                Ok(IntervalValue::Duration(v)) => Ok(Some(*v)),
+                _ => Err(IntervalError(
+                    "GROUP BY TIME offset must be a constant duration",
+                )),
+            },
+            _ => Err(IntervalError("dimension is not a TIME dimension")),
+        }
+    }
+}
+
+impl Display for Dimension {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Time {
+                interval,
+                offset: Some(offset),
+            } => write!(f, "TIME({}, {})", interval, offset),
+            Self::Time { interval, .. } => write!(f, "TIME({})", interval),
+            Self::Tag(v) => Display::fmt(v, f),
+            Self::Regex(v) => Display::fmt(v, f),
+            Self::Wildcard => f.write_char('*'),
+        }
+    }
+}
+
+impl Parser for Dimension {
+    fn parse(i: &str) -> ParseResult<&str, Self> {
+        alt((
+            // Explicitly ignore the `WildCardType`, is InfluxQL always assumes `*::tag`
+            map(wildcard, |_| Self::Wildcard),
+            time_call_expression,
+            map(regex, Self::Regex),
+            map(var_ref, |v| {
+                Self::Tag(match v {
+                    Expr::VarRef { name, .. } => name,
+                    // var_ref only returns Expr::VarRef
+                    _ => unreachable!(),
+                })
+            }),
+        ))(i)
+    }
+}
+
+fn time_call_expression(i: &str) -> ParseResult<&str, Dimension> {
+    map(
+        preceded(
+            keyword("TIME"),
+            delimited(
+                expect(
+                    "invalid TIME call, expected 1 or 2 arguments",
+                    preceded(multispace0, char('(')),
+                ),
+                pair(
+                    expect(
+                        "invalid TIME call, expected a duration for the interval",
+                        arithmetic::<TimeCallIntervalArgument>,
+                    ),
+                    opt(preceded(
+                        preceded(multispace0, char(',')),
+                        preceded(multispace0, arithmetic::<TimeCallOffsetArgument>),
+                    )),
+                ),
+                expect(
+                    "invalid TIME call, expected ')'",
+                    preceded(multispace0, char(')')),
+                ),
+            ),
+        ),
+        |(interval, offset)| Dimension::Time { interval, offset },
+    )(i)
+}
+
+/// Parse a `GROUP BY` clause.
+///
+/// ```text
+/// group_by_clause ::= dimension ( "," dimension )*
+/// ```
+///
+/// At most one `TIME` dimension is permitted.
+fn group_by_clause(i: &str) -> ParseResult<&str, GroupByClause> {
+    preceded(
+        tuple((
+            keyword("GROUP"),
+            multispace1,
+            expect("invalid GROUP BY clause, expected BY", keyword("BY")),
+            multispace1,
+        )),
+        verify(
+            "invalid GROUP BY clause, multiple TIME dimensions are not permitted",
+            GroupByClause::separated_list1(
+                "invalid GROUP BY clause, expected wildcard, TIME, identifier or regular expression",
+            ),
+            |v: &GroupByClause| {
+                v.iter()
+                    .filter(|d| matches!(d, Dimension::Time { .. }))
+                    .count()
+                    <= 1
+            },
+        ),
+    )(i)
+}
+
+/// Represents a `FILL` clause, and specifies all possible cases of the argument to the `FILL` clause.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FillClause {
+    /// Empty aggregate windows will contain null values and is specified as `fill(null)`
+    Null,
+
+    /// Empty aggregate windows will be discarded and is specified as `fill(none)`.
+    None,
+
+    /// Empty aggregate windows will be filled with the specified numerical value and is specified as
+    /// `fill(<value>)`
+    Value(Number),
+
+    /// Empty aggregate windows will be filled with the value from the previous aggregate window
+    /// and is specified as `fill(previous)`
+    Previous,
+
+    /// Empty aggregate windows will be filled with a value that is the linear interpolation of
+    /// the prior two non-null window values and is specified as `fill(linear)`
+    Linear,
+}
+
+impl Display for FillClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("FILL(")?;
+        match self {
+            Self::Null => f.write_str("NULL")?,
+            Self::None => f.write_str("NONE")?,
+            Self::Value(v) => fmt::Display::fmt(v, f)?,
+            Self::Previous => f.write_str("PREVIOUS")?,
+            Self::Linear => f.write_str("LINEAR")?,
+        }
+        f.write_str(")")
+    }
+}
+
+/// Represents an expression specified in the projection list of a `SELECT` statement.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Field {
+    /// The expression which represents the field projection.
+    pub expr: Expr,
+
+    /// An optional alias for the field projection.
+    pub alias: Option<Identifier>,
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.expr, f)?;
+        if let Some(alias) = &self.alias {
+            write!(f, " AS {}", alias)?;
+        }
+        Ok(())
+    }
+}
+
+impl Parser for Field {
+    /// Parse a field expression that appears in the projection list of a `SELECT` clause.
+    ///
+    /// ```text
+    /// field ::= field_expression ( "AS" identifier )?
+    /// ```
+    fn parse(i: &str) -> ParseResult<&str, Self> {
+        map(
+            pair(
+                arithmetic::<FieldExpression>,
+                opt(preceded(
+                    delimited(multispace0, keyword("AS"), multispace1),
+                    expect("invalid field alias, expected identifier", identifier),
+                )),
+            ),
+            |(expr, alias)| Self { expr, alias },
+        )(i)
+    }
+}
+
+/// Parse a wildcard expression.
+///
+/// wildcard ::= "*" ( "::" ("field" | "tag")?
+fn wildcard(i: &str) -> ParseResult<&str, Option<WildcardType>> {
+    preceded(
+        char('*'),
+        opt(preceded(
+            tag("::"),
+            expect(
+                "invalid wildcard type specifier, expected TAG or FIELD",
+                alt((
+                    value(WildcardType::Tag, keyword("TAG")),
+                    value(WildcardType::Field, keyword("FIELD")),
+                )),
+            ),
+        )),
+    )(i)
+}
+
+/// Represents the field projection list of a `SELECT` statement.
+pub type FieldList = OneOrMore<Field>;
+
+impl Display for FieldList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.first(), f)?;
+        for arg in self.rest() {
+            write!(f, ", {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a field expression.
+///
+/// A field expression is an arithmetic expression accepting
+/// a specific set of operands.
+struct FieldExpression;
+
+impl ArithmeticParsers for FieldExpression {
+    fn operand(i: &str) -> ParseResult<&str, Expr> {
+        preceded(
+            multispace0,
+            alt((
+                // DISTINCT identifier
+                map(
+                    preceded(
+                        pair(keyword("DISTINCT"), multispace1),
+                        expect(
+                            "invalid DISTINCT expression, expected identifier",
+                            identifier,
+                        ),
+                    ),
+                    Expr::Distinct,
+                ),
+                // *
+                map(wildcard, Wildcard),
+                // measurement.*
+                map(
+                    pair(terminated(identifier, char('.')), wildcard),
+                    |(measurement, data_type)| Expr::QualifiedWildcard {
+                        measurement,
+                        data_type,
+                    },
+                ),
+                // Any literal
+                map(literal, Expr::Literal),
+                // A call expression
+                field_call_expression,
+                // A tag or field reference
+                var_ref,
+                // A bind parameter
+                map(parameter, Expr::BindParameter),
+            )),
+        )(i)
+    }
+}
+
+/// The scalar math functions of the field list, with the minimum and maximum
+/// number of arguments of each, and the error returned for any other number.
+const MATH_FUNCTIONS: &[(&str, usize, usize, &str)] = &[
+    ("ABS", 1, 1, "invalid ABS function, expected 1 argument"),
+    ("CEIL", 1, 1, "invalid CEIL function, expected 1 argument"),
+    ("FLOOR", 1, 1, "invalid FLOOR function, expected 1 argument"),
+    (
+        "ROUND",
+        1,
+        2,
+        "invalid ROUND function, expected 1 or 2 arguments",
+    ),
+];
+
+/// Parse a call expression of the field list, verifying the number of arguments
+/// of any of the [`MATH_FUNCTIONS`].
+fn field_call_expression(i: &str) -> ParseResult<&str, Expr> {
+    let (remaining, expr) = call_expression::<FieldExpression>(i)?;
+
+    if let Expr::Call { name, args } = &expr {
+        if let Some((_, min, max, message)) = MATH_FUNCTIONS
+            .iter()
+            .find(|(f, ..)| f.eq_ignore_ascii_case(name))
+        {
+            if args.len() < *min || args.len() > *max {
+                return Err(nom::Err::Failure(InternalError::from_message(i, *message)));
+            }
+        }
+    }
+
+    Ok((remaining, expr))
+}
+
+/// Parse the projection list of a `SELECT` statement.
+///
+/// ```text
+/// field_list ::= field ( "," field )*
+/// ```
+///
+/// The number of fields is limited by [`ParseLimits::max_fields`].
+///
+/// [`ParseLimits::max_fields`]: crate::ParseLimits::max_fields
+fn field_list(i: &str) -> ParseResult<&str, FieldList> {
+    verify(
+        "too many fields in SELECT statement",
+        FieldList::separated_list1("invalid SELECT statement, expected field"),
+        |fields: &FieldList| fields.len() <= limits().max_fields,
+    )(i)
+}
+
+/// Parse a `FILL(option)` clause.
+///
+/// ```text
+/// fill_clause ::= "FILL" "(" fill_option ")"
+/// fill_option ::= "NULL" | "NONE" | "PREVIOUS" | "LINEAR" | number
+/// number      ::= signed_integer | signed_float
+/// ```
+fn fill_clause(i: &str) -> ParseResult<&str, FillClause> {
+    preceded(
+        keyword("FILL"),
+        delimited(
+            preceded(multispace0, char('(')),
+            expect(
+                "invalid FILL option, expected NULL, NONE, PREVIOUS, LINEAR, or a number",
+                preceded(
+                    multispace0,
+                    alt((
+                        value(FillClause::Null, keyword("NULL")),
+                        value(FillClause::None, keyword("NONE")),
+                        map(number, FillClause::Value),
+                        value(FillClause::Previous, keyword("PREVIOUS")),
+                        value(FillClause::Linear, keyword("LINEAR")),
+                    )),
+                ),
+            ),
+            preceded(multispace0, char(')')),
+        ),
+    )(i)
+}
+
+/// Represents the value for a `SLIMIT` clause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SLimitClause(pub(crate) u64);
+
+impl_tuple_clause!(SLimitClause, u64);
+
+impl Display for SLimitClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SLIMIT {}", self.0)
+    }
+}
+
+/// Parse a series limit (`SLIMIT <n>`) clause.
+///
+/// ```text
+/// slimit_clause ::= "SLIMIT" unsigned_integer
+/// ```
+fn slimit_clause(i: &str) -> ParseResult<&str, SLimitClause> {
+    preceded(
+        pair(keyword("SLIMIT"), multispace1),
+        expect(
+            "invalid SLIMIT clause, expected unsigned integer",
+            map(unsigned_integer, SLimitClause),
+        ),
+    )(i)
+}
+
+/// Represents the value for a `SOFFSET` clause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SOffsetClause(pub(crate) u64);
+
+impl_tuple_clause!(SOffsetClause, u64);
+
+impl Display for SOffsetClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SOFFSET {}", self.0)
+    }
+}
+
+/// Parse a series offset (`SOFFSET <n>`) clause.
+///
+/// ```text
+/// soffset_clause ::= "SOFFSET" unsigned_integer
+/// ```
+fn soffset_clause(i: &str) -> ParseResult<&str, SOffsetClause> {
+    preceded(
+        pair(keyword("SOFFSET"), multispace1),
+        expect(
+            "invalid SLIMIT clause, expected unsigned integer",
+            map(unsigned_integer, SOffsetClause),
+        ),
+    )(i)
+}
+
+/// Represents the value of the time zone string of a `TZ` clause.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeZoneClause(pub(crate) String);
+
+impl_tuple_clause!(TimeZoneClause, String);
+
+impl Display for TimeZoneClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("TZ('")?;
+        write_escaped!(f, self.0, '\n' => "\\n", '\\' => "\\\\", '\'' => "\\'", '"' => "\\\"");
+        f.write_str("')")
+    }
+}
+
+/// Parse a timezone clause.
+///
+/// ```text
+/// timezone_clause ::= "TZ" "(" single_quoted_string ")"
+/// ```
+///
+/// The string must name a time zone of the tz database, such as `America/New_York`.
+fn timezone_clause(i: &str) -> ParseResult<&str, TimeZoneClause> {
+    preceded(
+        keyword("TZ"),
+        delimited(
+            preceded(multispace0, char('(')),
+            expect(
+                "invalid TZ clause, expected string",
+                preceded(multispace0, |i| {
+                    let (remaining, zone) = single_quoted_string(i)?;
+                    match Tz::from_str(&zone) {
+                        Ok(_) => Ok((remaining, TimeZoneClause(zone))),
+                        Err(_) => Err(nom::Err::Failure(InternalError::Syntax {
+                            input: i,
+                            message: format!("invalid TZ clause, unknown time zone '{}'", zone)
+                                .into(),
+                        })),
+                    }
+                }),
+            ),
+            preceded(multispace0, char(')')),
+        ),
+    )(i)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{assert_expect_error, binary_op, call, distinct, regex, var_ref, wildcard};
+    use assert_matches::assert_matches;
+
+    #[test]
+    fn test_into_clause() {
+        let (_, got) = into_clause("INTO cpu_copy").unwrap();
+        assert_eq!(
+            got,
+            IntoClause {
+                database: None,
+                retention_policy: None,
+                measurement: IntoMeasurement::Name("cpu_copy".into()),
+            }
+        );
+
+        let (_, got) = into_clause(r#"INTO "db"."rp".:MEASUREMENT"#).unwrap();
+        assert_eq!(
+            got,
+            IntoClause {
+                database: Some("db".into()),
+                retention_policy: Some("rp".into()),
+                measurement: IntoMeasurement::Backreference,
+            }
+        );
+        assert_eq!(got.to_string(), "INTO db.rp.:MEASUREMENT");
+
+        // Each form of qualification round-trips, quoting identifiers when required
+        for q in [
+            "INTO cpu",
+            "INTO rp.cpu",
+            "INTO db..cpu",
+            "INTO db.rp.cpu",
+            "INTO :MEASUREMENT",
+            "INTO rp.:MEASUREMENT",
+            "INTO db..:MEASUREMENT",
+            r#"INTO "my db"."1h".:MEASUREMENT"#,
+            r#"INTO "cpu-copy""#,
+        ] {
+            let (rem, got) = into_clause(q).unwrap();
+            assert_eq!(rem, "");
+            assert_eq!(got.to_string(), q);
+        }
+
+        // Fallible cases
+        assert_expect_error!(
+            into_clause("INTO FROM"),
+            "invalid INTO clause, expected measurement name or :MEASUREMENT"
+        );
+        assert_expect_error!(
+            into_clause("INTO :cpu"),
+            "invalid INTO clause, expected measurement name or :MEASUREMENT"
+        );
+    }
+
+    #[test]
+    fn test_select_statement_into() {
+        let (_, got) = select_statement(
+            r#"SELECT mean(usage) INTO "db"."rp".:MEASUREMENT FROM cpu, mem GROUP BY *"#,
+        )
+        .unwrap();
+        assert_eq!(
+            got.into,
+            Some(IntoClause {
+                database: Some("db".into()),
+                retention_policy: Some("rp".into()),
+                measurement: IntoMeasurement::Backreference,
+            })
+        );
+        assert_eq!(
+            got.to_string(),
+            "SELECT mean(usage) INTO db.rp.:MEASUREMENT FROM cpu, mem GROUP BY *"
+        );
+
+        let (_, got) = select_statement(r#"SELECT * INTO "cpu copy" FROM cpu"#).unwrap();
+        assert_eq!(got.to_string(), r#"SELECT * INTO "cpu copy" FROM cpu"#);
+
+        let (_, got) = select_statement("SELECT * FROM cpu").unwrap();
+        assert_eq!(got.into, None);
+    }
+
+    #[test]
+    fn test_select_statement() {
+        let (_, got) = select_statement("SELECT value FROM foo").unwrap();
+        assert_eq!(format!("{}", got), "SELECT value FROM foo");
+
+        let (_, got) =
+            select_statement(r#"SELECT f1,/f2/, f3 AS "a field" FROM foo WHERE host =~ /c1/"#)
+                .unwrap();
+        assert_eq!(
+            format!("{}", got),
+            r#"SELECT f1, /f2/, f3 AS "a field" FROM foo WHERE host =~ /c1/"#
+        );
+
+        // Floats in scientific notation
+        let (_, got) = select_statement("SELECT value FROM cpu WHERE x > 1.5e10").unwrap();
+        assert_eq!(
+            format!("{}", got),
+            "SELECT value FROM cpu WHERE x > 15000000000.0"
+        );
+        assert_expect_error!(
+            select_statement("SELECT value FROM cpu WHERE x > 1e400"),
+            "float literal out of range"
+        );
+
+        let (_, got) =
+            select_statement("SELECT sum(value) FROM foo GROUP BY time(5m), host").unwrap();
+        assert_eq!(
+            format!("{}", got),
+            r#"SELECT sum(value) FROM foo GROUP BY TIME(5m), host"#
+        );
+
+        // Parses TIME() with expressions
+        let (_, got) =
+            select_statement("SELECT sum(value) FROM foo GROUP BY time(5m * 10), host").unwrap();
+        assert_eq!(
+            format!("{}", got),
+            r#"SELECT sum(value) FROM foo GROUP BY TIME(5m * 10), host"#
+        );
+
+        // TIME only supports integer and duration literals
+        select_statement("SELECT sum(value) FROM foo GROUP BY time(5m + 'foo'), host").unwrap_err();
+        select_statement("SELECT sum(value) FROM foo GROUP BY time(5m + 5.4), host").unwrap_err();
+        select_statement("SELECT sum(value) FROM foo GROUP BY time(5m + true), host").unwrap_err();
+
+        let (_, got) =
+            select_statement("SELECT sum(value) FROM foo GROUP BY time(5m), host FILL(previous)")
+                .unwrap();
+        assert_eq!(
+            format!("{}", got),
+            r#"SELECT sum(value) FROM foo GROUP BY TIME(5m), host FILL(PREVIOUS)"#
+        );
+
+        let (_, got) = select_statement("SELECT value FROM foo ORDER BY DESC").unwrap();
+        assert_eq!(
+            format!("{}", got),
+            r#"SELECT value FROM foo ORDER BY TIME DESC"#
+        );
+
+        let (_, got) = select_statement("SELECT value FROM foo ORDER BY TIME ASC").unwrap();
+        assert_eq!(
+            format!("{}", got),
+            r#"SELECT value FROM foo ORDER BY TIME ASC"#
+        );
+
+        let (_, got) = select_statement("SELECT value FROM foo LIMIT 5").unwrap();
+        assert_eq!(format!("{}", got), r#"SELECT value FROM foo LIMIT 5"#);
+
+        let (_, got) = select_statement("SELECT value FROM foo OFFSET 20").unwrap();
+        assert_eq!(format!("{}", got), r#"SELECT value FROM foo OFFSET 20"#);
+
+        let (_, got) = select_statement("SELECT value FROM foo SLIMIT 25").unwrap();
+        assert_eq!(format!("{}", got), r#"SELECT value FROM foo SLIMIT 25"#);
+
+        let (_, got) = select_statement("SELECT value FROM foo SOFFSET 220").unwrap();
+        assert_eq!(format!("{}", got), r#"SELECT value FROM foo SOFFSET 220"#);
+
+        let (_, got) = select_statement("SELECT value FROM foo tz('Australia/Hobart')").unwrap();
+        assert_eq!(
+            format!("{}", got),
+            r#"SELECT value FROM foo TZ('Australia/Hobart')"#
+        );
+
+        // validate spacing between keywords
+
+        let (rem, _) = select_statement("SELECT value FROM(SELECT val FROM cpu)").unwrap();
+        assert_eq!(rem, "");
+
+        let (rem, _) = select_statement("SELECT (value)FROM cpu").unwrap();
+        assert_eq!(rem, "");
+
+        let (rem, _) =
+            select_statement("SELECT value FROM (SELECT val FROM cpu)WHERE 1=1").unwrap();
+        assert_eq!(rem, "");
+
+        let (rem, _) =
+            select_statement("SELECT value FROM cpu WHERE time <= now()FILL(previous)").unwrap();
+        assert_eq!(rem, "");
+
+        let (rem, _) =
+            select_statement("SELECT value FROM cpu WHERE time <= now()ORDER BY time").unwrap();
+        assert_eq!(rem, "");
+
+        let (rem, _) =
+            select_statement("SELECT value FROM cpu WHERE time <= now()LIMIT 10").unwrap();
+        assert_eq!(rem, "");
+
+        let (rem, _) =
+            select_statement("SELECT value FROM cpu WHERE time <= now()OFFSET 10").unwrap();
+        assert_eq!(rem, "");
+
+        let (rem, _) =
+            select_statement("SELECT value FROM cpu WHERE time <= now()SLIMIT 10").unwrap();
+        assert_eq!(rem, "");
+
+        let (rem, _) =
+            select_statement("SELECT value FROM cpu WHERE time <= now()SOFFSET 10").unwrap();
+        assert_eq!(rem, "");
+
+        let (rem, _) =
+            select_statement("SELECT value FROM cpu WHERE time <= now()TZ('Australia/Hobart')")
+                .unwrap();
+        assert_eq!(rem, "");
+    }
+
+    #[test]
+    fn test_function_names() {
+        let (_, got) = select_statement(
+            "SELECT MEAN(derivative(value)), max(usage) FROM (SELECT Mean(idle) AS value FROM cpu) \
+             WHERE time > now() - 1h GROUP BY time(1m)",
+        )
+        .unwrap();
+        assert_eq!(
+            got.function_names(),
+            ["derivative", "max", "mean", "now"]
+                .into_iter()
+                .map(String::from)
+                .collect::<BTreeSet<_>>()
+        );
+
+        let (_, got) = select_statement("SELECT usage FROM cpu").unwrap();
+        assert!(got.function_names().is_empty());
+    }
+
+    #[test]
+    fn test_to_normalized_string() {
+        let normalize = |q: &str| select_statement(q).unwrap().1.to_normalized_string();
+
+        let want = "SELECT mean(usage) FROM cpu, (SELECT max(usage) AS usage FROM cpu \
+                    GROUP BY TIME(1m), host, region) WHERE host = 'West  Coast' \
+                    GROUP BY TIME(5m), host, region FILL(NONE)";
+        assert_eq!(
+            normalize(
+                r#"select MEAN("usage")  from  "cpu",(select Max(usage) as usage from cpu
+                group by region,host,time(1m))
+                where host = 'West  Coast' group by host, time(5m), region fill(none)"#
+            ),
+            want
+        );
+        assert_eq!(
+            normalize(
+                r#"SELECT mean(usage) FROM cpu, (SELECT MAX(usage) AS usage FROM cpu GROUP BY
+                TIME(1m), "host", region) WHERE "host" = 'West  Coast'
+                GROUP BY TIME(5m), region, "host", host FILL(NONE)"#
+            ),
+            want
+        );
+
+        // String and regular expression literals are preserved
+        assert_eq!(
+            normalize(
+                "SELECT value FROM cpu WHERE host = 'ABC' OR host =~ /A b/ GROUP BY *, /Z/, c"
+            ),
+            "SELECT value FROM cpu WHERE host = 'ABC' OR host =~ /A b/ GROUP BY c, /Z/, *"
+        );
+    }
+
+    #[test]
+    fn test_referenced_tag_keys() {
+        let (_, got) = select_statement(
+            r#"SELECT usage, region::tag FROM cpu, (SELECT idle FROM cpu WHERE dc =~ /west/)
+            WHERE host = 'server01' AND 'a' != az AND time > now() - 1h AND usage > 5
+            GROUP BY time(5m), "rack", host"#,
+        )
+        .unwrap();
+        assert_eq!(
+            got.referenced_tag_keys(),
+            ["az", "dc", "host", "rack", "region"]
+                .into_iter()
+                .map(Identifier::from)
+                .collect::<BTreeSet<_>>()
+        );
+
+        // time is excluded, even when compared with a string literal
+        let (_, got) =
+            select_statement("SELECT value FROM cpu WHERE time = '2022-10-31T02:00:00Z'").unwrap();
+        assert!(got.referenced_tag_keys().is_empty());
+    }
+
+    #[test]
+    fn test_validate_series_limits() {
+        use ValidationSeverity::*;
+
+        let validate = |q: &str, severity| {
+            let (_, got) = select_statement(q).unwrap();
+            got.validate_series_limits(severity)
+                .into_iter()
+                .map(|issue| {
+                    assert_eq!(issue.severity, severity);
+                    issue.message
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // SLIMIT and SOFFSET with GROUP BY are valid
+        assert!(validate(
+            "SELECT value FROM cpu GROUP BY host SLIMIT 1 SOFFSET 2",
+            Error
+        )
+        .is_empty());
+        assert!(validate("SELECT value FROM cpu", Error).is_empty());
+
+        // Misuse is reported with the requested severity
+        assert_eq!(
+            validate("SELECT value FROM cpu SLIMIT 1", Warning),
+            vec!["SLIMIT requires a GROUP BY clause"]
+        );
+        assert_eq!(
+            validate("SELECT value FROM cpu SLIMIT 1 SOFFSET 2", Error),
+            vec![
+                "SLIMIT requires a GROUP BY clause",
+                "SOFFSET requires a GROUP BY clause"
+            ]
+        );
+
+        // Subqueries are validated
+        assert_eq!(
+            validate(
+                "SELECT value FROM (SELECT value FROM cpu SOFFSET 2) GROUP BY host SLIMIT 1",
+                Error
+            ),
+            vec!["SOFFSET requires a GROUP BY clause"]
+        );
+    }
+
+    #[test]
+    fn test_group_by_interval() {
+        let interval = |q: &str| {
+            let (_, got) = select_statement(q).unwrap();
+            got.group_by_interval()
+        };
+
+        assert_eq!(
+            interval("SELECT MEAN(value) FROM cpu GROUP BY time(5m)"),
+            Ok(Some(300_000_000_000))
+        );
+        assert_eq!(
+            interval("SELECT MEAN(value) FROM cpu GROUP BY host, time(1h+30m, 5m)"),
+            Ok(Some(5_400_000_000_000))
+        );
+        assert_eq!(
+            interval("SELECT MEAN(value) FROM cpu GROUP BY time((1h - 30m) * 2 / 3)"),
+            Ok(Some(1_200_000_000_000))
+        );
+
+        // No time grouping
+        assert_eq!(
+            interval("SELECT MEAN(value) FROM cpu GROUP BY host"),
+            Ok(None)
+        );
+        assert_eq!(interval("SELECT value FROM cpu"), Ok(None));
+
+        // Fallible cases
+
+        assert_eq!(
+            interval("SELECT MEAN(value) FROM cpu GROUP BY time(10)")
+                .unwrap_err()
+                .to_string(),
+            "GROUP BY TIME interval must be a duration"
+        );
+        assert_eq!(
+            interval("SELECT MEAN(value) FROM cpu GROUP BY time(5m * 5m)")
+                .unwrap_err()
+                .to_string(),
+            "GROUP BY TIME interval must be a constant duration"
+        );
+        assert_eq!(
+            interval("SELECT MEAN(value) FROM cpu GROUP BY time(5m / 0)")
+                .unwrap_err()
+                .to_string(),
+            "GROUP BY TIME interval divides by zero"
+        );
+        assert_eq!(
+            interval("SELECT MEAN(value) FROM cpu GROUP BY time(0s)")
+                .unwrap_err()
+                .to_string(),
+            "GROUP BY TIME interval must be positive"
+        );
+        assert_eq!(
+            interval("SELECT MEAN(value) FROM cpu GROUP BY time(5m - 10m)")
+                .unwrap_err()
+                .to_string(),
+            "GROUP BY TIME interval must be positive"
+        );
+    }
+
+    #[test]
+    fn test_dimension_time_interval_nanos() {
+        let interval = |s: &str| Dimension::parse(s).unwrap().1.time_interval_nanos();
+        let offset = |s: &str| Dimension::parse(s).unwrap().1.time_offset_nanos();
+
+        assert_eq!(interval("time(5m)"), Ok(300_000_000_000));
+        assert_eq!(interval("time(5m3s)"), Ok(303_000_000_000));
+        assert_eq!(interval("time(5m + 3s)"), Ok(303_000_000_000));
+        assert_eq!(interval("time(1h - 2 * 15m)"), Ok(1_800_000_000_000));
+        assert_eq!(interval("time(1d, 6h)"), Ok(86_400_000_000_000));
+
+        assert_eq!(offset("time(1d)"), Ok(None));
+        assert_eq!(offset("time(1d, 6h)"), Ok(Some(21_600_000_000_000)));
+        assert_eq!(offset("time(1d, 1h30m)"), Ok(Some(5_400_000_000_000)));
+        assert_eq!(offset("time(1d, 2h - 30m)"), Ok(Some(5_400_000_000_000)));
+        assert_eq!(offset("time(1d, 2h - 3h)"), Ok(Some(-3_600_000_000_000)));
+
+        // Fallible cases
+
+        assert_eq!(
+            interval("time(10)").unwrap_err().to_string(),
+            "GROUP BY TIME interval must be a duration"
+        );
+        assert_eq!(
+            interval("time(0s)").unwrap_err().to_string(),
+            "GROUP BY TIME interval must be positive"
+        );
+        assert_eq!(
+            interval("time(1h - 2h)").unwrap_err().to_string(),
+            "GROUP BY TIME interval must be positive"
+        );
+
+        // The parser only accepts literals, so construct a field reference
+        let dim = Dimension::Time {
+            interval: var_ref!("field"),
+            offset: Some(var_ref!("field")),
+        };
+        assert_eq!(
+            dim.time_interval_nanos().unwrap_err().to_string(),
+            "GROUP BY TIME interval must be a constant duration"
+        );
+        assert_eq!(
+            dim.time_offset_nanos().unwrap_err().to_string(),
+            "GROUP BY TIME offset must be a constant duration"
+        );
+
+        assert_eq!(
+            offset("time(1d, now())").unwrap_err().to_string(),
+            "GROUP BY TIME offset must be a constant duration"
+        );
+        assert_eq!(
+            offset("time(1d, '2022-10-31T00:00:00Z')")
+                .unwrap_err()
+                .to_string(),
+            "GROUP BY TIME offset must be a constant duration"
+        );
+
+        assert_eq!(
+            Dimension::Tag("host".into())
+                .time_interval_nanos()
+                .unwrap_err()
+                .to_string(),
+            "dimension is not a TIME dimension"
+        );
+    }
+
+    #[test]
+    fn test_is_aggregate() {
+        let is_aggregate = |q: &str| select_statement(q).unwrap().1.is_aggregate();
+
+        // Plain fields
+        assert!(!is_aggregate("SELECT value FROM cpu"));
+        assert!(!is_aggregate(
+            "SELECT value * 2, host FROM cpu GROUP BY host"
+        ));
+        assert!(!is_aggregate("SELECT abs(value) FROM cpu"));
+
+        // Aggregates and selectors
+        assert!(is_aggregate("SELECT MEAN(value) FROM cpu"));
+        assert!(is_aggregate("SELECT last(value) FROM cpu"));
+        assert!(is_aggregate("SELECT COUNT(DISTINCT host) FROM cpu"));
+        assert!(is_aggregate("SELECT percentile(value, 95) * 2 FROM cpu"));
+        assert!(is_aggregate("SELECT derivative(mean(value), 1m) FROM cpu"));
+
+        // Mixed field lists
+        assert!(is_aggregate("SELECT value, max(value) FROM cpu"));
+        assert!(is_aggregate("SELECT -(sum(value)), host FROM cpu"));
+
+        // Grouping by time
+        assert!(is_aggregate("SELECT value FROM cpu GROUP BY time(5m)"));
+    }
+
+    #[test]
+    fn test_rename_measurement() {
+        let rename = |q: &str, from: &str, to: &str| {
+            let (_, mut got) = select_statement(q).unwrap();
+            let count = got.rename_measurement(from, to);
+            (got.to_string(), count)
+        };
+
+        assert_eq!(
+            rename(
+                "SELECT cpu.*, value FROM cpu, db.rp.cpu, mem, (SELECT value FROM cpu, /cpu/)",
+                "cpu",
+                "cpu_v2"
+            ),
+            (
+                "SELECT cpu_v2.*, value FROM cpu_v2, db.rp.cpu_v2, mem, (SELECT value FROM cpu_v2, /cpu/)"
+                    .to_string(),
+                4
+            )
+        );
+
+        // The INTO measurement is renamed, but not a backreference
+        assert_eq!(
+            rename("SELECT value INTO db.rp.cpu FROM cpu", "cpu", "cpu_v2"),
+            ("SELECT value INTO db.rp.cpu_v2 FROM cpu_v2".to_string(), 2)
+        );
+        assert_eq!(
+            rename("SELECT value INTO :MEASUREMENT FROM cpu", "cpu", "cpu_v2"),
+            ("SELECT value INTO :MEASUREMENT FROM cpu_v2".to_string(), 1)
+        );
+
+        // Names are quoted as necessary
+        assert_eq!(
+            rename("SELECT value FROM cpu", "cpu", "cpu load"),
+            ("SELECT value FROM \"cpu load\"".to_string(), 1)
+        );
+
+        // Measurement names are case-sensitive
+        assert_eq!(
+            rename("SELECT value FROM CPU", "cpu", "cpu_v2"),
+            ("SELECT value FROM CPU".to_string(), 0)
+        );
+    }
+
+    #[test]
+    fn test_validate_fill() {
+        let field_types = HashMap::from([
+            ("value".to_string(), VarRefDataType::Float),
+            ("status".to_string(), VarRefDataType::String),
+            ("up".to_string(), VarRefDataType::Boolean),
+        ]);
+        let validate = |q: &str| {
+            let (_, got) = select_statement(q).unwrap();
+            got.validate_fill(&field_types).map(|issue| {
+                assert_eq!(issue.severity, ValidationSeverity::Error);
+                issue.message
+            })
+        };
+
+        // Numeric aggregates may be filled
+        assert_eq!(
+            validate("SELECT mean(value) FROM cpu GROUP BY time(1m) FILL(linear)"),
+            None
+        );
+        assert_eq!(
+            validate("SELECT last(value), count(status) FROM cpu GROUP BY time(1m) FILL(previous)"),
+            None
+        );
+        assert_eq!(
+            validate("SELECT max(value) * 2 FROM cpu GROUP BY time(1m) FILL(linear)"),
+            None
+        );
+
+        // Aggregates of unknown type are assumed to be numeric
+        assert_eq!(
+            validate("SELECT last(other) FROM cpu GROUP BY time(1m) FILL(linear)"),
+            None
+        );
+
+        // Other fill options are not restricted
+        assert_eq!(
+            validate("SELECT last(status) FROM cpu GROUP BY time(1m) FILL(null)"),
+            None
+        );
+
+        // Non-numeric selectors are rejected
+        assert_eq!(
+            validate("SELECT last(status) FROM cpu GROUP BY time(1m) FILL(linear)"),
+            Some("FILL(linear) requires numeric aggregates")
+        );
+        assert_eq!(
+            validate("SELECT mean(value), first(up) FROM cpu GROUP BY time(1m) FILL(previous)"),
+            Some("FILL(previous) requires numeric aggregates")
+        );
+
+        // Casts take precedence over field types
+        assert_eq!(
+            validate("SELECT last(other::string) FROM cpu GROUP BY time(1m) FILL(linear)"),
+            Some("FILL(linear) requires numeric aggregates")
+        );
+        assert_eq!(
+            validate("SELECT last(host::tag) FROM cpu GROUP BY time(1m) FILL(linear)"),
+            Some("FILL(linear) requires numeric aggregates")
+        );
+    }
+
+    #[test]
+    fn test_measurements() {
+        let measurements = |q: &str| {
+            let (_, got) = select_statement(q).unwrap();
+            let got = got.measurements();
+            (
+                got.names.iter().map(|n| n.to_string()).collect::<Vec<_>>(),
+                got.regexes
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        assert_eq!(
+            measurements("SELECT value FROM cpu"),
+            (vec!["cpu".to_owned()], vec![])
+        );
+
+        assert_eq!(
+            measurements("SELECT value FROM db.rp.cpu, /^mem/, cpu, db.rp.cpu"),
+            (
+                vec!["db.rp.cpu".to_owned(), "cpu".to_owned()],
+                vec!["/^mem/".to_owned()]
+            )
+        );
+
+        // Measurements of subqueries are included
+        assert_eq!(
+            measurements(
+                "SELECT max(value) FROM (SELECT mean(value) AS value FROM cpu, /^disk/ GROUP BY host), mem"
+            ),
+            (
+                vec!["cpu".to_owned(), "mem".to_owned()],
+                vec!["/^disk/".to_owned()]
+            )
+        );
+
+        assert_eq!(
+            measurements(
+                "SELECT value FROM (SELECT value FROM (SELECT value FROM /^net/), db..cpu)"
+            ),
+            (vec!["db..cpu".to_owned()], vec!["/^net/".to_owned()])
+        );
+    }
+
+    #[test]
+    fn test_validate_function_calls() {
+        let validate = |q: &str| {
+            let (_, got) = select_statement(q).unwrap();
+            got.validate_function_calls()
+                .into_iter()
+                .map(|issue| {
+                    assert_eq!(issue.severity, ValidationSeverity::Error);
+                    issue.message
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Valid calls
+        assert!(validate("SELECT percentile(value, 95) FROM cpu").is_empty());
+        assert!(validate("SELECT PERCENTILE(value, 99.9) FROM cpu").is_empty());
+        assert!(
+            validate("SELECT top(value, 3), bottom(value, host, region, 2) FROM cpu").is_empty()
+        );
+        assert!(validate("SELECT sample(value, $n) FROM cpu").is_empty());
+        assert!(validate("SELECT derivative(value), derivative(value, 1s) FROM cpu").is_empty());
+        assert!(
+            validate("SELECT moving_average(mean(value), 3) FROM cpu GROUP BY time(1m)").is_empty()
+        );
+        assert!(
+            validate("SELECT count(DISTINCT value), mean(/usage/), max(*) FROM cpu").is_empty()
+        );
+        assert!(
+            validate("SELECT holt_winters(first(value), 10, 4) FROM cpu GROUP BY time(1m)")
+                .is_empty()
+        );
+
+        // Other functions are not validated
+        assert!(validate("SELECT abs(value - 1), foo() FROM cpu").is_empty());
+
+        // Invalid calls
+        assert_eq!(
+            validate("SELECT percentile(value) FROM cpu"),
+            vec!["invalid PERCENTILE function, expected a field and a number"]
+        );
+        assert_eq!(
+            validate("SELECT percentile(value, 'a') FROM cpu"),
+            vec!["invalid PERCENTILE function, expected a field and a number"]
+        );
+        assert_eq!(
+            validate("SELECT top(value) FROM cpu"),
+            vec!["invalid TOP function, expected a field, optional tags and an integer"]
+        );
+        assert_eq!(
+            validate("SELECT top(value, 1.5) FROM cpu"),
+            vec!["invalid TOP function, expected a field, optional tags and an integer"]
+        );
+        assert_eq!(
+            validate("SELECT derivative() FROM cpu"),
+            vec!["invalid DERIVATIVE function, expected a field and an optional duration"]
+        );
+        assert_eq!(
+            validate("SELECT derivative(value, 10) FROM cpu"),
+            vec!["invalid DERIVATIVE function, expected a field and an optional duration"]
+        );
+        assert_eq!(
+            validate("SELECT mean(value, 1) FROM cpu"),
+            vec!["invalid MEAN function, expected a field"]
+        );
+        assert_eq!(
+            validate("SELECT sum(1) FROM cpu"),
+            vec!["invalid SUM function, expected a field"]
+        );
+
+        // Each misuse is reported, including those of subqueries
+        assert_eq!(
+            validate("SELECT sample(value), count() FROM (SELECT moving_average(value) FROM cpu)"),
+            vec![
+                "invalid SAMPLE function, expected a field and an integer",
+                "invalid COUNT function, expected a field",
+                "invalid MOVING_AVERAGE function, expected a field and an integer",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_regexes() {
+        let validate = |q: &str| {
+            let (_, got) = select_statement(q).unwrap();
+            got.validate_regexes()
+                .map_err(|err| (err.clause, err.regex.to_string()))
+        };
+
+        assert_eq!(validate("SELECT value FROM cpu"), Ok(()));
+        assert_eq!(
+            validate("SELECT value FROM /^c.*/ WHERE host =~ /^(a|b)$/ GROUP BY /reg/"),
+            Ok(())
+        );
+
+        // Malformed regular expressions are reported with their clause
+        assert_eq!(
+            validate("SELECT value FROM cpu WHERE host =~ /(a/"),
+            Err(("WHERE clause", "/(a/".to_owned()))
+        );
+        assert_eq!(
+            validate("SELECT value FROM /(a/"),
+            Err(("FROM clause", "/(a/".to_owned()))
+        );
+        assert_eq!(
+            validate("SELECT value FROM cpu GROUP BY /(a/"),
+            Err(("GROUP BY clause", "/(a/".to_owned()))
+        );
+
+        // Only the first malformed regular expression is reported
+        assert_eq!(
+            validate("SELECT value FROM cpu WHERE host =~ /(a/ GROUP BY /(b/"),
+            Err(("WHERE clause", "/(a/".to_owned()))
+        );
+
+        // Subqueries are validated
+        assert_eq!(
+            validate("SELECT value FROM (SELECT value FROM cpu WHERE host !~ /(a/)"),
+            Err(("WHERE clause", "/(a/".to_owned()))
+        );
+
+        let (_, stmt) = select_statement("SELECT value FROM cpu WHERE host =~ /(a/").unwrap();
+        let err = stmt.validate_regexes().unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid regular expression /(a/ in WHERE clause: "));
+    }
+
+    #[test]
+    fn test_sort_fields() {
+        let sort = |q: &str| {
+            let (_, mut got) = select_statement(q).unwrap();
+            (got.sort_fields(), got.to_string())
+        };
+
+        assert_eq!(
+            sort("SELECT usage_user, max(usage_idle) AS max, host FROM cpu, (SELECT b, a FROM m)"),
+            (
+                true,
+                "SELECT host, max(usage_idle) AS max, usage_user FROM cpu, (SELECT b, a FROM m)"
+                    .to_owned()
+            )
+        );
+
+        // Refused, leaving the statement unchanged
+        for q in [
+            "SELECT value, * FROM cpu",
+            "SELECT value, cpu.* FROM cpu",
+            "SELECT value, /usage/ FROM cpu",
+            "SELECT value, mean(/usage/) FROM cpu",
+            "SELECT value, top(usage, host, 3) FROM cpu",
+            "SELECT value, BOTTOM(usage, 3) FROM cpu",
+            "SELECT value + 2, value + 1 FROM cpu",
+        ] {
+            assert_eq!(sort(q), (false, q.to_owned()), "{}", q);
+        }
+
+        // Aliased fields are not given generated names
+        assert_eq!(
+            sort("SELECT value + 2 AS b, value + 1 AS a FROM cpu"),
+            (
+                true,
+                "SELECT value + 1 AS a, value + 2 AS b FROM cpu".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn test_output_columns() {
+        let columns = |q: &str| {
+            let (_, got) = select_statement(q).unwrap();
+            got.output_columns()
+        };
+
+        // An explicit `time` projection is the timestamp column
+        let (_, got) = select_statement("SELECT time, value FROM m").unwrap();
+        assert_eq!(format!("{}", got), "SELECT time, value FROM m");
+        assert_matches!(
+            &got.fields.first().expr,
+            Expr::VarRef { name, data_type: None } if name.as_str() == "time"
+        );
+        assert_eq!(got.output_columns(), ["time", "value"]);
+        assert_eq!(columns("SELECT value FROM m"), ["time", "value"]);
+        assert_eq!(columns("SELECT value, TIME FROM m"), ["time", "value"]);
+        assert_eq!(columns("SELECT time AS ts, value FROM m"), ["ts", "value"]);
+
+        // Generated names
+        assert_eq!(
+            columns("SELECT MEAN(value), distinct(host), usage / total * 100, -idle FROM m"),
+            ["time", "mean", "distinct", "usage_total", "idle"]
+        );
+
+        // Aliases take precedence, and conflicts are suffixed
+        assert_eq!(
+            columns("SELECT mean(a), mean(b), mean(c) AS mean_1, max(a) AS mean FROM m"),
+            ["time", "mean_2", "mean_3", "mean_1", "mean"]
+        );
+        assert_eq!(
+            columns("SELECT value, value FROM m"),
+            ["time", "value", "value_1"]
+        );
+
+        // Wildcards are not expanded
+        assert_eq!(columns("SELECT *, mean(/u/) FROM m"), ["time"]);
+    }
+
+    #[test]
+    fn test_group_windows() {
+        const MINUTE: i64 = 60_000_000_000;
+
+        let windows = |q: &str, start: i64, end: i64| {
+            let (_, got) = select_statement(q).unwrap();
+            got.group_windows(&TimeRange::new(start * MINUTE, end * MINUTE))
+                .map(|v| {
+                    v.into_iter()
+                        .map(|r| (r.start() / MINUTE, r.end() / MINUTE))
+                        .collect::<Vec<_>>()
+                })
+        };
+
+        let q = "SELECT MEAN(value) FROM cpu GROUP BY time(5m)";
+        assert_eq!(
+            windows(q, 0, 20).unwrap(),
+            vec![(0, 5), (5, 10), (10, 15), (15, 20)]
+        );
+
+        // Windows are aligned to the epoch rather than the start of the range
+        assert_eq!(
+            windows(q, 2, 22).unwrap(),
+            vec![(0, 5), (5, 10), (10, 15), (15, 20), (20, 25)]
+        );
+
+        // An offset shifts the windows
+        let q = "SELECT MEAN(value) FROM cpu GROUP BY time(5m, 1m)";
+        assert_eq!(
+            windows(q, 0, 20).unwrap(),
+            vec![(-4, 1), (1, 6), (6, 11), (11, 16), (16, 21)]
+        );
+
+        // An offset greater than the interval wraps
+        let q = "SELECT MEAN(value) FROM cpu GROUP BY time(5m, 11m)";
+        assert_eq!(
+            windows(q, 0, 20).unwrap(),
+            vec![(-4, 1), (1, 6), (6, 11), (11, 16), (16, 21)]
+        );
+
+        let q = "SELECT MEAN(value) FROM cpu GROUP BY time(5m, -1m)";
+        assert_eq!(
+            windows(q, 0, 20).unwrap(),
+            vec![(-1, 4), (4, 9), (9, 14), (14, 19), (19, 24)]
+        );
+
+        // An empty range has no windows
+        assert_eq!(windows(q, 10, 10).unwrap(), vec![]);
+
+        // Fallible cases
+
+        assert_eq!(
+            windows("SELECT value FROM cpu", 0, 20)
+                .unwrap_err()
+                .to_string(),
+            "SELECT statement is not grouped by time"
+        );
+        assert_eq!(
+            windows(
+                "SELECT MEAN(value) FROM cpu GROUP BY time(5m, now())",
+                0,
+                20
+            )
+            .unwrap_err()
+            .to_string(),
+            "GROUP BY TIME offset must be a constant duration"
+        );
+        assert_eq!(
+            windows("SELECT MEAN(value) FROM cpu GROUP BY time(5m - 5m)", 0, 20)
+                .unwrap_err()
+                .to_string(),
+            "GROUP BY TIME interval must be positive"
+        );
+
+        // The number of windows is bounded
+        let q = "SELECT MEAN(value) FROM cpu GROUP BY time(1m)";
+        let max = MAX_GROUP_WINDOWS as i64;
+        assert_eq!(windows(q, 0, max).unwrap().len(), MAX_GROUP_WINDOWS);
+        assert_eq!(
+            windows(q, 0, max + 1).unwrap_err().to_string(),
+            "GROUP BY TIME produces too many windows over the time range"
+        );
+        let (_, got) = select_statement("SELECT MEAN(value) FROM cpu GROUP BY time(1ns)").unwrap();
+        assert_eq!(
+            got.group_windows(&TimeRange::new(i64::MIN, i64::MAX))
+                .unwrap_err()
+                .to_string(),
+            "GROUP BY TIME produces too many windows over the time range"
+        );
+    }
+
+    #[cfg(feature = "data_types")]
+    #[test]
+    fn test_time_range_conversion() {
+        use data_types::TimestampRange;
+
+        let got = TimestampRange::from(TimeRange::new(10, 20));
+        assert_eq!(got, TimestampRange::new(10, 20));
+        assert_eq!(TimeRange::from(got), TimeRange::new(10, 20));
+
+        // Empty ranges remain empty
+        let got = TimestampRange::from(TimeRange::new(20, 10));
+        assert_eq!((got.start(), got.end()), (10, 10));
+    }
+
+    #[test]
+    fn test_fill_none() {
+        // An explicit FILL(NONE) is distinguished from no FILL clause
+        let (_, got) =
+            select_statement("SELECT MEAN(value) FROM cpu GROUP BY time(5m) FILL(none)").unwrap();
+        assert_eq!(got.fill, Some(FillClause::None));
+        assert_eq!(
+            got.to_string(),
+            "SELECT MEAN(value) FROM cpu GROUP BY TIME(5m) FILL(NONE)"
+        );
+
+        // and survives a round trip
+        let (_, round_trip) = select_statement(&got.to_string()).unwrap();
+        assert_eq!(round_trip, got);
+
+        let (_, got) = select_statement("SELECT MEAN(value) FROM cpu GROUP BY time(5m)").unwrap();
+        assert_eq!(got.fill, None);
+        assert_eq!(
+            got.to_string(),
+            "SELECT MEAN(value) FROM cpu GROUP BY TIME(5m)"
+        );
+    }
+
+    #[test]
+    fn test_fill_position() {
+        let (_, canonical) =
+            select_statement("SELECT MEAN(value) FROM cpu GROUP BY time(5m) FILL(0)").unwrap();
+
+        // FILL may precede the GROUP BY clause
+        let (_, got) =
+            select_statement("SELECT MEAN(value) FROM cpu FILL(0) GROUP BY time(5m)").unwrap();
+        assert_eq!(got, canonical);
+        assert_eq!(
+            got.to_string(),
+            "SELECT MEAN(value) FROM cpu GROUP BY TIME(5m) FILL(0)"
+        );
+
+        let (_, canonical) = select_statement(
+            "SELECT MEAN(value) FROM cpu WHERE host = 'a' GROUP BY time(5m), host FILL(none) LIMIT 1",
+        )
+        .unwrap();
+        let (_, got) = select_statement(
+            "SELECT MEAN(value) FROM cpu WHERE host = 'a' FILL(none) GROUP BY time(5m), host LIMIT 1",
+        )
+        .unwrap();
+        assert_eq!(got, canonical);
+
+        // Fallible cases
+
+        assert_expect_error!(
+            select_statement("SELECT MEAN(value) FROM cpu FILL(0) GROUP BY time(5m) FILL(0)"),
+            "invalid SELECT statement, FILL specified more than once"
+        );
+    }
+
+    #[test]
+    fn test_order_direction() {
+        let (_, got) = select_statement("SELECT value FROM cpu ORDER BY TIME DESC").unwrap();
+        assert_eq!(got.order_direction(), OrderByClause::Descending);
+
+        let (_, got) = select_statement("SELECT value FROM cpu ORDER BY ASC").unwrap();
+        assert_eq!(got.order_direction(), OrderByClause::Ascending);
+
+        // An explicit ascending order is retained, and distinct from no ORDER BY clause
+        let (_, got) = select_statement("SELECT value FROM foo ORDER BY TIME ASC").unwrap();
+        assert_eq!(got.order_by, Some(OrderByClause::Ascending));
+        assert_eq!(got.to_string(), "SELECT value FROM foo ORDER BY TIME ASC");
+
+        // Defaults to ascending when there is no ORDER BY clause
+        let (_, got) = select_statement("SELECT value FROM cpu").unwrap();
+        assert_eq!(got.order_by, None);
+        assert_eq!(got.order_direction(), OrderByClause::Ascending);
+        assert_eq!(got.to_string(), "SELECT value FROM cpu");
+    }
 
     #[test]
-    fn test_select_statement() {
-        let (_, got) = select_statement("SELECT value FROM foo").unwrap();
-        assert_eq!(format!("{}", got), "SELECT value FROM foo");
+    fn test_ensure_time_bound() {
+        let ensure = |q: &str| {
+            let (_, mut got) = select_statement(q).unwrap();
+            got.ensure_time_bound(3_600_000_000_000);
+            got.to_string()
+        };
+
+        // Statements with a lower time bound are unchanged
+        assert_eq!(
+            ensure("SELECT value FROM cpu WHERE time > now() - 5m"),
+            "SELECT value FROM cpu WHERE time > now() - 5m"
+        );
+        assert_eq!(
+            ensure("SELECT value FROM cpu WHERE host = 'a' AND (now() - 5m <= time)"),
+            "SELECT value FROM cpu WHERE host = 'a' AND (now() - 5m <= time)"
+        );
 
-        let (_, got) =
-            select_statement(r#"SELECT f1,/f2/, f3 AS "a field" FROM foo WHERE host =~ /c1/"#)
-                .unwrap();
+        // Statements without a WHERE clause have one created
         assert_eq!(
-            format!("{}", got),
-            r#"SELECT f1, /f2/, f3 AS "a field" FROM foo WHERE host =~ /c1/"#
+            ensure("SELECT value FROM cpu"),
+            "SELECT value FROM cpu WHERE time > now() - 1h"
         );
 
-        let (_, got) =
-            select_statement("SELECT sum(value) FROM foo GROUP BY time(5m), host").unwrap();
+        // Existing conditions are conjoined with the bound
         assert_eq!(
-            format!("{}", got),
-            r#"SELECT sum(value) FROM foo GROUP BY TIME(5m), host"#
+            ensure("SELECT value FROM cpu WHERE host = 'a'"),
+            "SELECT value FROM cpu WHERE host = 'a' AND time > now() - 1h"
+        );
+        assert_eq!(
+            ensure("SELECT value FROM cpu WHERE time < now()"),
+            "SELECT value FROM cpu WHERE time < now() AND time > now() - 1h"
         );
 
-        // Parses TIME() with expressions
-        let (_, got) =
-            select_statement("SELECT sum(value) FROM foo GROUP BY time(5m * 10), host").unwrap();
+        // A bound within an OR does not bound the statement
         assert_eq!(
-            format!("{}", got),
-            r#"SELECT sum(value) FROM foo GROUP BY TIME(5m * 10), host"#
+            ensure("SELECT value FROM cpu WHERE host = 'a' OR time > now() - 5m"),
+            "SELECT value FROM cpu WHERE (host = 'a' OR time > now() - 5m) AND time > now() - 1h"
         );
+    }
 
-        // TIME only supports integer and duration literals
-        select_statement("SELECT sum(value) FROM foo GROUP BY time(5m + 'foo'), host").unwrap_err();
-        select_statement("SELECT sum(value) FROM foo GROUP BY time(5m + 5.4), host").unwrap_err();
-        select_statement("SELECT sum(value) FROM foo GROUP BY time(5m + true), host").unwrap_err();
+    #[test]
+    fn test_time_range() {
+        use std::ops::Bound::*;
+
+        const MINUTE: i64 = 60_000_000_000;
+        const NOW: i64 = 100 * MINUTE;
+
+        let time_range = |q: &str| select_statement(q).unwrap().1.time_range(NOW);
 
-        let (_, got) =
-            select_statement("SELECT sum(value) FROM foo GROUP BY time(5m), host FILL(previous)")
-                .unwrap();
         assert_eq!(
-            format!("{}", got),
-            r#"SELECT sum(value) FROM foo GROUP BY TIME(5m), host FILL(PREVIOUS)"#
+            time_range("SELECT value FROM cpu WHERE time > now() - 1h"),
+            Some((Included(40 * MINUTE + 1), Unbounded))
+        );
+        assert_eq!(
+            time_range("SELECT value FROM cpu WHERE time <= now() AND host = 'a'"),
+            Some((Unbounded, Excluded(NOW + 1)))
         );
 
-        let (_, got) = select_statement("SELECT value FROM foo ORDER BY DESC").unwrap();
+        // Closed intervals
         assert_eq!(
-            format!("{}", got),
-            r#"SELECT value FROM foo ORDER BY TIME DESC"#
+            time_range("SELECT value FROM cpu WHERE time >= 10 AND time <= 20"),
+            Some((Included(10), Excluded(21)))
+        );
+        assert_eq!(
+            time_range(
+                "SELECT value FROM cpu WHERE host = 'a' AND (time >= now() - 1h AND time < now())"
+            ),
+            Some((Included(40 * MINUTE), Excluded(NOW)))
         );
 
-        let (_, got) = select_statement("SELECT value FROM foo ORDER BY TIME ASC").unwrap();
+        // The tightest bounds are returned
         assert_eq!(
-            format!("{}", got),
-            r#"SELECT value FROM foo ORDER BY TIME ASC"#
+            time_range(
+                "SELECT value FROM cpu WHERE time > 10 AND time >= 50 AND time < 100 AND 80 > time"
+            ),
+            Some((Included(50), Excluded(80)))
         );
 
-        let (_, got) = select_statement("SELECT value FROM foo LIMIT 5").unwrap();
-        assert_eq!(format!("{}", got), r#"SELECT value FROM foo LIMIT 5"#);
+        // No time predicate
+        assert_eq!(time_range("SELECT value FROM cpu"), None);
+        assert_eq!(time_range("SELECT value FROM cpu WHERE host = 'a'"), None);
+        assert_eq!(
+            time_range("SELECT value FROM cpu WHERE time > now() - 1h OR host = 'a'"),
+            None
+        );
+    }
 
-        let (_, got) = select_statement("SELECT value FROM foo OFFSET 20").unwrap();
-        assert_eq!(format!("{}", got), r#"SELECT value FROM foo OFFSET 20"#);
+    #[test]
+    fn test_scan_time_range() {
+        const MINUTE: i64 = 60_000_000_000;
 
-        let (_, got) = select_statement("SELECT value FROM foo SLIMIT 25").unwrap();
-        assert_eq!(format!("{}", got), r#"SELECT value FROM foo SLIMIT 25"#);
+        let scan = |q: &str| {
+            let (_, got) = select_statement(q).unwrap();
+            let r = got.scan_time_range(100 * MINUTE, &TimeRange::new(0, 200 * MINUTE));
+            (r.start(), r.end())
+        };
 
-        let (_, got) = select_statement("SELECT value FROM foo SOFFSET 220").unwrap();
-        assert_eq!(format!("{}", got), r#"SELECT value FROM foo SOFFSET 220"#);
+        // Without a WHERE clause, the default range is scanned
+        assert_eq!(scan("SELECT value FROM cpu"), (0, 200 * MINUTE));
 
-        let (_, got) = select_statement("SELECT value FROM foo tz('Australia/Hobart')").unwrap();
+        // Lower bound only
         assert_eq!(
-            format!("{}", got),
-            r#"SELECT value FROM foo TZ('Australia/Hobart')"#
+            scan("SELECT value FROM cpu WHERE time > now() - 10m"),
+            (90 * MINUTE + 1, 200 * MINUTE)
+        );
+        assert_eq!(
+            scan("SELECT value FROM cpu WHERE host = 'a' AND time >= 3000000000000"),
+            (50 * MINUTE, 200 * MINUTE)
         );
 
-        // validate spacing between keywords
+        // Upper bound only
+        assert_eq!(
+            scan("SELECT value FROM cpu WHERE time <= now() - 30m AND host = 'a'"),
+            (0, 70 * MINUTE + 1)
+        );
+        assert_eq!(
+            scan("SELECT value FROM cpu WHERE now() - 30m > time"),
+            (0, 70 * MINUTE)
+        );
 
-        let (rem, _) = select_statement("SELECT value FROM(SELECT val FROM cpu)").unwrap();
-        assert_eq!(rem, "");
+        // Both bounds
+        assert_eq!(
+            scan("SELECT value FROM cpu WHERE time >= now() - 1h AND time < now()"),
+            (40 * MINUTE, 100 * MINUTE)
+        );
+        assert_eq!(
+            scan("SELECT value FROM cpu WHERE (now() - 1h <= time AND time < 6000000000000) AND time < now() + 1h"),
+            (40 * MINUTE, 100 * MINUTE)
+        );
+        assert_eq!(
+            scan("SELECT value FROM cpu WHERE time = now()"),
+            (100 * MINUTE, 100 * MINUTE + 1)
+        );
 
-        let (rem, _) = select_statement("SELECT (value)FROM cpu").unwrap();
-        assert_eq!(rem, "");
+        // Bounds are intersected with the default range
+        assert_eq!(
+            scan("SELECT value FROM cpu WHERE time > now() - 1000m AND time < now() + 1000m"),
+            (0, 200 * MINUTE)
+        );
+        let (start, end) = scan("SELECT value FROM cpu WHERE time >= now() + 200m");
+        assert_eq!(start, end);
 
-        let (rem, _) =
-            select_statement("SELECT value FROM (SELECT val FROM cpu)WHERE 1=1").unwrap();
-        assert_eq!(rem, "");
+        // Bounds within an OR are ignored
+        assert_eq!(
+            scan("SELECT value FROM cpu WHERE time > now() - 10m OR host = 'a'"),
+            (0, 200 * MINUTE)
+        );
+    }
 
-        let (rem, _) =
-            select_statement("SELECT value FROM cpu WHERE time <= now()FILL(previous)").unwrap();
+    #[test]
+    fn test_parameterize() {
+        let (_, stmt) = select_statement(
+            "SELECT value * 2 FROM cpu WHERE host = 'abc' AND (value > 10 OR up = true) \
+             AND time > now() - 1h GROUP BY time(5m)",
+        )
+        .unwrap();
+        let (got, params) = stmt.parameterize();
+        assert_eq!(
+            got.to_string(),
+            "SELECT value * 2 FROM cpu WHERE host = $p1 AND (value > $p2 OR up = $p3) \
+             AND time > now() - $p4 GROUP BY TIME(5m)"
+        );
+        assert_eq!(
+            params,
+            HashMap::from([
+                ("p1".to_string(), Literal::from("abc".to_string())),
+                ("p2".to_string(), Literal::from(10_u64)),
+                ("p3".to_string(), Literal::from(true)),
+                (
+                    "p4".to_string(),
+                    Literal::Duration(3_600_000_000_000.into())
+                ),
+            ])
+        );
+        assert_eq!(got.bind(&params).unwrap(), stmt);
+
+        // Subqueries are parameterized, and existing parameters are preserved
+        let (_, stmt) = select_statement(
+            "SELECT value FROM (SELECT value FROM cpu WHERE region = 'us') \
+             WHERE host = $p1 AND value > 0.5",
+        )
+        .unwrap();
+        let (got, params) = stmt.parameterize();
+        assert_eq!(
+            got.to_string(),
+            "SELECT value FROM (SELECT value FROM cpu WHERE region = $p3) \
+             WHERE host = $p1 AND value > $p2"
+        );
+        assert_eq!(params.len(), 2);
+        assert_eq!(params["p2"], Literal::Float(0.5));
+        assert_eq!(params["p3"], Literal::from("us".to_string()));
+
+        // Regular expressions are not replaced, so the result parses
+        let (_, stmt) = select_statement(
+            "SELECT value FROM cpu WHERE host =~ /^server\\d+$/ AND region !~ /us/ AND value > 1",
+        )
+        .unwrap();
+        let (got, params) = stmt.parameterize();
+        assert_eq!(
+            got.to_string(),
+            "SELECT value FROM cpu WHERE host =~ /^server\\d+$/ AND region !~ /us/ AND value > $p1"
+        );
+        assert_eq!(
+            params,
+            HashMap::from([("p1".to_string(), Literal::from(1_u64))])
+        );
+        let (rem, reparsed) = select_statement(&got.to_string()).unwrap();
         assert_eq!(rem, "");
+        assert_eq!(reparsed, got);
+        assert_eq!(reparsed.bind(&params).unwrap(), stmt);
+
+        // Statements without a WHERE clause are unchanged
+        let (_, stmt) = select_statement("SELECT value FROM cpu LIMIT 10").unwrap();
+        let (got, params) = stmt.parameterize();
+        assert_eq!(got, stmt);
+        assert!(params.is_empty());
+    }
 
-        let (rem, _) =
-            select_statement("SELECT value FROM cpu WHERE time <= now()ORDER BY time").unwrap();
-        assert_eq!(rem, "");
+    #[test]
+    fn test_bind_parameters() {
+        let (_, got) = select_statement(
+            "SELECT $threshold, usage FROM cpu, \
+             (SELECT idle FROM mem WHERE host = $host AND idle > $threshold) \
+             WHERE region = $region GROUP BY time(1m)",
+        )
+        .unwrap();
+        assert_eq!(
+            got.bind_parameters(),
+            ["host", "region", "threshold"]
+                .into_iter()
+                .map(String::from)
+                .collect::<BTreeSet<_>>()
+        );
 
-        let (rem, _) =
-            select_statement("SELECT value FROM cpu WHERE time <= now()LIMIT 10").unwrap();
-        assert_eq!(rem, "");
+        let (_, got) = select_statement("SELECT usage FROM cpu WHERE host = 'a'").unwrap();
+        assert!(got.bind_parameters().is_empty());
+    }
 
-        let (rem, _) =
-            select_statement("SELECT value FROM cpu WHERE time <= now()OFFSET 10").unwrap();
-        assert_eq!(rem, "");
+    #[test]
+    fn test_bind() {
+        let params = HashMap::from([
+            ("host".to_string(), Literal::from("server01".to_string())),
+            (
+                "interval".to_string(),
+                Literal::Duration(600_000_000_000.into()),
+            ),
+            ("n".to_string(), Literal::from(5_u64)),
+            ("unused".to_string(), Literal::from(true)),
+        ]);
+
+        // All parameters are bound, including those of subqueries
+        let (_, stmt) = select_statement(
+            "SELECT mean(value) * $n FROM (SELECT value FROM cpu WHERE host = $host) \
+             WHERE time > now() - $interval GROUP BY time($interval)",
+        )
+        .unwrap();
+        assert_eq!(
+            stmt.bind(&params).unwrap().to_string(),
+            "SELECT mean(value) * 5 FROM (SELECT value FROM cpu WHERE host = 'server01') \
+             WHERE time > now() - 10m GROUP BY TIME(10m)"
+        );
 
-        let (rem, _) =
-            select_statement("SELECT value FROM cpu WHERE time <= now()SLIMIT 10").unwrap();
-        assert_eq!(rem, "");
+        // The same parameter may be referenced more than once
+        let (_, stmt) =
+            select_statement("SELECT value FROM cpu WHERE host = $host OR region = $host").unwrap();
+        assert_eq!(
+            stmt.bind(&params).unwrap().to_string(),
+            "SELECT value FROM cpu WHERE host = 'server01' OR region = 'server01'"
+        );
 
-        let (rem, _) =
-            select_statement("SELECT value FROM cpu WHERE time <= now()SOFFSET 10").unwrap();
-        assert_eq!(rem, "");
+        // Fallible cases
 
-        let (rem, _) =
-            select_statement("SELECT value FROM cpu WHERE time <= now()TZ('Australia/Hobart')")
-                .unwrap();
-        assert_eq!(rem, "");
+        let (_, stmt) = select_statement("SELECT value FROM cpu WHERE host = $missing").unwrap();
+        let err = stmt.bind(&params).unwrap_err();
+        assert_eq!(err, UnboundParameter("missing".into()));
+        assert_eq!(
+            err.to_string(),
+            "no value specified for bind parameter $missing"
+        );
+    }
+
+    #[test]
+    fn test_debug_tree() {
+        let (_, got) = select_statement(
+            "SELECT MEAN(value) AS mean FROM (SELECT value FROM cpu WHERE host = 'a') \
+             WHERE time > now() - 1h GROUP BY time(10m), host FILL(none) LIMIT 5",
+        )
+        .unwrap();
+        let tree = got.debug_tree();
+        let lines = tree.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], "SelectStatement");
+        assert!(lines.contains(&"    Field AS mean"));
+        assert!(lines.contains(&"      Call MEAN"));
+        assert!(lines.contains(&"        VarRef value"));
+        assert!(lines.contains(&"    Subquery"));
+        assert!(lines.contains(&"      SelectStatement"));
+        assert!(lines.contains(&"          Measurement cpu"));
+        assert!(lines.contains(&"          ConditionalBinary ="));
+        assert!(lines.contains(&"            VarRef host"));
+        assert!(lines.contains(&"    Tag host"));
+        assert!(lines.contains(&"  FILL(NONE)"));
+        assert!(lines.contains(&"  LIMIT 5"));
     }
 
     #[test]
@@ -918,6 +4221,36 @@ mod test {
             }
         );
 
+        // Parse a wildcard qualified by a measurement
+        let (_, got) = Field::parse("cpu.*").unwrap();
+        assert_eq!(
+            got,
+            Field {
+                expr: Expr::QualifiedWildcard {
+                    measurement: "cpu".into(),
+                    data_type: None,
+                },
+                alias: None,
+            }
+        );
+        assert_eq!(got.to_string(), "cpu.*");
+
+        let (_, got) = Field::parse("\"cpu.load\".*::field").unwrap();
+        assert_eq!(
+            got,
+            Field {
+                expr: Expr::QualifiedWildcard {
+                    measurement: "cpu.load".into(),
+                    data_type: Some(WildcardType::Field),
+                },
+                alias: None,
+            }
+        );
+        assert_eq!(got.to_string(), "\"cpu.load\".*::field");
+
+        let (_, got) = Field::parse("cpu.*::tag").unwrap();
+        assert_eq!(got.to_string(), "cpu.*::tag");
+
         // Regex
         let (_, got) = Field::parse("/foo/").unwrap();
         assert_eq!(
@@ -939,6 +4272,57 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_field_math_functions() {
+        let (_, got) = Field::parse("ABS(value)").unwrap();
+        assert_eq!(got, Field::new(call!("ABS", var_ref!("value"))));
+
+        let (_, got) = Field::parse("round(value, 2)").unwrap();
+        assert_eq!(
+            got,
+            Field::new(call!(
+                "round",
+                var_ref!("value"),
+                Expr::Literal(Literal::Unsigned(2))
+            ))
+        );
+
+        // An aggregate may be the argument
+        let (_, got) = Field::parse("ROUND(mean(value))").unwrap();
+        assert_eq!(
+            got,
+            Field::new(call!("ROUND", call!("mean", var_ref!("value"))))
+        );
+
+        let (_, got) = Field::parse("CEIL(value) + FLOOR(value)").unwrap();
+        assert_eq!(
+            got,
+            Field::new(binary_op!(
+                call!("CEIL", var_ref!("value")),
+                Add,
+                call!("FLOOR", var_ref!("value"))
+            ))
+        );
+
+        // Fallible cases
+        assert_expect_error!(
+            Field::parse("FLOOR()"),
+            "invalid FLOOR function, expected 1 argument"
+        );
+        assert_expect_error!(
+            Field::parse("abs(a, b)"),
+            "invalid ABS function, expected 1 argument"
+        );
+        assert_expect_error!(
+            Field::parse("mean(CEIL())"),
+            "invalid CEIL function, expected 1 argument"
+        );
+        assert_expect_error!(
+            Field::parse("ROUND(value, 2, 3)"),
+            "invalid ROUND function, expected 1 or 2 arguments"
+        );
+    }
+
     impl Field {
         fn new(expr: Expr) -> Self {
             Self { expr, alias: None }
@@ -982,6 +4366,32 @@ mod test {
         assert_matches!(got, MeasurementSelection::Subquery(_));
     }
 
+    #[test]
+    fn test_subquery_nesting() {
+        let nest = |depth: usize| {
+            format!(
+                "SELECT a FROM {}b{}",
+                "(SELECT a FROM ".repeat(depth),
+                ")".repeat(depth)
+            )
+        };
+
+        let max_depth = crate::ParseLimits::default().max_subquery_depth;
+        let input = nest(max_depth);
+        let (_, got) = select_statement(&input).unwrap();
+        assert_eq!(got.to_string(), input);
+
+        // Fallible cases
+
+        assert_expect_error!(
+            select_statement(&nest(max_depth + 1)),
+            "subquery nesting too deep"
+        );
+
+        // pathological nesting fails gracefully rather than overflowing the stack
+        assert_expect_error!(select_statement(&nest(10_000)), "subquery nesting too deep");
+    }
+
     #[test]
     fn test_from_clause() {
         // Single, exact-match measurement source
@@ -1021,6 +4431,20 @@ mod test {
             from_clause("FROM WHERE"),
             "invalid FROM clause, expected identifier, regular expression or subquery"
         );
+
+        // Negated sources
+        assert_expect_error!(
+            from_clause("FROM !~ /x/"),
+            "regular expression sources cannot be negated; use WHERE instead"
+        );
+        assert_expect_error!(
+            from_clause("FROM NOT /x/"),
+            "regular expression sources cannot be negated; use WHERE instead"
+        );
+        assert_expect_error!(
+            from_clause("FROM !/x/"),
+            "regular expression sources cannot be negated; use WHERE instead"
+        );
     }
 
     #[test]
@@ -1063,6 +4487,9 @@ mod test {
         let (got, _) = group_by_clause("GROUP BY time(5m), foo").unwrap();
         assert_eq!(got, "");
 
+        let (got, _) = group_by_clause("GROUP BY time(5m), host").unwrap();
+        assert_eq!(got, "");
+
         let (got, _) = group_by_clause("GROUP BY time(5m), /foo/, *").unwrap();
         assert_eq!(got, "");
 
@@ -1072,6 +4499,32 @@ mod test {
             group_by_clause("GROUP time(5m)"),
             "invalid GROUP BY clause, expected BY"
         );
+
+        assert_expect_error!(
+            group_by_clause("GROUP BY time(5m), time(1m)"),
+            "invalid GROUP BY clause, multiple TIME dimensions are not permitted"
+        );
+
+        assert_expect_error!(
+            group_by_clause("GROUP BY time(5m), host, TIME(1m, 30s)"),
+            "invalid GROUP BY clause, multiple TIME dimensions are not permitted"
+        );
+    }
+
+    #[test]
+    fn test_group_by_clause_merge() {
+        let group_by = |s: &str| group_by_clause(s).unwrap().1;
+
+        // Overlapping dimensions are included once, in order of appearance
+        let got = group_by("GROUP BY foo, /bar/, *").merge(group_by("GROUP BY *, baz, foo, /bar/"));
+        assert_eq!(got.to_string(), "GROUP BY foo, /bar/, *, baz");
+
+        let got = group_by("GROUP BY foo").merge(group_by("GROUP BY time(5m)"));
+        assert_eq!(got.to_string(), "GROUP BY foo, TIME(5m)");
+
+        // The TIME dimension of self is preferred
+        let got = group_by("GROUP BY time(5m), foo").merge(group_by("GROUP BY bar, time(10m, 1m)"));
+        assert_eq!(got.to_string(), "GROUP BY TIME(5m), foo, bar");
     }
 
     #[test]
@@ -1100,6 +4553,15 @@ mod test {
         let (got, _) = time_call_expression("TIME(5m * 10)").unwrap();
         assert_eq!(got, "");
 
+        // Including for the offset argument
+        let (got, dim) = time_call_expression("TIME(5m, now() - 1m)").unwrap();
+        assert_eq!(got, "");
+        assert_eq!(dim.to_string(), "TIME(5m, now() - 1m)");
+
+        let (got, dim) = time_call_expression("time(5m, 1m + now())").unwrap();
+        assert_eq!(got, "");
+        assert_eq!(dim.to_string(), "TIME(5m, 1m + now())");
+
         // Fallible cases
         assert_expect_error!(
             time_call_expression("TIME"),
@@ -1116,6 +4578,9 @@ mod test {
             time_call_expression("TIME(5m, 3)"),
             "invalid TIME call, expected ')'"
         );
+
+        // Only durations, strings and `now()` may be combined for the offset
+        time_call_expression("TIME(5m, now() - 3)").unwrap_err();
     }
 
     #[test]
@@ -1132,6 +4597,36 @@ mod test {
         let (_, got) = fill_clause("FILL(-18.9)").unwrap();
         assert_matches!(got, FillClause::Value(v) if v == (-18.9).into());
 
+        let (_, got) = fill_clause("FILL(1e3)").unwrap();
+        assert_matches!(got, FillClause::Value(v) if v == 1000.0.into());
+
+        let (_, got) = fill_clause("FILL(-2.5e-1)").unwrap();
+        assert_matches!(got, FillClause::Value(v) if v == (-0.25).into());
+
+        // negative values, with optional whitespace following the sign
+        let (_, got) = fill_clause("FILL(- 18.9)").unwrap();
+        assert_matches!(got, FillClause::Value(v) if v == (-18.9).into());
+
+        let (_, got) = fill_clause("FILL(-5)").unwrap();
+        assert_matches!(got, FillClause::Value(Number::Integer(-5)));
+        assert_eq!(got.to_string(), "FILL(-5)");
+
+        let (_, got) = fill_clause("FILL( - 5 )").unwrap();
+        assert_matches!(got, FillClause::Value(Number::Integer(-5)));
+
+        // negative zero is distinct from zero, and survives a round trip
+        let (_, got) = fill_clause("FILL(-0.0)").unwrap();
+        assert_matches!(got, FillClause::Value(Number::Float(v)) if v == 0.0 && v.is_sign_negative());
+        assert_eq!(got.to_string(), "FILL(-0.0)");
+
+        let (_, got) = fill_clause("FILL(0)").unwrap();
+        assert_matches!(got, FillClause::Value(Number::Integer(0)));
+        assert_eq!(got.to_string(), "FILL(0)");
+
+        let (_, got) = fill_clause("FILL(0.0)").unwrap();
+        assert_matches!(got, FillClause::Value(Number::Float(v)) if v == 0.0 && v.is_sign_positive());
+        assert_eq!(got.to_string(), "FILL(0.0)");
+
         let (_, got) = fill_clause("FILL(previous)").unwrap();
         assert_matches!(got, FillClause::Previous);
 
@@ -1155,11 +4650,44 @@ mod test {
         let (_, got) = timezone_clause("TZ('Australia/Hobart')").unwrap();
         assert_eq!(*got, "Australia/Hobart");
 
+        // Special characters are escaped
+        let got = TimeZoneClause("Aus'tra\nlia\\Ho\"bart".into()).to_string();
+        assert_eq!(got, r#"TZ('Aus\'tra\nlia\\Ho\"bart')"#);
+
         // Fallible cases
         assert_expect_error!(
             timezone_clause("TZ(foo)"),
             "invalid TZ clause, expected string"
         );
+
+        assert_expect_error!(
+            timezone_clause("TZ('Australia/Hobrat')"),
+            "invalid TZ clause, unknown time zone 'Australia/Hobrat'"
+        );
+        assert_expect_error!(
+            timezone_clause("TZ('Not/AZone')"),
+            "invalid TZ clause, unknown time zone 'Not/AZone'"
+        );
+
+        // Escaped characters are unescaped before the zone is resolved
+        assert_expect_error!(
+            timezone_clause(r#"TZ('Aus\'tra\nlia\\Ho\"bart')"#),
+            "invalid TZ clause, unknown time zone 'Aus'tra\nlia\\Ho\"bart'"
+        );
+    }
+
+    #[test]
+    fn test_timezone_tz() {
+        let (_, mut got) =
+            select_statement("SELECT value FROM cpu TZ('Australia/Hobart')").unwrap();
+        assert_eq!(got.timezone_tz(), Some(Ok(chrono_tz::Australia::Hobart)));
+
+        got.timezone = Some(TimeZoneClause("Australia/Hobrat".into()));
+        let err = got.timezone_tz().unwrap().unwrap_err();
+        assert!(err.contains("Australia/Hobrat"), "{}", err);
+
+        let (_, got) = select_statement("SELECT value FROM cpu").unwrap();
+        assert_eq!(got.timezone_tz(), None);
     }
 
     #[test]
@@ -1178,4 +4706,55 @@ mod test {
             "invalid wildcard type specifier, expected TAG or FIELD"
         );
     }
+
+    #[cfg(feature = "serde")]
+    fn assert_serde_round_trip(q: &str) {
+        let (_, stmt) = select_statement(q).unwrap();
+        let json = serde_json::to_string(&stmt).unwrap();
+        let got: SelectStatement = serde_json::from_str(&json).unwrap();
+        assert_eq!(got, stmt, "{}", json);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        assert_serde_round_trip("SELECT value FROM cpu");
+        assert_serde_round_trip(
+            "SELECT mean(usage) * 2, -idle::float, $p AS q INTO db.rp.:MEASUREMENT \
+            FROM db.rp.cpu, /^m/, (SELECT usage FROM cpu WHERE host =~ /^west/) \
+            WHERE region = 'us-west' AND (time > now() - 1h30m OR usage >= 0.5) \
+            GROUP BY time(5m, 1µs), host, /^dc/, * fill(1.5) ORDER BY time DESC \
+            LIMIT 10 OFFSET 2 SLIMIT 3 SOFFSET 1 TZ('Australia/Hobart')",
+        );
+        assert_serde_round_trip("SELECT *::tag, cpu.*, DISTINCT host FROM cpu fill(previous)");
+
+        // An empty list is rejected, as a OneOrMore requires at least one element
+        serde_json::from_str::<FieldList>("[]").unwrap_err();
+    }
+
+    #[cfg(feature = "serde")]
+    proptest::proptest! {
+        #[test]
+        fn prop_serde_round_trip(
+            field in "[a-z][a-z0-9_]{0,8}",
+            func in proptest::sample::select(vec!["mean", "max", "count", "derivative"]),
+            measurement in "[a-z][a-z0-9_]{0,8}",
+            regex in "[a-z.^$]{1,8}",
+            tag in "[a-z][a-z0-9_]{0,8}",
+            tag_value in "[a-z '\\\\]{0,10}",
+            int in 0u64..=u64::MAX,
+            (whole, frac) in (0u32..1_000_000, 0u32..1000),
+            interval in 1i64..10_000,
+            unit in proptest::sample::select(vec!["ns", "µs", "us", "ms", "s", "m", "h", "d", "w"]),
+        ) {
+            let q = format!(
+                "SELECT \"{field}\" + {int}, {func}(\"{field}\") * {whole}.{frac:03} \
+                FROM \"{measurement}\", /{regex}/ \
+                WHERE \"{tag}\" = '{tag_value}' AND time > now() - {interval}{unit} \
+                GROUP BY time({interval}{unit}), \"{tag}\" fill({whole}) LIMIT {int}",
+                tag_value = tag_value.replace('\\', "\\\\").replace('\'', "\\'"),
+            );
+            assert_serde_round_trip(&q);
+        }
+    }
 }
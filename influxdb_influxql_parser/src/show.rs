@@ -9,13 +9,14 @@ use crate::keywords::keyword;
 use crate::show_field_keys::show_field_keys;
 use crate::show_measurements::show_measurements;
 use crate::show_retention_policies::show_retention_policies;
+use crate::show_series::show_series;
 use crate::show_tag_keys::show_tag_keys;
 use crate::show_tag_values::show_tag_values;
 use crate::statement::Statement;
 use nom::branch::alt;
 use nom::character::complete::multispace1;
-use nom::combinator::{map, value};
-use nom::sequence::{pair, preceded};
+use nom::combinator::{map, not, value};
+use nom::sequence::{pair, preceded, terminated};
 use std::fmt::{Display, Formatter};
 
 /// Parse a SHOW statement.
@@ -23,10 +24,18 @@ pub(crate) fn show_statement(i: &str) -> ParseResult<&str, Statement> {
     preceded(
         pair(keyword("SHOW"), multispace1),
         expect(
-            "invalid SHOW statement, expected DATABASES, FIELD, MEASUREMENTS, TAG, or RETENTION following SHOW",
+            "invalid SHOW statement, expected DATABASES, FIELD, MEASUREMENTS, SERIES, TAG, or RETENTION following SHOW",
             alt((
                 // SHOW DATABASES
-                map(show_databases, |s| Statement::ShowDatabases(Box::new(s))),
+                map(
+                    terminated(
+                        show_databases,
+                        unsupported_order_by(
+                            "invalid SHOW DATABASES statement, ORDER BY is not supported",
+                        ),
+                    ),
+                    |s| Statement::ShowDatabases(Box::new(s)),
+                ),
                 // SHOW FIELD KEYS
                 map(show_field_keys, |s| Statement::ShowFieldKeys(Box::new(s))),
                 // SHOW MEASUREMENTS
@@ -34,9 +43,25 @@ pub(crate) fn show_statement(i: &str) -> ParseResult<&str, Statement> {
                     Statement::ShowMeasurements(Box::new(s))
                 }),
                 // SHOW RETENTION POLICIES
-                map(show_retention_policies, |s| {
-                    Statement::ShowRetentionPolicies(Box::new(s))
-                }),
+                map(
+                    terminated(
+                        show_retention_policies,
+                        unsupported_order_by(
+                            "invalid SHOW RETENTION POLICIES statement, ORDER BY is not supported",
+                        ),
+                    ),
+                    |s| Statement::ShowRetentionPolicies(Box::new(s)),
+                ),
+                // SHOW SERIES
+                map(
+                    terminated(
+                        show_series,
+                        unsupported_order_by(
+                            "invalid SHOW SERIES statement, ORDER BY is not supported",
+                        ),
+                    ),
+                    |s| Statement::ShowSeries(Box::new(s)),
+                ),
                 // SHOW TAG
                 show_tag,
             )),
@@ -44,6 +69,20 @@ pub(crate) fn show_statement(i: &str) -> ParseResult<&str, Statement> {
     )(i)
 }
 
+/// Fails with `message` if the input is followed by an `ORDER BY` clause,
+/// for `SHOW` statements which do not support one.
+fn unsupported_order_by<'a>(
+    message: &'static str,
+) -> impl FnMut(&'a str) -> ParseResult<&'a str, ()> {
+    expect(
+        message,
+        not(preceded(
+            multispace1,
+            pair(keyword("ORDER"), preceded(multispace1, keyword("BY"))),
+        )),
+    )
+}
+
 /// Represents a `SHOW DATABASES` statement.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ShowDatabasesStatement;
@@ -117,6 +156,12 @@ mod test {
         let (_, got) = show_statement("SHOW RETENTION POLICIES ON \"foo\"").unwrap();
         assert_eq!(format!("{}", got), "SHOW RETENTION POLICIES ON foo");
 
+        let (_, got) = show_statement("SHOW SERIES FROM cpu WHERE host = 'west'").unwrap();
+        assert_eq!(
+            format!("{}", got),
+            "SHOW SERIES FROM cpu WHERE host = 'west'"
+        );
+
         let (_, got) = show_statement("SHOW TAG KEYS").unwrap();
         assert_eq!(format!("{}", got), "SHOW TAG KEYS");
 
@@ -130,10 +175,29 @@ mod test {
             "invalid SHOW TAG statement, expected KEYS or VALUES"
         );
 
+        // ORDER BY is only supported by some SHOW statements
+        let (_, got) = show_statement("SHOW TAG KEYS ORDER BY DESC").unwrap();
+        assert_eq!(format!("{}", got), "SHOW TAG KEYS ORDER BY TIME DESC");
+
+        assert_expect_error!(
+            show_statement("SHOW DATABASES ORDER BY DESC"),
+            "invalid SHOW DATABASES statement, ORDER BY is not supported"
+        );
+
+        assert_expect_error!(
+            show_statement("SHOW RETENTION POLICIES ON foo ORDER BY time"),
+            "invalid SHOW RETENTION POLICIES statement, ORDER BY is not supported"
+        );
+
+        assert_expect_error!(
+            show_statement("SHOW SERIES FROM cpu ORDER BY time"),
+            "invalid SHOW SERIES statement, ORDER BY is not supported"
+        );
+
         // Unsupported SHOW
         assert_expect_error!(
             show_statement("SHOW FOO"),
-            "invalid SHOW statement, expected DATABASES, FIELD, MEASUREMENTS, TAG, or RETENTION following SHOW"
+            "invalid SHOW statement, expected DATABASES, FIELD, MEASUREMENTS, SERIES, TAG, or RETENTION following SHOW"
         );
     }
 }
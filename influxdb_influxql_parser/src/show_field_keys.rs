@@ -2,7 +2,9 @@
 //!
 //! [sql]: https://docs.influxdata.com/influxdb/v1.8/query_language/explore-schema/#show-field-keys
 
-use crate::common::{limit_clause, offset_clause, LimitClause, OffsetClause};
+use crate::common::{
+    limit_clause, offset_clause, order_by_clause, LimitClause, OffsetClause, OrderByClause,
+};
 use crate::internal::{expect, ParseResult};
 use crate::keywords::keyword;
 use crate::show::{on_clause, OnClause};
@@ -24,6 +26,9 @@ pub struct ShowFieldKeysStatement {
     /// are retrieved.
     pub from: Option<ShowFromClause>,
 
+    /// The order in which the field keys are returned.
+    pub order_by: Option<OrderByClause>,
+
     /// A value to restrict the number of field keys returned.
     pub limit: Option<LimitClause>,
 
@@ -43,6 +48,10 @@ impl fmt::Display for ShowFieldKeysStatement {
             write!(f, " {}", expr)?;
         }
 
+        if let Some(ref order_by) = self.order_by {
+            write!(f, " {}", order_by)?;
+        }
+
         if let Some(ref limit) = self.limit {
             write!(f, " {}", limit)?;
         }
@@ -65,6 +74,7 @@ pub(crate) fn show_field_keys(i: &str) -> ParseResult<&str, ShowFieldKeysStateme
             _, // "KEYS"
             database,
             from,
+            order_by,
             limit,
             offset,
         ),
@@ -77,6 +87,7 @@ pub(crate) fn show_field_keys(i: &str) -> ParseResult<&str, ShowFieldKeysStateme
         ),
         opt(preceded(multispace1, on_clause)),
         opt(preceded(multispace1, show_from_clause)),
+        opt(preceded(multispace1, order_by_clause)),
         opt(preceded(multispace1, limit_clause)),
         opt(preceded(multispace1, offset_clause)),
     ))(i)?;
@@ -86,6 +97,7 @@ pub(crate) fn show_field_keys(i: &str) -> ParseResult<&str, ShowFieldKeysStateme
         ShowFieldKeysStatement {
             database,
             from,
+            order_by,
             limit,
             offset,
         },
@@ -96,6 +108,7 @@ pub(crate) fn show_field_keys(i: &str) -> ParseResult<&str, ShowFieldKeysStateme
 mod test {
     use super::*;
     use crate::assert_expect_error;
+    use crate::common::{MeasurementName, QualifiedMeasurementName};
 
     #[test]
     fn test_show_field_keys() {
@@ -127,12 +140,34 @@ mod test {
         let (_, got) = show_field_keys("FIELD KEYS OFFSET 2").unwrap();
         assert_eq!(format!("{}", got), "SHOW FIELD KEYS OFFSET 2");
 
+        let (_, got) = show_field_keys("FIELD KEYS ORDER BY time").unwrap();
+        assert_eq!(format!("{}", got), "SHOW FIELD KEYS ORDER BY TIME ASC");
+
         // all optional clauses
-        let (_, got) = show_field_keys("FIELD KEYS ON db FROM /foo/ LIMIT 1 OFFSET 2").unwrap();
+        let (_, got) =
+            show_field_keys("FIELD KEYS ON db FROM /foo/ ORDER BY DESC LIMIT 1 OFFSET 2").unwrap();
         assert_eq!(
             format!("{}", got),
-            "SHOW FIELD KEYS ON db FROM /foo/ LIMIT 1 OFFSET 2"
+            "SHOW FIELD KEYS ON db FROM /foo/ ORDER BY TIME DESC LIMIT 1 OFFSET 2"
+        );
+
+        // A regex and several sources are parsed as distinct measurement selections
+        let (_, got) = show_field_keys("FIELD KEYS FROM /^cpu/, db.rp.mem, disk LIMIT 5").unwrap();
+        assert_eq!(got.database, None);
+        assert_eq!(
+            got.from.unwrap().iter().cloned().collect::<Vec<_>>(),
+            vec![
+                QualifiedMeasurementName::new(MeasurementName::Regex("^cpu".into())),
+                QualifiedMeasurementName {
+                    database: Some("db".into()),
+                    retention_policy: Some("rp".into()),
+                    name: MeasurementName::Name("mem".into()),
+                },
+                QualifiedMeasurementName::new(MeasurementName::Name("disk".into())),
+            ]
         );
+        assert_eq!(got.limit, Some(5.into()));
+        assert_eq!(got.offset, None);
 
         // Fallible cases
         assert_expect_error!(
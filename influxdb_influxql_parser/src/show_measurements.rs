@@ -3,8 +3,8 @@
 //! [sql]: https://docs.influxdata.com/influxdb/v1.8/query_language/explore-schema/#show-measurements
 
 use crate::common::{
-    limit_clause, offset_clause, qualified_measurement_name, where_clause, LimitClause,
-    OffsetClause, QualifiedMeasurementName, WhereClause,
+    limit_clause, offset_clause, order_by_clause, qualified_measurement_name, where_clause,
+    LimitClause, OffsetClause, OrderByClause, QualifiedMeasurementName, WhereClause,
 };
 use crate::identifier::{identifier, Identifier};
 use crate::internal::{expect, ParseResult};
@@ -84,6 +84,10 @@ pub struct ShowMeasurementsStatement {
     /// expression to filter the measurement list.
     pub condition: Option<WhereClause>,
 
+    /// Represents the `ORDER BY` clause, which specifies the order
+    /// in which the measurements are returned.
+    pub order_by: Option<OrderByClause>,
+
     /// Represents the `LIMIT` clause, which holds a value to
     /// restrict the number of tag keys returned.
     pub limit: Option<LimitClause>,
@@ -110,6 +114,10 @@ impl fmt::Display for ShowMeasurementsStatement {
             write!(f, " {}", where_clause)?;
         }
 
+        if let Some(ref order_by) = self.order_by {
+            write!(f, " {}", order_by)?;
+        }
+
         if let Some(ref limit) = self.limit {
             write!(f, " {}", limit)?;
         }
@@ -181,6 +189,7 @@ pub(crate) fn show_measurements(i: &str) -> ParseResult<&str, ShowMeasurementsSt
             on_expression,
             measurement_expression,
             condition,
+            order_by,
             limit,
             offset,
         ),
@@ -189,6 +198,7 @@ pub(crate) fn show_measurements(i: &str) -> ParseResult<&str, ShowMeasurementsSt
         opt(preceded(multispace1, extended_on_clause)),
         opt(preceded(multispace1, with_measurement_clause)),
         opt(preceded(multispace1, where_clause)),
+        opt(preceded(multispace1, order_by_clause)),
         opt(preceded(multispace1, limit_clause)),
         opt(preceded(multispace1, offset_clause)),
     ))(i)?;
@@ -199,6 +209,7 @@ pub(crate) fn show_measurements(i: &str) -> ParseResult<&str, ShowMeasurementsSt
             on: on_expression,
             with_measurement: measurement_expression,
             condition,
+            order_by,
             limit,
             offset,
         },
@@ -247,6 +258,7 @@ mod test {
                     name: "bar".into(),
                 })),
                 condition: Some(WhereClause::new(Expr::Literal(true.into()).into())),
+                order_by: None,
                 limit: Some(10.into()),
                 offset: Some(20.into())
             },
@@ -267,6 +279,7 @@ mod test {
                     QualifiedMeasurementName::new(MeasurementName::Regex("bar".into()))
                 )),
                 condition: Some(WhereClause::new(Expr::Literal(true.into()).into())),
+                order_by: None,
                 limit: None,
                 offset: None
             },
@@ -275,6 +288,63 @@ mod test {
             got.to_string(),
             "SHOW MEASUREMENTS ON foo WITH MEASUREMENT =~ /bar/ WHERE true"
         );
+
+        let (_, got) = show_measurements("MEASUREMENTS WHERE true ORDER BY DESC LIMIT 10").unwrap();
+        assert_eq!(
+            got,
+            ShowMeasurementsStatement {
+                condition: Some(WhereClause::new(Expr::Literal(true.into()).into())),
+                order_by: Some(OrderByClause::Descending),
+                limit: Some(10.into()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            got.to_string(),
+            "SHOW MEASUREMENTS WHERE true ORDER BY TIME DESC LIMIT 10"
+        );
+    }
+
+    #[test]
+    fn test_show_measurements_optional_clauses() {
+        // Each optional clause, alone and combined, round-trips through Display
+        for input in [
+            "SHOW MEASUREMENTS",
+            "SHOW MEASUREMENTS ON foo",
+            "SHOW MEASUREMENTS WITH MEASUREMENT = bar",
+            "SHOW MEASUREMENTS WITH MEASUREMENT =~ /bar/",
+            "SHOW MEASUREMENTS WHERE host = 'west'",
+            "SHOW MEASUREMENTS LIMIT 5",
+            "SHOW MEASUREMENTS OFFSET 10",
+            "SHOW MEASUREMENTS ON foo WITH MEASUREMENT =~ /bar/ LIMIT 5",
+            "SHOW MEASUREMENTS WITH MEASUREMENT = bar WHERE host = 'west' OFFSET 10",
+            "SHOW MEASUREMENTS ON foo WHERE host = 'west' LIMIT 5 OFFSET 10",
+        ] {
+            let (rem, got) = show_measurements(&input["SHOW ".len()..]).unwrap();
+            assert_eq!(rem, "");
+            assert_eq!(got.to_string(), input);
+        }
+
+        let (_, got) = show_measurements("MEASUREMENTS OFFSET 10").unwrap();
+        assert_eq!(
+            got,
+            ShowMeasurementsStatement {
+                offset: Some(10.into()),
+                ..Default::default()
+            },
+        );
+
+        let (_, got) = show_measurements("MEASUREMENTS WITH MEASUREMENT = bar LIMIT 5").unwrap();
+        assert_eq!(
+            got,
+            ShowMeasurementsStatement {
+                with_measurement: Some(WithMeasurementClause::Equals(
+                    QualifiedMeasurementName::new(MeasurementName::Name("bar".into()))
+                )),
+                limit: Some(5.into()),
+                ..Default::default()
+            },
+        );
     }
 
     #[test]
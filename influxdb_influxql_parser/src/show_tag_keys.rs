@@ -3,7 +3,8 @@
 //! [sql]: https://docs.influxdata.com/influxdb/v1.8/query_language/explore-schema/#show-tag-keys
 
 use crate::common::{
-    limit_clause, offset_clause, where_clause, LimitClause, OffsetClause, WhereClause,
+    limit_clause, offset_clause, order_by_clause, where_clause, LimitClause, OffsetClause,
+    OrderByClause, WhereClause,
 };
 use crate::internal::ParseResult;
 use crate::keywords::keyword;
@@ -29,6 +30,9 @@ pub struct ShowTagKeysStatement {
     /// A conditional expression to filter the tag keys.
     pub condition: Option<WhereClause>,
 
+    /// The order in which the tag keys are returned.
+    pub order_by: Option<OrderByClause>,
+
     /// A value to restrict the number of tag keys returned.
     pub limit: Option<LimitClause>,
 
@@ -52,6 +56,10 @@ impl fmt::Display for ShowTagKeysStatement {
             write!(f, " {}", cond)?;
         }
 
+        if let Some(ref order_by) = self.order_by {
+            write!(f, " {}", order_by)?;
+        }
+
         if let Some(ref limit) = self.limit {
             write!(f, " {}", limit)?;
         }
@@ -73,6 +81,7 @@ pub(crate) fn show_tag_keys(i: &str) -> ParseResult<&str, ShowTagKeysStatement>
             database,
             from,
             condition,
+            order_by,
             limit,
             offset,
         ),
@@ -81,6 +90,7 @@ pub(crate) fn show_tag_keys(i: &str) -> ParseResult<&str, ShowTagKeysStatement>
         opt(preceded(multispace1, on_clause)),
         opt(preceded(multispace1, show_from_clause)),
         opt(preceded(multispace1, where_clause)),
+        opt(preceded(multispace1, order_by_clause)),
         opt(preceded(multispace1, limit_clause)),
         opt(preceded(multispace1, offset_clause)),
     ))(i)?;
@@ -91,6 +101,7 @@ pub(crate) fn show_tag_keys(i: &str) -> ParseResult<&str, ShowTagKeysStatement>
             database,
             from,
             condition,
+            order_by,
             limit,
             offset,
         },
@@ -128,6 +139,9 @@ mod test {
         let (_, got) = show_tag_keys("KEYS WHERE foo = 'bar'").unwrap();
         assert_eq!(format!("{}", got), "SHOW TAG KEYS WHERE foo = 'bar'");
 
+        let (_, got) = show_tag_keys("KEYS ORDER BY DESC").unwrap();
+        assert_eq!(format!("{}", got), "SHOW TAG KEYS ORDER BY TIME DESC");
+
         let (_, got) = show_tag_keys("KEYS LIMIT 1").unwrap();
         assert_eq!(format!("{}", got), "SHOW TAG KEYS LIMIT 1");
 
@@ -136,10 +150,11 @@ mod test {
 
         // all optional clauses
         let (_, got) =
-            show_tag_keys("KEYS ON db FROM /foo/ WHERE foo = 'bar' LIMIT 1 OFFSET 2").unwrap();
+            show_tag_keys("KEYS ON db FROM /foo/ WHERE foo = 'bar' ORDER BY time LIMIT 1 OFFSET 2")
+                .unwrap();
         assert_eq!(
             format!("{}", got),
-            "SHOW TAG KEYS ON db FROM /foo/ WHERE foo = 'bar' LIMIT 1 OFFSET 2"
+            "SHOW TAG KEYS ON db FROM /foo/ WHERE foo = 'bar' ORDER BY TIME ASC LIMIT 1 OFFSET 2"
         );
 
         // Fallible cases are tested by the various combinator functions
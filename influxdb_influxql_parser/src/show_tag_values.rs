@@ -3,7 +3,8 @@
 //! [sql]: https://docs.influxdata.com/influxdb/v1.8/query_language/explore-schema/#show-tag-values
 
 use crate::common::{
-    limit_clause, offset_clause, where_clause, LimitClause, OffsetClause, OneOrMore, WhereClause,
+    limit_clause, offset_clause, order_by_clause, where_clause, LimitClause, OffsetClause,
+    OneOrMore, OrderByClause, WhereClause,
 };
 use crate::identifier::{identifier, Identifier};
 use crate::internal::{expect, ParseResult};
@@ -37,6 +38,9 @@ pub struct ShowTagValuesStatement {
     /// A conditional expression to filter the tag keys.
     pub condition: Option<WhereClause>,
 
+    /// The order in which the tag values are returned.
+    pub order_by: Option<OrderByClause>,
+
     /// A value to restrict the number of tag keys returned.
     pub limit: Option<LimitClause>,
 
@@ -62,6 +66,10 @@ impl Display for ShowTagValuesStatement {
             write!(f, " {}", where_clause)?;
         }
 
+        if let Some(ref order_by) = self.order_by {
+            write!(f, " {}", order_by)?;
+        }
+
         if let Some(ref limit) = self.limit {
             write!(f, " {}", limit)?;
         }
@@ -84,6 +92,7 @@ pub(crate) fn show_tag_values(i: &str) -> ParseResult<&str, ShowTagValuesStateme
             from,
             with_key,
             condition,
+            order_by,
             limit,
             offset,
         ),
@@ -96,6 +105,7 @@ pub(crate) fn show_tag_values(i: &str) -> ParseResult<&str, ShowTagValuesStateme
             preceded(multispace1, with_key_clause),
         ),
         opt(preceded(multispace1, where_clause)),
+        opt(preceded(multispace1, order_by_clause)),
         opt(preceded(multispace1, limit_clause)),
         opt(preceded(multispace1, offset_clause)),
     ))(i)?;
@@ -107,6 +117,7 @@ pub(crate) fn show_tag_values(i: &str) -> ParseResult<&str, ShowTagValuesStateme
             from,
             with_key,
             condition,
+            order_by,
             limit,
             offset,
         },
@@ -220,7 +231,7 @@ fn with_key_clause(i: &str) -> ParseResult<&str, WithKeyClause> {
                 ),
                 map(
                     preceded(
-                        preceded(multispace1, tag("IN")),
+                        preceded(multispace1, keyword("IN")),
                         expect(
                             "invalid WITH KEY clause, expected identifier list following IN",
                             identifier_list,
@@ -290,19 +301,39 @@ mod test {
             "SHOW TAG VALUES WITH KEY = some_key OFFSET 2"
         );
 
+        let (_, got) = show_tag_values("VALUES WITH KEY = some_key ORDER BY time DESC").unwrap();
+        assert_eq!(
+            format!("{}", got),
+            "SHOW TAG VALUES WITH KEY = some_key ORDER BY TIME DESC"
+        );
+
         // all optional clauses
         let (_, got) = show_tag_values(
-            "VALUES ON db FROM /foo/ WITH KEY = some_key WHERE foo = 'bar' LIMIT 1 OFFSET 2",
+            "VALUES ON db FROM /foo/ WITH KEY = some_key WHERE foo = 'bar' ORDER BY ASC LIMIT 1 OFFSET 2",
         )
         .unwrap();
         assert_eq!(
             format!("{}", got),
-            "SHOW TAG VALUES ON db FROM /foo/ WITH KEY = some_key WHERE foo = 'bar' LIMIT 1 OFFSET 2"
+            "SHOW TAG VALUES ON db FROM /foo/ WITH KEY = some_key WHERE foo = 'bar' ORDER BY TIME ASC LIMIT 1 OFFSET 2"
         );
 
         let (_, got) = show_tag_values("VALUES WITH KEY IN( foo )").unwrap();
         assert_eq!(format!("{}", got), "SHOW TAG VALUES WITH KEY IN (foo)");
 
+        // IN list with all optional clauses
+        let input =
+            "SHOW TAG VALUES ON db FROM cpu, /mem/ WITH KEY IN (host, region, \"foo bar\") \
+                     WHERE foo = 'bar' LIMIT 1 OFFSET 2";
+        let (_, got) = show_tag_values(&input["SHOW TAG ".len()..]).unwrap();
+        assert_eq!(format!("{}", got), input);
+
+        // IN is case insensitive
+        let (_, got) = show_tag_values("VALUES with key in (host, region)").unwrap();
+        assert_eq!(
+            format!("{}", got),
+            "SHOW TAG VALUES WITH KEY IN (host, region)"
+        );
+
         // Fallible cases are tested by the various combinator functions
     }
 
@@ -337,6 +368,12 @@ mod test {
         let (_, got) = with_key_clause("WITH KEY=foo").unwrap();
         assert_eq!(got, WithKeyClause::Eq("foo".into()));
 
+        let (_, got) = with_key_clause("with key in(foo,bar)").unwrap();
+        assert_eq!(
+            got,
+            WithKeyClause::In(InList::new(vec!["foo".into(), "bar".into()]))
+        );
+
         // Fallible cases
 
         assert_expect_error!(
@@ -4,7 +4,7 @@ use crate::common::{
     qualified_measurement_name, MeasurementName, OneOrMore, Parser, QualifiedMeasurementName,
 };
 use crate::identifier::{identifier, Identifier};
-use crate::internal::ParseResult;
+use crate::internal::{verify, ParseResult};
 use crate::keywords::keyword;
 use nom::character::complete::multispace1;
 use nom::sequence::{pair, preceded};
@@ -89,6 +89,15 @@ impl Parser for Identifier {
 }
 
 /// Represents a `FROM` clause for a `DELETE` statement.
+///
+/// A `DELETE` targets exactly one measurement, expressed as either an
+/// [`Identifier`] or a regular expression, which may match many measurements.
+///
+/// ```text
+/// from_clause ::= "FROM" measurement_name
+///
+/// measurement_name ::= identifier | regex_lit
+/// ```
 pub type DeleteFromClause = FromMeasurementClause<MeasurementName>;
 
 impl Display for DeleteFromClause {
@@ -103,12 +112,17 @@ impl Display for DeleteFromClause {
 
 /// Parse a `FROM` clause for a `DELETE` statement.
 pub(crate) fn delete_from_clause(i: &str) -> ParseResult<&str, DeleteFromClause> {
-    from_clause(i)
+    verify(
+        "DELETE targets a single measurement or regex",
+        from_clause,
+        |v: &DeleteFromClause| v.len() == 1,
+    )(i)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::assert_expect_error;
 
     #[test]
     fn test_show_from_clause() {
@@ -165,16 +179,22 @@ mod test {
         let (_, from) = delete_from_clause("FROM /reg/").unwrap();
         assert_eq!(from, DeleteFromClause::new(vec![Regex("reg".into())]));
 
-        let (_, from) = delete_from_clause("FROM c, /reg/").unwrap();
-        assert_eq!(
-            from,
-            DeleteFromClause::new(vec![Name("c".into()), Regex("reg".into())])
-        );
-
         // Demonstrate that the 3-part name is not parsed
         let (i, from) = delete_from_clause("FROM a.b.c").unwrap();
         assert_eq!(from, DeleteFromClause::new(vec![Name("a".into())]));
         // The remaining input will fail in a later parser
         assert_eq!(i, ".b.c");
+
+        // Fallible cases
+
+        assert_expect_error!(
+            delete_from_clause("FROM c, /reg/"),
+            "DELETE targets a single measurement or regex"
+        );
+
+        assert_expect_error!(
+            delete_from_clause("FROM /reg/, c"),
+            "DELETE targets a single measurement or regex"
+        );
     }
 }
@@ -1,18 +1,21 @@
 //! Types and parsers for an InfluxQL statement.
 
 use crate::delete::{delete_statement, DeleteStatement};
-use crate::drop::{drop_statement, DropMeasurementStatement};
+use crate::drop::{drop_statement, DropMeasurementStatement, DropSeriesStatement};
 use crate::explain::{explain_statement, ExplainStatement};
-use crate::internal::ParseResult;
+use crate::internal::{expect, ParseResult};
+use crate::keywords::keyword;
 use crate::select::{select_statement, SelectStatement};
 use crate::show::{show_statement, ShowDatabasesStatement};
 use crate::show_field_keys::ShowFieldKeysStatement;
 use crate::show_measurements::ShowMeasurementsStatement;
 use crate::show_retention_policies::ShowRetentionPoliciesStatement;
+use crate::show_series::ShowSeriesStatement;
 use crate::show_tag_keys::ShowTagKeysStatement;
 use crate::show_tag_values::ShowTagValuesStatement;
 use nom::branch::alt;
-use nom::combinator::map;
+use nom::combinator::{fail, map, peek};
+use nom::sequence::preceded;
 use std::fmt::{Display, Formatter};
 
 /// An InfluxQL statement.
@@ -22,6 +25,8 @@ pub enum Statement {
     Delete(Box<DeleteStatement>),
     /// Represents a `DROP MEASUREMENT` statement.
     DropMeasurement(Box<DropMeasurementStatement>),
+    /// Represents a `DROP SERIES` statement.
+    DropSeries(Box<DropSeriesStatement>),
     /// Represents an `EXPLAIN` statement.
     Explain(Box<ExplainStatement>),
     /// Represents a `SELECT` statement.
@@ -32,6 +37,8 @@ pub enum Statement {
     ShowMeasurements(Box<ShowMeasurementsStatement>),
     /// Represents a `SHOW RETENTION POLICIES` statement.
     ShowRetentionPolicies(Box<ShowRetentionPoliciesStatement>),
+    /// Represents a `SHOW SERIES` statement.
+    ShowSeries(Box<ShowSeriesStatement>),
     /// Represents a `SHOW TAG KEYS` statement.
     ShowTagKeys(Box<ShowTagKeysStatement>),
     /// Represents a `SHOW TAG VALUES` statement.
@@ -45,11 +52,13 @@ impl Display for Statement {
         match self {
             Self::Delete(s) => Display::fmt(s, f),
             Self::DropMeasurement(s) => Display::fmt(s, f),
+            Self::DropSeries(s) => Display::fmt(s, f),
             Self::Explain(s) => Display::fmt(s, f),
             Self::Select(s) => Display::fmt(s, f),
             Self::ShowDatabases(s) => Display::fmt(s, f),
             Self::ShowMeasurements(s) => Display::fmt(s, f),
             Self::ShowRetentionPolicies(s) => Display::fmt(s, f),
+            Self::ShowSeries(s) => Display::fmt(s, f),
             Self::ShowTagKeys(s) => Display::fmt(s, f),
             Self::ShowTagValues(s) => Display::fmt(s, f),
             Self::ShowFieldKeys(s) => Display::fmt(s, f),
@@ -61,15 +70,37 @@ impl Display for Statement {
 pub fn statement(i: &str) -> ParseResult<&str, Statement> {
     alt((
         map(delete_statement, |s| Statement::Delete(Box::new(s))),
-        map(drop_statement, |s| Statement::DropMeasurement(Box::new(s))),
+        drop_statement,
         map(explain_statement, |s| Statement::Explain(Box::new(s))),
         map(select_statement, |s| Statement::Select(Box::new(s))),
         show_statement,
+        unsupported_statement,
+    ))(i)
+}
+
+/// Recognize statements that are valid InfluxQL but are not supported, to
+/// return a more helpful error than a syntax error.
+fn unsupported_statement(i: &str) -> ParseResult<&str, Statement> {
+    fn unsupported<'a>(
+        kw: &'static str,
+        message: &'static str,
+    ) -> impl FnMut(&'a str) -> ParseResult<&'a str, Statement> {
+        preceded(peek(keyword(kw)), expect(message, fail))
+    }
+
+    alt((
+        unsupported("ALTER", "statement type not supported: ALTER"),
+        unsupported("CREATE", "statement type not supported: CREATE"),
+        unsupported("GRANT", "statement type not supported: GRANT"),
+        unsupported("KILL", "statement type not supported: KILL"),
+        unsupported("REVOKE", "statement type not supported: REVOKE"),
+        unsupported("SET", "statement type not supported: SET"),
     ))(i)
 }
 
 #[cfg(test)]
 mod test {
+    use crate::assert_expect_error;
     use crate::statement;
 
     #[test]
@@ -84,6 +115,9 @@ mod test {
         let (got, _) = statement("DROP MEASUREMENT foo").unwrap();
         assert_eq!(got, "");
 
+        let (got, _) = statement("DROP SERIES WHERE host = 'a'").unwrap();
+        assert_eq!(got, "");
+
         // explain_statement combinator
         let (got, _) = statement("EXPLAIN SELECT * FROM cpu").unwrap();
         assert_eq!(got, "");
@@ -95,4 +129,25 @@ mod test {
         let (got, _) = statement("SHOW TAG KEYS").unwrap();
         assert_eq!(got, "");
     }
+
+    #[test]
+    fn test_unsupported_statement() {
+        assert_expect_error!(
+            statement("KILL QUERY 36"),
+            "statement type not supported: KILL"
+        );
+
+        assert_expect_error!(
+            statement("create database foo"),
+            "statement type not supported: CREATE"
+        );
+
+        assert_expect_error!(
+            statement("GRANT ALL TO jdoe"),
+            "statement type not supported: GRANT"
+        );
+
+        // Fails with a syntax error, as the keyword must be followed by a separator
+        statement("KILLQUERY 36").unwrap_err();
+    }
 }
@@ -66,8 +66,8 @@ pub(crate) fn single_quoted_string(i: &str) -> ParseResult<&str, String> {
     let escaped = preceded(
         char('\\'),
         expect(
-            r#"invalid escape sequence, expected \\, \' or \n"#,
-            alt((char('\\'), char('\''), value('\n', char('n')))),
+            r#"invalid escape sequence, expected \\, \', \" or \n"#,
+            alt((char('\\'), char('\''), char('"'), value('\n', char('n')))),
         ),
     );
 
@@ -159,6 +159,7 @@ fn regex_literal(i: &str) -> ParseResult<&str, &str> {
 
 /// An unescaped regular expression.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Regex(pub(crate) String);
 
 impl_tuple_clause!(Regex, String);
@@ -177,6 +178,13 @@ impl From<&str> for Regex {
     }
 }
 
+impl Regex {
+    /// Compiles the unescaped pattern of the regular expression.
+    pub fn compile(&self) -> Result<regex::Regex, regex::Error> {
+        regex::Regex::new(&self.0)
+    }
+}
+
 /// Parse a regular expression, delimited by `/`.
 pub(crate) fn regex(i: &str) -> ParseResult<&str, Regex> {
     map(
@@ -265,6 +273,11 @@ mod test {
         let (_, got) = single_quoted_string(r#"'\n\''"#).unwrap();
         assert_eq!(got, "\n'");
 
+        // an escaped double quote, as written by the Display
+        // implementation of string literals
+        let (_, got) = single_quoted_string(r#"'\"quick\\draw\"'"#).unwrap();
+        assert_eq!(got, r#""quick\draw""#);
+
         // literal tab
         let (_, got) = single_quoted_string("'quick\tdraw'").unwrap();
         assert_eq!(got, "quick\tdraw");
@@ -290,7 +303,7 @@ mod test {
         // Invalid escape
         assert_expect_error!(
             single_quoted_string(r#"'quick\idraw'"#),
-            r#"invalid escape sequence, expected \\, \' or \n"#
+            r#"invalid escape sequence, expected \\, \', \" or \n"#
         );
     }
 
@@ -0,0 +1,293 @@
+//! Split InfluxQL into tokens, for uses such as syntax highlighting, which
+//! require the lexical structure of the input without a full parse.
+
+use crate::identifier::unquoted_identifier;
+use crate::internal::ParseResult;
+use crate::keywords::{is_keyword, sql_keyword};
+use crate::literal::{duration, number};
+use crate::parameter::parameter;
+use crate::string::{double_quoted_string, regex, single_quoted_string};
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_until};
+use nom::character::complete::{not_line_ending, one_of};
+use nom::combinator::{fail, map, peek, rest, value};
+use nom::sequence::{delimited, preceded};
+use std::ops::Range;
+
+/// The kind of a [`Token`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// A reserved keyword, such as `SELECT`.
+    Keyword,
+    /// An unquoted or double-quoted identifier.
+    Identifier,
+    /// A single-quoted string literal.
+    String,
+    /// An integer or floating point number.
+    Number,
+    /// A duration literal, such as `1h30m`.
+    Duration,
+    /// A regular expression literal, such as `/^cpu/`.
+    Regex,
+    /// A bind parameter, such as `$host`.
+    BindParameter,
+    /// An operator, such as `=~` or `+`.
+    Operator,
+    /// Punctuation, such as `(` or `,`.
+    Punctuation,
+    /// A line (`--`) or block (`/* */`) comment.
+    Comment,
+    /// Input that could not be recognized as any other kind of token.
+    Error,
+}
+
+/// A token of InfluxQL and its location within the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// The kind of the token.
+    pub kind: TokenKind,
+    /// The byte range of the token within the input.
+    pub span: Range<usize>,
+    /// The text of the token.
+    pub text: &'a str,
+}
+
+/// Returns an iterator over the tokens of `input`, skipping any whitespace.
+///
+/// The tokenizer never fails. Any unrecognized character is returned as a
+/// token of kind [`TokenKind::Error`], as is the remainder of the input
+/// following an incomplete token, such as an unterminated string.
+pub fn tokens(input: &str) -> impl Iterator<Item = Token<'_>> {
+    Tokens {
+        input,
+        pos: 0,
+        prev: None,
+    }
+}
+
+struct Tokens<'a> {
+    input: &'a str,
+    pos: usize,
+    /// The previous token, excluding comments, which determines whether
+    /// a `/` begins a regular expression or is the division operator.
+    prev: Option<(TokenKind, &'a str)>,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.input[self.pos..].trim_start_matches([' ', '\t', '\r', '\n']);
+        let start = self.input.len() - i.len();
+        let first = i.chars().next()?;
+
+        let regex_allowed = match self.prev {
+            None | Some((TokenKind::Keyword | TokenKind::Operator, _)) => true,
+            Some((TokenKind::Punctuation, text)) => text != ")",
+            _ => false,
+        };
+
+        let (remaining, kind) = match token(i, regex_allowed) {
+            Ok(v) => v,
+            // An incomplete token consumes the remainder of the input
+            Err(nom::Err::Failure(_) | nom::Err::Incomplete(_)) => ("", TokenKind::Error),
+            Err(nom::Err::Error(_)) => (&i[first.len_utf8()..], TokenKind::Error),
+        };
+
+        let end = self.input.len() - remaining.len();
+        let text = &self.input[start..end];
+        self.pos = end;
+        if kind != TokenKind::Comment {
+            self.prev = Some((kind, text));
+        }
+
+        Some(Token {
+            kind,
+            span: start..end,
+            text,
+        })
+    }
+}
+
+/// Recognize a single token, returning its kind.
+fn token(i: &str, regex_allowed: bool) -> ParseResult<&str, TokenKind> {
+    alt((
+        value(TokenKind::Comment, comment),
+        value(TokenKind::Keyword, sql_keyword),
+        map(unquoted_identifier, |v| {
+            // A keyword not followed by whitespace or punctuation, such as `SELECT*`
+            if is_keyword(v) {
+                TokenKind::Keyword
+            } else {
+                TokenKind::Identifier
+            }
+        }),
+        value(TokenKind::Identifier, double_quoted_string),
+        value(TokenKind::String, single_quoted_string),
+        value(TokenKind::BindParameter, parameter),
+        preceded(
+            peek(one_of("0123456789.")),
+            alt((
+                value(TokenKind::Duration, duration),
+                value(TokenKind::Number, number),
+            )),
+        ),
+        move |i| {
+            if regex_allowed {
+                value(TokenKind::Regex, regex)(i)
+            } else {
+                fail(i)
+            }
+        },
+        value(
+            TokenKind::Operator,
+            alt((
+                tag("=~"),
+                tag("!~"),
+                tag("!="),
+                tag("<>"),
+                tag("<="),
+                tag(">="),
+                tag("::"),
+                alt((
+                    tag("="),
+                    tag("<"),
+                    tag(">"),
+                    tag("+"),
+                    tag("-"),
+                    tag("*"),
+                    tag("/"),
+                    tag("%"),
+                    tag("&"),
+                    tag("|"),
+                    tag("^"),
+                )),
+            )),
+        ),
+        value(
+            TokenKind::Punctuation,
+            alt((tag("("), tag(")"), tag(","), tag(";"), tag("."))),
+        ),
+    ))(i)
+}
+
+/// Recognize a line or block comment. An unterminated block comment extends
+/// to the end of the input.
+fn comment(i: &str) -> ParseResult<&str, &str> {
+    alt((
+        preceded(tag("--"), not_line_ending),
+        delimited(tag("/*"), take_until("*/"), tag("*/")),
+        preceded(tag("/*"), rest),
+    ))(i)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use TokenKind::*;
+
+    fn kinds(input: &str) -> Vec<(TokenKind, &str)> {
+        tokens(input).map(|t| (t.kind, t.text)).collect()
+    }
+
+    #[test]
+    fn test_tokens() {
+        let got = kinds(
+            "SELECT mean(\"usage idle\") / 2 FROM cpu, /^mem/ \
+             WHERE host =~ /^a/ AND time > now() - 1h30m AND v::field != $v; -- last hour",
+        );
+        assert_eq!(
+            got,
+            vec![
+                (Keyword, "SELECT"),
+                (Identifier, "mean"),
+                (Punctuation, "("),
+                (Identifier, "\"usage idle\""),
+                (Punctuation, ")"),
+                (Operator, "/"),
+                (Number, "2"),
+                (Keyword, "FROM"),
+                (Identifier, "cpu"),
+                (Punctuation, ","),
+                (Regex, "/^mem/"),
+                (Keyword, "WHERE"),
+                (Identifier, "host"),
+                (Operator, "=~"),
+                (Regex, "/^a/"),
+                (Keyword, "AND"),
+                (Identifier, "time"),
+                (Operator, ">"),
+                (Identifier, "now"),
+                (Punctuation, "("),
+                (Punctuation, ")"),
+                (Operator, "-"),
+                (Duration, "1h30m"),
+                (Keyword, "AND"),
+                (Identifier, "v"),
+                (Operator, "::"),
+                (Keyword, "field"),
+                (Operator, "!="),
+                (BindParameter, "$v"),
+                (Punctuation, ";"),
+                (Comment, "-- last hour"),
+            ]
+        );
+
+        // Keywords are case-insensitive and need not be followed by whitespace
+        assert_eq!(
+            kinds("select*from /* all */ db..cpu LIMIT 1.5"),
+            vec![
+                (Keyword, "select"),
+                (Operator, "*"),
+                (Keyword, "from"),
+                (Comment, "/* all */"),
+                (Identifier, "db"),
+                (Punctuation, "."),
+                (Punctuation, "."),
+                (Identifier, "cpu"),
+                (Keyword, "LIMIT"),
+                (Number, "1.5"),
+            ]
+        );
+
+        // Spans are byte offsets into the input
+        let got = tokens("SELECT  'ü' FROM").collect::<Vec<_>>();
+        assert_eq!(got[1].span, 8..12);
+        assert_eq!(got[2].span, 13..17);
+
+        assert_eq!(kinds(""), vec![]);
+        assert_eq!(kinds(" \n\t"), vec![]);
+    }
+
+    #[test]
+    fn test_tokens_partial_input() {
+        // Unknown characters
+        assert_eq!(
+            kinds("SELECT # x"),
+            vec![(Keyword, "SELECT"), (Error, "#"), (Identifier, "x")]
+        );
+
+        // Incomplete tokens consume the remainder of the input
+        assert_eq!(
+            kinds("SELECT 'foo FROM cpu"),
+            vec![(Keyword, "SELECT"), (Error, "'foo FROM cpu")]
+        );
+        assert_eq!(
+            kinds("SELECT \"foo"),
+            vec![(Keyword, "SELECT"), (Error, "\"foo")]
+        );
+        assert_eq!(
+            kinds("SELECT * FROM /cpu"),
+            vec![
+                (Keyword, "SELECT"),
+                (Operator, "*"),
+                (Keyword, "FROM"),
+                (Error, "/cpu")
+            ]
+        );
+        assert_eq!(
+            kinds("SELECT /* comment"),
+            vec![(Keyword, "SELECT"), (Comment, "/* comment")]
+        );
+    }
+}
@@ -26,12 +26,12 @@ use crate::common::{
     WhereClause,
 };
 use crate::delete::DeleteStatement;
-use crate::drop::DropMeasurementStatement;
+use crate::drop::{DropMeasurementStatement, DropSeriesStatement};
 use crate::explain::ExplainStatement;
 use crate::expression::arithmetic::Expr;
 use crate::expression::conditional::ConditionalExpression;
 use crate::select::{
-    Dimension, Field, FieldList, FillClause, FromMeasurementClause, GroupByClause,
+    Dimension, Field, FieldList, FillClause, FromMeasurementClause, GroupByClause, IntoClause,
     MeasurementSelection, SLimitClause, SOffsetClause, SelectStatement, TimeZoneClause,
 };
 use crate::show::{OnClause, ShowDatabasesStatement};
@@ -40,6 +40,7 @@ use crate::show_measurements::{
     ExtendedOnClause, ShowMeasurementsStatement, WithMeasurementClause,
 };
 use crate::show_retention_policies::ShowRetentionPoliciesStatement;
+use crate::show_series::ShowSeriesStatement;
 use crate::show_tag_keys::ShowTagKeysStatement;
 use crate::show_tag_values::{ShowTagValuesStatement, WithKeyClause};
 use crate::simple_from_clause::{DeleteFromClause, ShowFromClause};
@@ -118,6 +119,19 @@ pub trait Visitor: Sized {
         Ok(self)
     }
 
+    /// Invoked before any children of the `DROP SERIES` statement are visited.
+    fn pre_visit_drop_series_statement(
+        self,
+        _n: &DropSeriesStatement,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `DROP SERIES` statement are visited.
+    fn post_visit_drop_series_statement(self, _n: &DropSeriesStatement) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
     /// Invoked before any children of the `EXPLAIN` statement are visited.
     fn pre_visit_explain_statement(self, _n: &ExplainStatement) -> VisitorResult<Recursion<Self>> {
         Ok(Continue(self))
@@ -186,6 +200,19 @@ pub trait Visitor: Sized {
         Ok(self)
     }
 
+    /// Invoked before any children of the `SHOW SERIES` statement are visited.
+    fn pre_visit_show_series_statement(
+        self,
+        _n: &ShowSeriesStatement,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `SHOW SERIES` statement are visited.
+    fn post_visit_show_series_statement(self, _n: &ShowSeriesStatement) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
     /// Invoked before any children of the `SHOW TAG KEYS` statement are visited.
     fn pre_visit_show_tag_keys_statement(
         self,
@@ -274,6 +301,16 @@ pub trait Visitor: Sized {
         Ok(self)
     }
 
+    /// Invoked before the `INTO` clause of a `SELECT` statement is visited.
+    fn pre_visit_select_into_clause(self, _n: &IntoClause) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after the `INTO` clause of a `SELECT` statement is visited.
+    fn post_visit_select_into_clause(self, _n: &IntoClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
     /// Invoked before any children of the `FROM` clause of a `SELECT` statement are visited.
     fn pre_visit_select_from_clause(
         self,
@@ -489,11 +526,13 @@ impl Visitable for Statement {
         let visitor = match self {
             Self::Delete(s) => s.accept(visitor),
             Self::DropMeasurement(s) => s.accept(visitor),
+            Self::DropSeries(s) => s.accept(visitor),
             Self::Explain(s) => s.accept(visitor),
             Self::Select(s) => s.accept(visitor),
             Self::ShowDatabases(s) => s.accept(visitor),
             Self::ShowMeasurements(s) => s.accept(visitor),
             Self::ShowRetentionPolicies(s) => s.accept(visitor),
+            Self::ShowSeries(s) => s.accept(visitor),
             Self::ShowTagKeys(s) => s.accept(visitor),
             Self::ShowTagValues(s) => s.accept(visitor),
             Self::ShowFieldKeys(s) => s.accept(visitor),
@@ -575,6 +614,30 @@ impl Visitable for DropMeasurementStatement {
     }
 }
 
+impl Visitable for DropSeriesStatement {
+    fn accept<V: Visitor>(&self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_drop_series_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = match self {
+            Self::FromWhere { from, condition } => {
+                let visitor = from.accept(visitor)?;
+
+                if let Some(condition) = condition {
+                    condition.accept(visitor)
+                } else {
+                    Ok(visitor)
+                }
+            }
+            Self::Where(condition) => condition.accept(visitor),
+        }?;
+
+        visitor.post_visit_drop_series_statement(self)
+    }
+}
+
 impl Visitable for ExplainStatement {
     fn accept<V: Visitor>(&self, visitor: V) -> VisitorResult<V> {
         let visitor = match visitor.pre_visit_explain_statement(self)? {
@@ -597,6 +660,12 @@ impl Visitable for SelectStatement {
 
         let visitor = self.fields.accept(visitor)?;
 
+        let visitor = if let Some(into) = &self.into {
+            into.accept(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
         let visitor = self.from.accept(visitor)?;
 
         let visitor = if let Some(condition) = &self.condition {
@@ -657,6 +726,17 @@ impl Visitable for SelectStatement {
     }
 }
 
+impl Visitable for IntoClause {
+    fn accept<V: Visitor>(&self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_select_into_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_select_into_clause(self)
+    }
+}
+
 impl Visitable for TimeZoneClause {
     fn accept<V: Visitor>(&self, visitor: V) -> VisitorResult<V> {
         let visitor = match visitor.pre_visit_timezone_clause(self)? {
@@ -772,6 +852,12 @@ impl Visitable for ShowMeasurementsStatement {
             Ok(visitor)
         }?;
 
+        let visitor = if let Some(order_by) = &self.order_by {
+            order_by.accept(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
         let visitor = if let Some(limit) = &self.limit {
             limit.accept(visitor)
         } else {
@@ -858,6 +944,47 @@ impl Visitable for QualifiedMeasurementName {
     }
 }
 
+impl Visitable for ShowSeriesStatement {
+    fn accept<V: Visitor>(&self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_show_series_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = if let Some(on_clause) = &self.database {
+            on_clause.accept(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(from) = &self.from {
+            from.accept(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(condition) = &self.condition {
+            condition.accept(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(limit) = &self.limit {
+            limit.accept(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(offset) = &self.offset {
+            offset.accept(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        visitor.post_visit_show_series_statement(self)
+    }
+}
+
 impl Visitable for ShowTagKeysStatement {
     fn accept<V: Visitor>(&self, visitor: V) -> VisitorResult<V> {
         let visitor = match visitor.pre_visit_show_tag_keys_statement(self)? {
@@ -883,6 +1010,12 @@ impl Visitable for ShowTagKeysStatement {
             Ok(visitor)
         }?;
 
+        let visitor = if let Some(order_by) = &self.order_by {
+            order_by.accept(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
         let visitor = if let Some(limit) = &self.limit {
             limit.accept(visitor)
         } else {
@@ -926,6 +1059,12 @@ impl Visitable for ShowTagValuesStatement {
             Ok(visitor)
         }?;
 
+        let visitor = if let Some(order_by) = &self.order_by {
+            order_by.accept(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
         let visitor = if let Some(limit) = &self.limit {
             limit.accept(visitor)
         } else {
@@ -961,6 +1100,12 @@ impl Visitable for ShowFieldKeysStatement {
             Ok(visitor)
         }?;
 
+        let visitor = if let Some(order_by) = &self.order_by {
+            order_by.accept(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
         let visitor = if let Some(limit) = &self.limit {
             limit.accept(visitor)
         } else {
@@ -1118,6 +1263,7 @@ impl Visitable for Expr {
             | Self::BindParameter(_)
             | Self::Literal(_)
             | Self::Wildcard(_)
+            | Self::QualifiedWildcard { .. }
             | Self::Distinct(_) => Ok(visitor),
         }?;
 
@@ -1143,12 +1289,12 @@ mod test {
         WhereClause,
     };
     use crate::delete::DeleteStatement;
-    use crate::drop::DropMeasurementStatement;
+    use crate::drop::{DropMeasurementStatement, DropSeriesStatement};
     use crate::explain::ExplainStatement;
     use crate::expression::arithmetic::Expr;
     use crate::expression::conditional::ConditionalExpression;
     use crate::select::{
-        Dimension, Field, FieldList, FillClause, FromMeasurementClause, GroupByClause,
+        Dimension, Field, FieldList, FillClause, FromMeasurementClause, GroupByClause, IntoClause,
         MeasurementSelection, SLimitClause, SOffsetClause, SelectStatement, TimeZoneClause,
     };
     use crate::show::{OnClause, ShowDatabasesStatement};
@@ -1157,6 +1303,7 @@ mod test {
         ExtendedOnClause, ShowMeasurementsStatement, WithMeasurementClause,
     };
     use crate::show_retention_policies::ShowRetentionPoliciesStatement;
+    use crate::show_series::ShowSeriesStatement;
     use crate::show_tag_keys::ShowTagKeysStatement;
     use crate::show_tag_values::{ShowTagValuesStatement, WithKeyClause};
     use crate::simple_from_clause::{DeleteFromClause, ShowFromClause};
@@ -1235,6 +1382,17 @@ mod test {
             Ok(self.push_post("drop_measurement_statement", n))
         }
 
+        fn pre_visit_drop_series_statement(
+            self,
+            n: &DropSeriesStatement,
+        ) -> VisitorResult<Recursion<Self>> {
+            Ok(Continue(self.push_pre("drop_series_statement", n)))
+        }
+
+        fn post_visit_drop_series_statement(self, n: &DropSeriesStatement) -> VisitorResult<Self> {
+            Ok(self.push_post("drop_series_statement", n))
+        }
+
         fn pre_visit_explain_statement(
             self,
             n: &ExplainStatement,
@@ -1298,6 +1456,17 @@ mod test {
             Ok(self.push_post("show_retention_policies_statement", n))
         }
 
+        fn pre_visit_show_series_statement(
+            self,
+            n: &ShowSeriesStatement,
+        ) -> VisitorResult<Recursion<Self>> {
+            Ok(Continue(self.push_pre("show_series_statement", n)))
+        }
+
+        fn post_visit_show_series_statement(self, n: &ShowSeriesStatement) -> VisitorResult<Self> {
+            Ok(self.push_post("show_series_statement", n))
+        }
+
         fn pre_visit_show_tag_keys_statement(
             self,
             n: &ShowTagKeysStatement,
@@ -1378,6 +1547,14 @@ mod test {
             Ok(self.push_post("select_field", n))
         }
 
+        fn pre_visit_select_into_clause(self, n: &IntoClause) -> VisitorResult<Recursion<Self>> {
+            Ok(Continue(self.push_pre("select_into_clause", n)))
+        }
+
+        fn post_visit_select_into_clause(self, n: &IntoClause) -> VisitorResult<Self> {
+            Ok(self.push_post("select_into_clause", n))
+        }
+
         fn pre_visit_select_from_clause(
             self,
             n: &FromMeasurementClause,
@@ -1567,6 +1744,12 @@ mod test {
         insta::assert_yaml_snapshot!(visit_statement!("DROP MEASUREMENT cpu"))
     }
 
+    #[test]
+    fn test_drop_series_statement() {
+        insta::assert_yaml_snapshot!(visit_statement!("DROP SERIES FROM cpu WHERE host = 'a'"));
+        insta::assert_yaml_snapshot!(visit_statement!("DROP SERIES WHERE host = 'a'"));
+    }
+
     #[test]
     fn test_explain_statement() {
         insta::assert_yaml_snapshot!(visit_statement!("EXPLAIN SELECT * FROM cpu"));
@@ -1621,6 +1804,14 @@ mod test {
         insta::assert_yaml_snapshot!(visit_statement!("SHOW RETENTION POLICIES ON telegraf"));
     }
 
+    #[test]
+    fn test_show_series_statement() {
+        insta::assert_yaml_snapshot!(visit_statement!("SHOW SERIES"));
+        insta::assert_yaml_snapshot!(visit_statement!(
+            "SHOW SERIES ON telegraf FROM cpu WHERE host = \"west\" LIMIT 5 OFFSET 10"
+        ));
+    }
+
     #[test]
     fn test_show_tag_keys_statement() {
         insta::assert_yaml_snapshot!(visit_statement!("SHOW TAG KEYS"));
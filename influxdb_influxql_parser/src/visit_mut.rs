@@ -0,0 +1,1415 @@
+//! The visit_mut module provides API for walking the AST and mutating it
+//! in place.
+//!
+//! # Example
+//!
+//! ```
+//! use influxdb_influxql_parser::visit_mut::{VisitableMut, VisitorMut, VisitorResult};
+//! use influxdb_influxql_parser::parse_statements;
+//! use influxdb_influxql_parser::common::WhereClause;
+//!
+//! struct MyVisitor;
+//!
+//! impl VisitorMut for MyVisitor {
+//!     fn post_visit_where_clause(self, n: &mut WhereClause) -> VisitorResult<Self> {
+//!         println!("{}", n);
+//!         Ok(self)
+//!     }
+//! }
+//!
+//! let mut statements = parse_statements("SELECT value FROM cpu WHERE host = 'west'").unwrap();
+//! let statement  = statements.first_mut().unwrap();
+//! let vis = MyVisitor;
+//! statement.accept_mut(vis);
+//! ```
+use crate::common::{
+    LimitClause, MeasurementName, OffsetClause, OrderByClause, QualifiedMeasurementName,
+    WhereClause,
+};
+use crate::delete::DeleteStatement;
+use crate::drop::{DropMeasurementStatement, DropSeriesStatement};
+use crate::explain::ExplainStatement;
+use crate::expression::arithmetic::Expr;
+use crate::expression::conditional::ConditionalExpression;
+use crate::select::{
+    Dimension, Field, FieldList, FillClause, FromMeasurementClause, GroupByClause, IntoClause,
+    MeasurementSelection, SLimitClause, SOffsetClause, SelectStatement, TimeZoneClause,
+};
+use crate::show::{OnClause, ShowDatabasesStatement};
+use crate::show_field_keys::ShowFieldKeysStatement;
+use crate::show_measurements::{
+    ExtendedOnClause, ShowMeasurementsStatement, WithMeasurementClause,
+};
+use crate::show_retention_policies::ShowRetentionPoliciesStatement;
+use crate::show_series::ShowSeriesStatement;
+use crate::show_tag_keys::ShowTagKeysStatement;
+use crate::show_tag_values::{ShowTagValuesStatement, WithKeyClause};
+use crate::simple_from_clause::{DeleteFromClause, ShowFromClause};
+use crate::statement::Statement;
+use crate::visit_mut::Recursion::*;
+
+/// The result type for a [`VisitorMut`].
+pub type VisitorResult<T, E = &'static str> = Result<T, E>;
+
+/// Controls how the visitor recursion should proceed.
+pub enum Recursion<V: VisitorMut> {
+    /// Attempt to visit all the children, recursively, of this expression.
+    Continue(V),
+    /// Do not visit the children of this expression, though the walk
+    /// of parents of this expression will not be affected
+    Stop(V),
+}
+
+/// Encode the depth-first traversal of an InfluxQL statement. When passed to
+/// any [`VisitableMut::accept_mut`], `pre_visit` functions are invoked repeatedly
+/// until a leaf node is reached or a `pre_visit` function returns [`Recursion::Stop`].
+pub trait VisitorMut: Sized {
+    /// Invoked before any children of the InfluxQL statement are visited.
+    fn pre_visit_statement(self, _n: &mut Statement) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the InfluxQL statement are visited.
+    fn post_visit_statement(self, _n: &mut Statement) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `DELETE` statement are visited.
+    fn pre_visit_delete_statement(
+        self,
+        _n: &mut DeleteStatement,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `DELETE` statement are visited.
+    fn post_visit_delete_statement(self, _n: &mut DeleteStatement) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `FROM` clause of a `DELETE` statement are visited.
+    fn pre_visit_delete_from_clause(
+        self,
+        _n: &mut DeleteFromClause,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `FROM` clause of a `DELETE` statement are visited.
+    fn post_visit_delete_from_clause(self, _n: &mut DeleteFromClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the measurement name are visited.
+    fn pre_visit_measurement_name(
+        self,
+        _n: &mut MeasurementName,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the measurement name are visited.
+    fn post_visit_measurement_name(self, _n: &mut MeasurementName) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `DROP MEASUREMENT` statement are visited.
+    fn pre_visit_drop_measurement_statement(
+        self,
+        _n: &mut DropMeasurementStatement,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `DROP MEASUREMENT` statement are visited.
+    fn post_visit_drop_measurement_statement(
+        self,
+        _n: &mut DropMeasurementStatement,
+    ) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `DROP SERIES` statement are visited.
+    fn pre_visit_drop_series_statement(
+        self,
+        _n: &mut DropSeriesStatement,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `DROP SERIES` statement are visited.
+    fn post_visit_drop_series_statement(self, _n: &mut DropSeriesStatement) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `EXPLAIN` statement are visited.
+    fn pre_visit_explain_statement(
+        self,
+        _n: &mut ExplainStatement,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `EXPLAIN` statement are visited.
+    fn post_visit_explain_statement(self, _n: &mut ExplainStatement) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `SELECT` statement are visited.
+    fn pre_visit_select_statement(
+        self,
+        _n: &mut SelectStatement,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `SELECT` statement are visited.
+    fn post_visit_select_statement(self, _n: &mut SelectStatement) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `SHOW DATABASES` statement are visited.
+    fn pre_visit_show_databases_statement(
+        self,
+        _n: &mut ShowDatabasesStatement,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `SHOW DATABASES` statement are visited.
+    fn post_visit_show_databases_statement(
+        self,
+        _n: &mut ShowDatabasesStatement,
+    ) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `SHOW MEASUREMENTS` statement are visited.
+    fn pre_visit_show_measurements_statement(
+        self,
+        _n: &mut ShowMeasurementsStatement,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `SHOW MEASUREMENTS` statement are visited.
+    fn post_visit_show_measurements_statement(
+        self,
+        _n: &mut ShowMeasurementsStatement,
+    ) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `SHOW RETENTION POLICIES` statement are visited.
+    fn pre_visit_show_retention_policies_statement(
+        self,
+        _n: &mut ShowRetentionPoliciesStatement,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `SHOW RETENTION POLICIES` statement are visited.
+    fn post_visit_show_retention_policies_statement(
+        self,
+        _n: &mut ShowRetentionPoliciesStatement,
+    ) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `SHOW SERIES` statement are visited.
+    fn pre_visit_show_series_statement(
+        self,
+        _n: &mut ShowSeriesStatement,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `SHOW SERIES` statement are visited.
+    fn post_visit_show_series_statement(self, _n: &mut ShowSeriesStatement) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `SHOW TAG KEYS` statement are visited.
+    fn pre_visit_show_tag_keys_statement(
+        self,
+        _n: &mut ShowTagKeysStatement,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `SHOW TAG KEYS` statement are visited.
+    fn post_visit_show_tag_keys_statement(
+        self,
+        _n: &mut ShowTagKeysStatement,
+    ) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `SHOW TAG VALUES` statement are visited.
+    fn pre_visit_show_tag_values_statement(
+        self,
+        _n: &mut ShowTagValuesStatement,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `SHOW TAG VALUES` statement are visited.
+    fn post_visit_show_tag_values_statement(
+        self,
+        _n: &mut ShowTagValuesStatement,
+    ) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `SHOW FIELD KEYS` statement are visited.
+    fn pre_visit_show_field_keys_statement(
+        self,
+        _n: &mut ShowFieldKeysStatement,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `SHOW FIELD KEYS` statement are visited.
+    fn post_visit_show_field_keys_statement(
+        self,
+        _n: &mut ShowFieldKeysStatement,
+    ) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the conditional expression are visited.
+    fn pre_visit_conditional_expression(
+        self,
+        _n: &mut ConditionalExpression,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the conditional expression are visited.
+    fn post_visit_conditional_expression(
+        self,
+        _n: &mut ConditionalExpression,
+    ) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the arithmetic expression are visited.
+    fn pre_visit_expr(self, _n: &mut Expr) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the arithmetic expression are visited.
+    fn post_visit_expr(self, _n: &mut Expr) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any fields of the `SELECT` projection are visited.
+    fn pre_visit_select_field_list(self, _n: &mut FieldList) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all fields of the `SELECT` projection are visited.
+    fn post_visit_select_field_list(self, _n: &mut FieldList) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the field of a `SELECT` statement are visited.
+    fn pre_visit_select_field(self, _n: &mut Field) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the field of a `SELECT` statement are visited.
+    fn post_visit_select_field(self, _n: &mut Field) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before the `INTO` clause of a `SELECT` statement is visited.
+    fn pre_visit_select_into_clause(self, _n: &mut IntoClause) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after the `INTO` clause of a `SELECT` statement is visited.
+    fn post_visit_select_into_clause(self, _n: &mut IntoClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `FROM` clause of a `SELECT` statement are visited.
+    fn pre_visit_select_from_clause(
+        self,
+        _n: &mut FromMeasurementClause,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `FROM` clause of a `SELECT` statement are visited.
+    fn post_visit_select_from_clause(self, _n: &mut FromMeasurementClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the measurement selection of a `FROM` clause for a `SELECT` statement are visited.
+    fn pre_visit_select_measurement_selection(
+        self,
+        _n: &mut MeasurementSelection,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the measurement selection of a `FROM` clause for a `SELECT` statement are visited.
+    fn post_visit_select_measurement_selection(
+        self,
+        _n: &mut MeasurementSelection,
+    ) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `GROUP BY` clause are visited.
+    fn pre_visit_group_by_clause(self, _n: &mut GroupByClause) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `GROUP BY` clause are visited.
+    fn post_visit_group_by_clause(self, _n: &mut GroupByClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `GROUP BY` dimension expression are visited.
+    fn pre_visit_select_dimension(self, _n: &mut Dimension) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `GROUP BY` dimension expression are visited.
+    fn post_visit_select_dimension(self, _n: &mut Dimension) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `WHERE` clause are visited.
+    fn pre_visit_where_clause(self, _n: &mut WhereClause) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `WHERE` clause are visited.
+    fn post_visit_where_clause(self, _n: &mut WhereClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `FROM` clause for any `SHOW` statement are visited.
+    fn pre_visit_show_from_clause(self, _n: &mut ShowFromClause) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `FROM` clause for any `SHOW` statement are visited.
+    fn post_visit_show_from_clause(self, _n: &mut ShowFromClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the qualified measurement name are visited.
+    fn pre_visit_qualified_measurement_name(
+        self,
+        _n: &mut QualifiedMeasurementName,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the qualified measurement name are visited.
+    fn post_visit_qualified_measurement_name(
+        self,
+        _n: &mut QualifiedMeasurementName,
+    ) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `FILL` clause are visited.
+    fn pre_visit_fill_clause(self, _n: &mut FillClause) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `FILL` clause are visited.
+    fn post_visit_fill_clause(self, _n: &mut FillClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `ORDER BY` clause are visited.
+    fn pre_visit_order_by_clause(self, _n: &mut OrderByClause) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `ORDER BY` clause are visited.
+    fn post_visit_order_by_clause(self, _n: &mut OrderByClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `LIMIT` clause are visited.
+    fn pre_visit_limit_clause(self, _n: &mut LimitClause) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `LIMIT` clause are visited.
+    fn post_visit_limit_clause(self, _n: &mut LimitClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `OFFSET` clause are visited.
+    fn pre_visit_offset_clause(self, _n: &mut OffsetClause) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `OFFSET` clause are visited.
+    fn post_visit_offset_clause(self, _n: &mut OffsetClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `SLIMIT` clause are visited.
+    fn pre_visit_slimit_clause(self, _n: &mut SLimitClause) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `SLIMIT` clause are visited.
+    fn post_visit_slimit_clause(self, _n: &mut SLimitClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of the `SOFFSET` clause are visited.
+    fn pre_visit_soffset_clause(self, _n: &mut SOffsetClause) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of the `SOFFSET` clause are visited.
+    fn post_visit_soffset_clause(self, _n: &mut SOffsetClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of a `TZ` clause are visited.
+    fn pre_visit_timezone_clause(self, _n: &mut TimeZoneClause) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of a `TZ` clause are visited.
+    fn post_visit_timezone_clause(self, _n: &mut TimeZoneClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of an extended `ON` clause are visited.
+    fn pre_visit_extended_on_clause(
+        self,
+        _n: &mut ExtendedOnClause,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of an extended `ON` clause are visited.
+    fn post_visit_extended_on_clause(self, _n: &mut ExtendedOnClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of an `ON` clause are visited.
+    fn pre_visit_on_clause(self, _n: &mut OnClause) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of an `ON` clause are visited.
+    fn post_visit_on_clause(self, _n: &mut OnClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of a `WITH MEASUREMENT` clause  are visited.
+    fn pre_visit_with_measurement_clause(
+        self,
+        _n: &mut WithMeasurementClause,
+    ) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of a `WITH MEASUREMENT` clause  are visited.
+    fn post_visit_with_measurement_clause(
+        self,
+        _n: &mut WithMeasurementClause,
+    ) -> VisitorResult<Self> {
+        Ok(self)
+    }
+
+    /// Invoked before any children of a `WITH KEY` clause are visited.
+    fn pre_visit_with_key_clause(self, _n: &mut WithKeyClause) -> VisitorResult<Recursion<Self>> {
+        Ok(Continue(self))
+    }
+
+    /// Invoked after all children of a `WITH KEY` clause  are visited.
+    fn post_visit_with_key_clause(self, _n: &mut WithKeyClause) -> VisitorResult<Self> {
+        Ok(self)
+    }
+}
+
+/// Trait for types that can be visited by [`VisitorMut`]
+pub trait VisitableMut: Sized {
+    /// accept a visitor, calling `visit` on all children of this, which may be mutated
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V>;
+}
+
+impl VisitableMut for Statement {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = match self {
+            Self::Delete(s) => s.accept_mut(visitor),
+            Self::DropMeasurement(s) => s.accept_mut(visitor),
+            Self::DropSeries(s) => s.accept_mut(visitor),
+            Self::Explain(s) => s.accept_mut(visitor),
+            Self::Select(s) => s.accept_mut(visitor),
+            Self::ShowDatabases(s) => s.accept_mut(visitor),
+            Self::ShowMeasurements(s) => s.accept_mut(visitor),
+            Self::ShowRetentionPolicies(s) => s.accept_mut(visitor),
+            Self::ShowSeries(s) => s.accept_mut(visitor),
+            Self::ShowTagKeys(s) => s.accept_mut(visitor),
+            Self::ShowTagValues(s) => s.accept_mut(visitor),
+            Self::ShowFieldKeys(s) => s.accept_mut(visitor),
+        }?;
+
+        visitor.post_visit_statement(self)
+    }
+}
+
+impl VisitableMut for DeleteStatement {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_delete_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = match self {
+            Self::FromWhere { from, condition } => {
+                let visitor = from.accept_mut(visitor)?;
+
+                if let Some(condition) = condition {
+                    condition.accept_mut(visitor)
+                } else {
+                    Ok(visitor)
+                }
+            }
+            Self::Where(condition) => condition.accept_mut(visitor),
+        }?;
+
+        visitor.post_visit_delete_statement(self)
+    }
+}
+
+impl VisitableMut for WhereClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_where_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = self.0.accept_mut(visitor)?;
+
+        visitor.post_visit_where_clause(self)
+    }
+}
+
+impl VisitableMut for DeleteFromClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_delete_from_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = self
+            .contents
+            .iter_mut()
+            .try_fold(visitor, |v, n| n.accept_mut(v))?;
+
+        visitor.post_visit_delete_from_clause(self)
+    }
+}
+
+impl VisitableMut for MeasurementName {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_measurement_name(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_measurement_name(self)
+    }
+}
+
+impl VisitableMut for DropMeasurementStatement {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_drop_measurement_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_drop_measurement_statement(self)
+    }
+}
+
+impl VisitableMut for DropSeriesStatement {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_drop_series_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = match self {
+            Self::FromWhere { from, condition } => {
+                let visitor = from.accept_mut(visitor)?;
+
+                if let Some(condition) = condition {
+                    condition.accept_mut(visitor)
+                } else {
+                    Ok(visitor)
+                }
+            }
+            Self::Where(condition) => condition.accept_mut(visitor),
+        }?;
+
+        visitor.post_visit_drop_series_statement(self)
+    }
+}
+
+impl VisitableMut for ExplainStatement {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_explain_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = self.select.accept_mut(visitor)?;
+
+        visitor.post_visit_explain_statement(self)
+    }
+}
+
+impl VisitableMut for SelectStatement {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_select_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = self.fields.accept_mut(visitor)?;
+
+        let visitor = if let Some(into) = &mut self.into {
+            into.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = self.from.accept_mut(visitor)?;
+
+        let visitor = if let Some(condition) = &mut self.condition {
+            condition.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(group_by) = &mut self.group_by {
+            group_by.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(fill_clause) = &mut self.fill {
+            fill_clause.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(order_by) = &mut self.order_by {
+            order_by.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(limit) = &mut self.limit {
+            limit.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(offset) = &mut self.offset {
+            offset.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(limit) = &mut self.series_limit {
+            limit.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(offset) = &mut self.series_offset {
+            offset.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(tz_clause) = &mut self.timezone {
+            tz_clause.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        visitor.post_visit_select_statement(self)
+    }
+}
+
+impl VisitableMut for IntoClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_select_into_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_select_into_clause(self)
+    }
+}
+
+impl VisitableMut for TimeZoneClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_timezone_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_timezone_clause(self)
+    }
+}
+
+impl VisitableMut for LimitClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_limit_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_limit_clause(self)
+    }
+}
+
+impl VisitableMut for OffsetClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_offset_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_offset_clause(self)
+    }
+}
+
+impl VisitableMut for SLimitClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_slimit_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_slimit_clause(self)
+    }
+}
+
+impl VisitableMut for SOffsetClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_soffset_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_soffset_clause(self)
+    }
+}
+
+impl VisitableMut for FillClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_fill_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_fill_clause(self)
+    }
+}
+
+impl VisitableMut for OrderByClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_order_by_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_order_by_clause(self)
+    }
+}
+
+impl VisitableMut for GroupByClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_group_by_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = self
+            .contents
+            .iter_mut()
+            .try_fold(visitor, |v, d| d.accept_mut(v))?;
+
+        visitor.post_visit_group_by_clause(self)
+    }
+}
+
+impl VisitableMut for ShowMeasurementsStatement {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_show_measurements_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = if let Some(on_clause) = &mut self.on {
+            on_clause.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(with_clause) = &mut self.with_measurement {
+            with_clause.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(condition) = &mut self.condition {
+            condition.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(order_by) = &mut self.order_by {
+            order_by.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(limit) = &mut self.limit {
+            limit.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(offset) = &mut self.offset {
+            offset.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        visitor.post_visit_show_measurements_statement(self)
+    }
+}
+
+impl VisitableMut for ExtendedOnClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_extended_on_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_extended_on_clause(self)
+    }
+}
+
+impl VisitableMut for WithMeasurementClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_with_measurement_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = match self {
+            Self::Equals(n) => n.accept_mut(visitor),
+            Self::Regex(n) => n.accept_mut(visitor),
+        }?;
+
+        visitor.post_visit_with_measurement_clause(self)
+    }
+}
+
+impl VisitableMut for ShowRetentionPoliciesStatement {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_show_retention_policies_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = if let Some(on_clause) = &mut self.database {
+            on_clause.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        visitor.post_visit_show_retention_policies_statement(self)
+    }
+}
+
+impl VisitableMut for ShowFromClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_show_from_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = self
+            .contents
+            .iter_mut()
+            .try_fold(visitor, |v, f| f.accept_mut(v))?;
+
+        visitor.post_visit_show_from_clause(self)
+    }
+}
+
+impl VisitableMut for QualifiedMeasurementName {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_qualified_measurement_name(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = self.name.accept_mut(visitor)?;
+
+        visitor.post_visit_qualified_measurement_name(self)
+    }
+}
+
+impl VisitableMut for ShowSeriesStatement {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_show_series_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = if let Some(on_clause) = &mut self.database {
+            on_clause.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(from) = &mut self.from {
+            from.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(condition) = &mut self.condition {
+            condition.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(limit) = &mut self.limit {
+            limit.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(offset) = &mut self.offset {
+            offset.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        visitor.post_visit_show_series_statement(self)
+    }
+}
+
+impl VisitableMut for ShowTagKeysStatement {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_show_tag_keys_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = if let Some(on_clause) = &mut self.database {
+            on_clause.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(from) = &mut self.from {
+            from.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(condition) = &mut self.condition {
+            condition.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(order_by) = &mut self.order_by {
+            order_by.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(limit) = &mut self.limit {
+            limit.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(offset) = &mut self.offset {
+            offset.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        visitor.post_visit_show_tag_keys_statement(self)
+    }
+}
+
+impl VisitableMut for ShowTagValuesStatement {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_show_tag_values_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = if let Some(on_clause) = &mut self.database {
+            on_clause.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(from) = &mut self.from {
+            from.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = self.with_key.accept_mut(visitor)?;
+
+        let visitor = if let Some(condition) = &mut self.condition {
+            condition.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(order_by) = &mut self.order_by {
+            order_by.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(limit) = &mut self.limit {
+            limit.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(offset) = &mut self.offset {
+            offset.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        visitor.post_visit_show_tag_values_statement(self)
+    }
+}
+
+impl VisitableMut for ShowFieldKeysStatement {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_show_field_keys_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = if let Some(on_clause) = &mut self.database {
+            on_clause.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(from) = &mut self.from {
+            from.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(order_by) = &mut self.order_by {
+            order_by.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(limit) = &mut self.limit {
+            limit.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        let visitor = if let Some(offset) = &mut self.offset {
+            offset.accept_mut(visitor)
+        } else {
+            Ok(visitor)
+        }?;
+
+        visitor.post_visit_show_field_keys_statement(self)
+    }
+}
+
+impl VisitableMut for FieldList {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_select_field_list(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = self
+            .contents
+            .iter_mut()
+            .try_fold(visitor, |v, f| f.accept_mut(v))?;
+
+        visitor.post_visit_select_field_list(self)
+    }
+}
+
+impl VisitableMut for Field {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_select_field(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = self.expr.accept_mut(visitor)?;
+
+        visitor.post_visit_select_field(self)
+    }
+}
+
+impl VisitableMut for FromMeasurementClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_select_from_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = self
+            .contents
+            .iter_mut()
+            .try_fold(visitor, |v, f| f.accept_mut(v))?;
+
+        visitor.post_visit_select_from_clause(self)
+    }
+}
+
+impl VisitableMut for MeasurementSelection {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_select_measurement_selection(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = match self {
+            Self::Name(name) => name.accept_mut(visitor),
+            Self::Subquery(select) => select.accept_mut(visitor),
+        }?;
+
+        visitor.post_visit_select_measurement_selection(self)
+    }
+}
+
+impl VisitableMut for Dimension {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_select_dimension(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = match self {
+            Self::Time { interval, offset } => {
+                let visitor = interval.accept_mut(visitor)?;
+                if let Some(offset) = offset {
+                    offset.accept_mut(visitor)
+                } else {
+                    Ok(visitor)
+                }
+            }
+            Self::Tag(_) | Self::Regex(_) | Self::Wildcard => Ok(visitor),
+        }?;
+
+        visitor.post_visit_select_dimension(self)
+    }
+}
+
+impl VisitableMut for WithKeyClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_with_key_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_with_key_clause(self)
+    }
+}
+
+impl VisitableMut for ShowDatabasesStatement {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_show_databases_statement(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+        visitor.post_visit_show_databases_statement(self)
+    }
+}
+
+impl VisitableMut for ConditionalExpression {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_conditional_expression(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = match self {
+            Self::Expr(expr) => expr.accept_mut(visitor),
+            Self::Binary { lhs, rhs, .. } => {
+                let visitor = lhs.accept_mut(visitor)?;
+                rhs.accept_mut(visitor)
+            }
+            Self::Grouped(expr) => expr.accept_mut(visitor),
+        }?;
+
+        visitor.post_visit_conditional_expression(self)
+    }
+}
+
+impl VisitableMut for Expr {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_expr(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        let visitor = match self {
+            Self::UnaryOp(_, expr) => expr.accept_mut(visitor),
+            Self::Call { args, .. } => args.iter_mut().try_fold(visitor, |v, e| e.accept_mut(v)),
+            Self::Binary { lhs, op: _, rhs } => {
+                let visitor = lhs.accept_mut(visitor)?;
+                rhs.accept_mut(visitor)
+            }
+            Self::Nested(expr) => expr.accept_mut(visitor),
+
+            // We explicitly list out each enumeration, to ensure
+            // we revisit if new items are added to the Expr enumeration.
+            Self::VarRef { .. }
+            | Self::BindParameter(_)
+            | Self::Literal(_)
+            | Self::Wildcard(_)
+            | Self::QualifiedWildcard { .. }
+            | Self::Distinct(_) => Ok(visitor),
+        }?;
+
+        visitor.post_visit_expr(self)
+    }
+}
+
+impl VisitableMut for OnClause {
+    fn accept_mut<V: VisitorMut>(&mut self, visitor: V) -> VisitorResult<V> {
+        let visitor = match visitor.pre_visit_on_clause(self)? {
+            Continue(visitor) => visitor,
+            Stop(visitor) => return Ok(visitor),
+        };
+
+        visitor.post_visit_on_clause(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::common::MeasurementName;
+    use crate::expression::arithmetic::Expr;
+    use crate::parse_statements;
+    use crate::visit_mut::Recursion::{Continue, Stop};
+    use crate::visit_mut::{Recursion, VisitableMut, VisitorMut, VisitorResult};
+
+    /// Counts the function calls in a statement, including those of subqueries.
+    struct CallCounter(usize);
+
+    impl VisitorMut for CallCounter {
+        fn pre_visit_expr(self, n: &mut Expr) -> VisitorResult<Recursion<Self>> {
+            Ok(Continue(match n {
+                Expr::Call { .. } => Self(self.0 + 1),
+                _ => self,
+            }))
+        }
+    }
+
+    /// Renames every measurement `from` to `to`.
+    struct RenameMeasurement {
+        from: &'static str,
+        to: &'static str,
+    }
+
+    impl VisitorMut for RenameMeasurement {
+        fn post_visit_measurement_name(self, n: &mut MeasurementName) -> VisitorResult<Self> {
+            if matches!(n, MeasurementName::Name(name) if name.as_str() == self.from) {
+                *n = MeasurementName::Name(self.to.into());
+            }
+            Ok(self)
+        }
+    }
+
+    /// Stops descending into function calls, uppercasing their names.
+    struct UppercaseOuterCalls;
+
+    impl VisitorMut for UppercaseOuterCalls {
+        fn pre_visit_expr(self, n: &mut Expr) -> VisitorResult<Recursion<Self>> {
+            match n {
+                Expr::Call { name, .. } => {
+                    *name = name.to_uppercase();
+                    Ok(Stop(self))
+                }
+                _ => Ok(Continue(self)),
+            }
+        }
+    }
+
+    fn parse(q: &str) -> crate::statement::Statement {
+        parse_statements(q).unwrap().pop().unwrap()
+    }
+
+    #[test]
+    fn test_call_counter() {
+        let mut stmt = parse("SELECT mean(a), max(b) FROM (SELECT sum(c) FROM m) WHERE d = 1");
+        assert_eq!(stmt.accept_mut(CallCounter(0)).unwrap().0, 3);
+
+        // Nested calls are counted
+        let mut stmt = parse("SELECT count(max(a)) FROM m");
+        assert_eq!(stmt.accept_mut(CallCounter(0)).unwrap().0, 2);
+
+        let mut stmt = parse("SHOW DATABASES");
+        assert_eq!(stmt.accept_mut(CallCounter(0)).unwrap().0, 0);
+    }
+
+    #[test]
+    fn test_rename_measurement() {
+        let mut stmt = parse("SELECT a FROM cpu, (SELECT b FROM cpu), mem");
+        stmt.accept_mut(RenameMeasurement {
+            from: "cpu",
+            to: "processor",
+        })
+        .unwrap();
+        assert_eq!(
+            stmt.to_string(),
+            "SELECT a FROM processor, (SELECT b FROM processor), mem"
+        );
+    }
+
+    #[test]
+    fn test_stop() {
+        let mut stmt = parse("SELECT sum(max(a)), b FROM cpu");
+        stmt.accept_mut(UppercaseOuterCalls).unwrap();
+        assert_eq!(stmt.to_string(), "SELECT SUM(max(a)), b FROM cpu");
+    }
+}
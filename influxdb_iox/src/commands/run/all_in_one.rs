@@ -21,7 +21,7 @@ use ioxd_common::{
 use ioxd_compactor::create_compactor_server_type;
 use ioxd_ingester::create_ingester_server_type;
 use ioxd_querier::{create_querier_server_type, QuerierServerTypeArgs};
-use ioxd_router::create_router_server_type;
+use ioxd_router::{create_router_server_type, SchemaLimits};
 use object_store::DynObjectStore;
 use observability_deps::tracing::*;
 use parquet_file::storage::{ParquetStorage, StorageId};
@@ -534,6 +534,8 @@ pub async fn command(config: Config) -> Result<()> {
         &write_buffer_config,
         QUERY_POOL_NAME,
         1_000, // max 1,000 concurrent HTTP requests
+        1_000, // max 1,000 concurrent DML requests
+        SchemaLimits::default(),
     )
     .await?;
 
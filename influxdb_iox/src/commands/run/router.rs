@@ -10,7 +10,7 @@ use ioxd_common::{
     server_type::{CommonServerState, CommonServerStateError},
     Service,
 };
-use ioxd_router::create_router_server_type;
+use ioxd_router::{create_router_server_type, SchemaLimits};
 use object_store::DynObjectStore;
 use object_store_metrics::ObjectStoreMetrics;
 use observability_deps::tracing::*;
@@ -87,6 +87,41 @@ pub struct Config {
         action
     )]
     pub(crate) http_request_limit: usize,
+
+    /// The maximum number of write and delete requests concurrently executing
+    /// in the DML handlers, once their body has been read.
+    ///
+    /// Requests received while this many are executing are rejected with a
+    /// 503, before their body is read. Requests received below the limit wait
+    /// for capacity if the limit is reached while their body is being read.
+    ///
+    /// If not set, this is the `--max-http-requests` limit, which is reached
+    /// first, so no requests are rejected by this limit.
+    #[clap(
+        long = "max-concurrent-dml-requests",
+        env = "INFLUXDB_IOX_MAX_CONCURRENT_DML_REQUESTS",
+        action
+    )]
+    pub(crate) dml_request_limit: Option<usize>,
+
+    /// The maximum number of columns, including tags and the timestamp, a
+    /// write may create in a table. Writes that would add columns beyond this
+    /// limit are rejected.
+    ///
+    /// If not set, only the column limit of the namespace applies.
+    #[clap(
+        long = "max-table-columns",
+        env = "INFLUXDB_IOX_MAX_TABLE_COLUMNS",
+        action
+    )]
+    pub(crate) max_table_columns: Option<usize>,
+
+    /// The maximum number of tag columns a write may create in a table. Writes
+    /// that would add tags beyond this limit are rejected.
+    ///
+    /// If not set, the number of tags is not limited.
+    #[clap(long = "max-table-tags", env = "INFLUXDB_IOX_MAX_TABLE_TAGS", action)]
+    pub(crate) max_table_tags: Option<usize>,
 }
 
 pub async fn command(config: Config) -> Result<()> {
@@ -116,6 +151,13 @@ pub async fn command(config: Config) -> Result<()> {
         &config.write_buffer_config,
         &config.query_pool_name,
         config.http_request_limit,
+        config
+            .dml_request_limit
+            .unwrap_or(config.http_request_limit),
+        SchemaLimits {
+            max_columns_per_table: config.max_table_columns,
+            max_tags_per_table: config.max_table_tags,
+        },
     )
     .await?;
 
@@ -1,18 +1,33 @@
-use std::sync::Arc;
+//! Tracking of the background jobs run by the ingester.
+
+use std::{fmt::Display, sync::Arc};
 
 use data_types::PartitionId;
 use iox_time::TimeProvider;
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use tracker::{
     AbstractTaskRegistry, TaskRegistration, TaskRegistry, TaskRegistryWithHistory,
-    TaskRegistryWithMetrics, TaskTracker,
+    TaskRegistryWithMetrics, TaskResult, TaskStatus, TaskTracker,
 };
 
 const JOB_HISTORY_SIZE: usize = 1000;
 
-#[derive(Debug)]
+/// The version of the serialized form of [`JobMetadata`].
+///
+/// It must be incremented whenever a change is made that older readers
+/// cannot deserialize.
+pub const JOB_METADATA_VERSION: u32 = 1;
+
+/// A background job run by the ingester.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum Job {
-    Persist { partition_id: PartitionId },
+    /// Persist the buffered data of a partition to object storage.
+    Persist {
+        /// The partition being persisted.
+        partition_id: PartitionId,
+    },
 }
 
 impl Job {
@@ -23,6 +38,138 @@ impl Job {
     }
 }
 
+impl Display for Job {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Persist { partition_id } => write!(f, "persist partition {}", partition_id),
+        }
+    }
+}
+
+/// The status of a [`Job`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// The job is being created.
+    Pending,
+    /// The job is running.
+    Running,
+    /// The job completed successfully.
+    Success,
+    /// The job was cancelled.
+    Cancelled,
+    /// The job was dropped before it completed.
+    Dropped,
+    /// The job failed.
+    Error,
+}
+
+impl JobStatus {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Success => "success",
+            Self::Cancelled => "cancelled",
+            Self::Dropped => "dropped",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl Display for JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The progress of a running or completed [`Job`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobProgress {
+    /// The number of tasks making up the job.
+    pub total_count: usize,
+    /// The number of tasks yet to complete.
+    pub pending_count: usize,
+    /// The CPU time spent executing the tasks, in nanoseconds.
+    pub cpu_nanos: usize,
+}
+
+/// A serializable description of a [`Job`] and its progress.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobMetadata {
+    /// The schema version, [`JOB_METADATA_VERSION`] when created.
+    pub version: u32,
+    /// The ID of the job in the [`JobRegistry`].
+    pub id: usize,
+    /// The job.
+    pub job: Job,
+    /// The status of the job.
+    pub status: JobStatus,
+    /// The progress of the job, absent while it is pending.
+    pub progress: Option<JobProgress>,
+}
+
+impl JobMetadata {
+    /// Describe the job tracked by `tracker`.
+    pub fn new(tracker: &TaskTracker<Job>) -> Self {
+        let (status, progress) = match tracker.get_status() {
+            TaskStatus::Creating => (JobStatus::Pending, None),
+            TaskStatus::Running {
+                total_count,
+                pending_count,
+                cpu_nanos,
+            } => (
+                JobStatus::Running,
+                Some(JobProgress {
+                    total_count,
+                    pending_count,
+                    cpu_nanos,
+                }),
+            ),
+            status @ TaskStatus::Complete {
+                total_count,
+                cpu_nanos,
+                ..
+            } => (
+                match status.result() {
+                    Some(TaskResult::Success) | None => JobStatus::Success,
+                    Some(TaskResult::Cancelled) => JobStatus::Cancelled,
+                    Some(TaskResult::Dropped) => JobStatus::Dropped,
+                    Some(TaskResult::Error) => JobStatus::Error,
+                },
+                Some(JobProgress {
+                    total_count,
+                    pending_count: 0,
+                    cpu_nanos,
+                }),
+            ),
+        };
+
+        Self {
+            version: JOB_METADATA_VERSION,
+            id: tracker.id().0,
+            job: tracker.metadata().clone(),
+            status,
+            progress,
+        }
+    }
+}
+
+impl Display for JobMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "job {} ({}): {}", self.id, self.job, self.status)?;
+        if let Some(progress) = &self.progress {
+            write!(
+                f,
+                ", {}/{} tasks complete",
+                progress.total_count - progress.pending_count,
+                progress.total_count
+            )?;
+        }
+        Ok(())
+    }
+}
+
 /// The global job registry
 #[derive(Debug)]
 pub struct JobRegistry {
@@ -30,6 +177,8 @@ pub struct JobRegistry {
 }
 
 impl JobRegistry {
+    /// Create a registry recording the metrics of its jobs in
+    /// `metric_registry`.
     pub fn new(
         metric_registry: Arc<metric::Registry>,
         time_provider: Arc<dyn TimeProvider>,
@@ -43,6 +192,8 @@ impl JobRegistry {
         }
     }
 
+    /// Register `job`, returning its tracker and the registration with
+    /// which to track its futures.
     pub fn register(&self, job: Job) -> (TaskTracker<Job>, TaskRegistration) {
         self.inner.lock().register(job)
     }
@@ -62,3 +213,144 @@ impl JobRegistry {
 fn f_attributes(job: &Job) -> metric::Attributes {
     metric::Attributes::from(&[("name", job.name())])
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use futures::future;
+    use iox_time::SystemProvider;
+    use tokio::sync::oneshot;
+    use tracker::TrackedFutureExt;
+
+    use super::*;
+
+    fn new_registry() -> JobRegistry {
+        JobRegistry::new(
+            Arc::new(metric::Registry::new()),
+            Arc::new(SystemProvider::new()),
+        )
+    }
+
+    fn persist(partition_id: i64) -> Job {
+        Job::Persist {
+            partition_id: PartitionId::new(partition_id),
+        }
+    }
+
+    fn running_persist() -> JobMetadata {
+        JobMetadata {
+            version: JOB_METADATA_VERSION,
+            id: 3,
+            job: Job::Persist {
+                partition_id: PartitionId::new(42),
+            },
+            status: JobStatus::Running,
+            progress: Some(JobProgress {
+                total_count: 4,
+                pending_count: 1,
+                cpu_nanos: 1_000,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let metadata = running_persist();
+        let json = serde_json::to_string(&metadata).unwrap();
+        assert_eq!(
+            json,
+            r#"{"version":1,"id":3,"job":{"type":"persist","partition_id":42},"status":"running","progress":{"total_count":4,"pending_count":1,"cpu_nanos":1000}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<JobMetadata>(&json).unwrap(),
+            metadata
+        );
+
+        let pending = JobMetadata {
+            status: JobStatus::Pending,
+            progress: None,
+            ..running_persist()
+        };
+        let json = serde_json::to_string(&pending).unwrap();
+        assert_eq!(serde_json::from_str::<JobMetadata>(&json).unwrap(), pending);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            running_persist().to_string(),
+            "job 3 (persist partition 42): running, 3/4 tasks complete"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_registered_job_metadata() {
+        let registry = new_registry();
+        let (tracker, registration) = registry.register(persist(42));
+
+        // Futures can still be registered
+        let metadata = JobMetadata::new(&tracker);
+        assert_eq!(metadata.id, tracker.id().0);
+        assert_eq!(metadata.job, persist(42));
+        assert_eq!(metadata.status, JobStatus::Pending);
+        assert_eq!(metadata.progress, None);
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let blocked = rx.track(registration.clone());
+        let done = future::ready(Ok::<_, Infallible>(())).track(registration);
+        done.await.unwrap().unwrap();
+
+        let metadata = JobMetadata::new(&tracker);
+        assert_eq!(metadata.status, JobStatus::Running);
+        let progress = metadata.progress.unwrap();
+        assert_eq!(progress.total_count, 2);
+        assert_eq!(progress.pending_count, 1);
+
+        tx.send(()).unwrap();
+        blocked.await.unwrap().unwrap();
+
+        let metadata = JobMetadata::new(&tracker);
+        assert_eq!(metadata.status, JobStatus::Success);
+        let progress = metadata.progress.unwrap();
+        assert_eq!(progress.total_count, 2);
+        assert_eq!(progress.pending_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_job_metadata() {
+        let registry = new_registry();
+        let (tracker, registration) = registry.register(persist(42));
+
+        let blocked = future::pending::<Result<(), Infallible>>().track(registration);
+        tracker.cancel();
+        blocked.await.expect_err("job should be aborted");
+
+        let metadata = JobMetadata::new(&tracker);
+        assert_eq!(metadata.status, JobStatus::Cancelled);
+        let progress = metadata.progress.unwrap();
+        assert_eq!(progress.total_count, 1);
+        assert_eq!(progress.pending_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_empty_job_metadata() {
+        let registry = new_registry();
+        let (tracker, registration) = registry.register(persist(42));
+
+        // A job whose registration is dropped without tracking any futures
+        // completes successfully
+        drop(registration);
+
+        let metadata = JobMetadata::new(&tracker);
+        assert_eq!(metadata.status, JobStatus::Success);
+        assert_eq!(
+            metadata.progress,
+            Some(JobProgress {
+                total_count: 0,
+                pending_count: 0,
+                cpu_nanos: 0,
+            })
+        );
+    }
+}
@@ -20,7 +20,7 @@
 pub(crate) mod compact;
 pub mod data;
 pub mod handler;
-mod job;
+pub mod job;
 pub mod lifecycle;
 mod poison;
 pub mod querier_handler;
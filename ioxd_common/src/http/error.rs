@@ -20,6 +20,7 @@ pub enum HttpApiErrorCode {
     MethodNotAllowed,
     RequestTooLarge,
     UnsupportedMediaType,
+    NotImplemented,
 }
 
 impl HttpApiErrorCode {
@@ -39,6 +40,7 @@ impl HttpApiErrorCode {
             Self::MethodNotAllowed => "method not allowed",
             Self::RequestTooLarge => "request too large",
             Self::UnsupportedMediaType => "unsupported media type",
+            Self::NotImplemented => "not implemented",
         }
     }
 
@@ -58,6 +60,7 @@ impl HttpApiErrorCode {
             Self::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
             Self::RequestTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
             Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::NotImplemented => StatusCode::NOT_IMPLEMENTED,
         }
     }
 
@@ -83,6 +86,7 @@ impl From<StatusCode> for HttpApiErrorCode {
             StatusCode::METHOD_NOT_ALLOWED => Self::MethodNotAllowed,
             StatusCode::PAYLOAD_TOO_LARGE => Self::RequestTooLarge,
             StatusCode::UNSUPPORTED_MEDIA_TYPE => Self::UnsupportedMediaType,
+            StatusCode::NOT_IMPLEMENTED => Self::NotImplemented,
             v => {
                 warn!(code=%v, "returning unexpected status code as internal error");
                 Self::InternalError
@@ -101,6 +105,9 @@ pub struct HttpApiError {
 
     /// Human-readable message.
     msg: String,
+
+    /// Optional stable, machine-readable identifier of the specific error.
+    error_code: Option<&'static str>,
 }
 
 impl HttpApiError {
@@ -109,16 +116,29 @@ impl HttpApiError {
         Self {
             code: code.into(),
             msg: msg.into(),
+            error_code: None,
+        }
+    }
+
+    /// Attach a stable identifier of the specific error, exposed as
+    /// `error_code` in the response body.
+    pub fn with_error_code(self, error_code: &'static str) -> Self {
+        Self {
+            error_code: Some(error_code),
+            ..self
         }
     }
 
     /// Generate response body for this error.
     fn body(&self) -> Body {
-        let json = serde_json::json!({
+        let mut json = serde_json::json!({
             "code": self.code.as_text().to_string(),
             "message": self.msg.clone(),
-        })
-        .to_string();
+        });
+        if let Some(error_code) = self.error_code {
+            json["error_code"] = error_code.into();
+        }
+        let json = json.to_string();
 
         Body::from(json)
     }
@@ -7,6 +7,8 @@ use observability_deps::tracing::info;
 use snafu::{ResultExt, Snafu};
 use std::{thread, time};
 
+use super::profile_sessions::{self, ProfileKind};
+
 #[derive(Debug, Snafu)]
 pub enum Error {
     #[snafu(display("{}", source))]
@@ -17,6 +19,8 @@ pub enum Error {
 }
 
 pub(crate) async fn dump_heappy_rsprof(seconds: u64, interval: i32) -> Result<HeapReport, Error> {
+    let _session = profile_sessions::start(ProfileKind::Alloc, seconds);
+
     // heap profiler guard is not Send so it can't be used in async code.
     let report = tokio::task::spawn_blocking(move || {
         let guard = heappy::HeapProfilerGuard::new(interval as usize)?;
@@ -0,0 +1,550 @@
+//! Replays the response to a request carrying an `Idempotency-Key` header when
+//! the request is retried, so that retried writes are not processed twice.
+
+use std::{
+    collections::{hash_map::RandomState, HashMap, VecDeque},
+    convert::Infallible,
+    hash::{BuildHasher, Hash, Hasher},
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use hyper::{
+    header::{AUTHORIZATION, CONTENT_ENCODING, CONTENT_LENGTH},
+    http::HeaderMap,
+    Body, Method, Request, Response, StatusCode,
+};
+use observability_deps::tracing::{debug, warn};
+use parking_lot::Mutex;
+use tower::{Layer, Service};
+
+use super::{
+    check_content_length,
+    error::{HttpApiError, HttpApiErrorCode, HttpApiErrorSource},
+    utils::parse_body,
+};
+
+/// The header carrying the client-chosen key identifying a request across
+/// retries.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Query parameters that select the namespace of a request.
+const NAMESPACE_PARAMS: &[&str] = &["db", "namespace", "org", "bucket"];
+
+/// Query parameters that identify the caller of the InfluxDB 1.x API, in
+/// addition to the `Authorization` header.
+const IDENTITY_PARAMS: &[&str] = &["u", "p", "password", "token"];
+
+/// Configuration of the replay of responses to retried requests.
+#[derive(Debug, Clone)]
+pub struct IdempotencyConfig {
+    /// How long a response is replayed for after it was first returned.
+    pub ttl: Duration,
+
+    /// The maximum number of responses retained, discarding the oldest first.
+    pub max_entries: usize,
+
+    /// The maximum size of a request body, in bytes. The body of a request
+    /// carrying an `Idempotency-Key` header is buffered, and decoded, to
+    /// compare it to that of the original request.
+    pub max_body_size: usize,
+
+    /// The paths of the `POST` routes whose responses are replayed.
+    pub routes: Vec<String>,
+}
+
+/// Identifies a request by its path, namespace, the hash of the identity of
+/// the caller, and idempotency key.
+type CacheKey = (String, String, u64, String);
+
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    /// The hash of the body of the request that the response was returned to.
+    body_hash: u64,
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl CachedResponse {
+    fn to_response(&self) -> Response<Body> {
+        let mut response = Response::new(Body::from(self.body.clone()));
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers.clone();
+        response
+    }
+}
+
+/// A bounded cache of responses, evicted in insertion order.
+///
+/// As every entry has the same TTL, insertion order is also expiry order.
+#[derive(Debug)]
+struct ResponseCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: HashMap<CacheKey, (Instant, CachedResponse)>,
+    order: VecDeque<(Instant, CacheKey)>,
+}
+
+impl ResponseCache {
+    fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            entries: Default::default(),
+            order: Default::default(),
+        }
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<&CachedResponse> {
+        self.entries
+            .get(key)
+            .filter(|(inserted, _)| inserted.elapsed() < self.ttl)
+            .map(|(_, response)| response)
+    }
+
+    fn insert(&mut self, key: CacheKey, response: CachedResponse) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let now = Instant::now();
+        while let Some((inserted, _)) = self.order.front() {
+            if self.order.len() < self.max_entries && inserted.elapsed() < self.ttl {
+                break;
+            }
+            let (inserted, key) = self.order.pop_front().expect("front exists");
+            // The key may have since been reinserted, superseding this entry
+            if matches!(self.entries.get(&key), Some((i, _)) if *i == inserted) {
+                self.entries.remove(&key);
+            }
+        }
+
+        self.order.push_back((now, key.clone()));
+        self.entries.insert(key, (now, response));
+    }
+}
+
+/// A [`Layer`] that replays the response to `POST` requests to the configured
+/// routes when they are retried with the same `Idempotency-Key` header, by the
+/// same caller, for the same namespace, within the configured TTL.
+///
+/// The caller is identified by the `Authorization` header and the credential
+/// query parameters of the request. A retry whose body differs from that of
+/// the original request is rejected with `422 Unprocessable Entity`, rather
+/// than being given the response to a different request.
+///
+/// Only responses that are not server errors are replayed, so that a request
+/// that failed transiently is processed again when retried. Concurrent
+/// requests with the same key are all processed.
+#[derive(Debug, Clone)]
+pub struct IdempotencyLayer {
+    state: Option<Arc<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    routes: Vec<String>,
+    max_body_size: usize,
+    /// Hashes request bodies and identities with keys chosen at startup, so
+    /// that collisions cannot be constructed by a client.
+    hasher: RandomState,
+    cache: Mutex<ResponseCache>,
+}
+
+impl IdempotencyLayer {
+    /// Replay responses as described by `config`, or never if it is `None`.
+    pub fn new(config: Option<IdempotencyConfig>) -> Self {
+        let state = config.map(|config| {
+            Arc::new(State {
+                routes: config.routes,
+                max_body_size: config.max_body_size,
+                hasher: RandomState::new(),
+                cache: Mutex::new(ResponseCache::new(config.ttl, config.max_entries)),
+            })
+        });
+        Self { state }
+    }
+}
+
+impl<S> Layer<S> for IdempotencyLayer {
+    type Service = IdempotencyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IdempotencyService {
+            inner,
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// The [`Service`] produced by an [`IdempotencyLayer`].
+#[derive(Debug, Clone)]
+pub struct IdempotencyService<S> {
+    inner: S,
+    state: Option<Arc<State>>,
+}
+
+impl State {
+    /// Returns the key identifying `req` if its response may be replayed.
+    fn cache_key(&self, req: &Request<Body>) -> Option<CacheKey> {
+        if req.method() != Method::POST || !self.routes.iter().any(|r| r == req.uri().path()) {
+            return None;
+        }
+
+        let key = req.headers().get(IDEMPOTENCY_KEY_HEADER)?.to_str().ok()?;
+        let params = serde_urlencoded::from_str::<Vec<(String, String)>>(
+            req.uri().query().unwrap_or_default(),
+        )
+        .ok()?;
+        let namespace = params
+            .iter()
+            .filter(|(k, _)| NAMESPACE_PARAMS.contains(&k.as_str()))
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let mut identity = self.hasher.build_hasher();
+        req.headers()
+            .get(AUTHORIZATION)
+            .map(|v| v.as_bytes())
+            .hash(&mut identity);
+        params
+            .iter()
+            .filter(|(k, _)| IDENTITY_PARAMS.contains(&k.as_str()))
+            .for_each(|param| param.hash(&mut identity));
+
+        Some((
+            req.uri().path().to_owned(),
+            namespace,
+            identity.finish(),
+            key.to_owned(),
+        ))
+    }
+
+    fn body_hash(&self, body: &[u8]) -> u64 {
+        let mut hasher = self.hasher.build_hasher();
+        body.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl<S> Service<Request<Body>> for IdempotencyService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = Infallible>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let (state, key) = match &self.state {
+            Some(state) => match state.cache_key(&req) {
+                Some(key) => (Arc::clone(state), key),
+                None => return Box::pin(self.inner.call(req)),
+            },
+            None => return Box::pin(self.inner.call(req)),
+        };
+
+        // The service that was driven to readiness handles this request, as
+        // required by `Service::call`, leaving the clone for the next
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            // Reject a declared length over the limit before buffering the body
+            if let Err(e) = check_content_length(&req, state.max_body_size) {
+                return Ok(e.to_http_api_error().response());
+            }
+
+            let (mut parts, body) = req.into_parts();
+            let mut body_req = Request::new(body);
+            *body_req.headers_mut() = parts.headers.clone();
+            let body = match parse_body(body_req, state.max_body_size).await {
+                Ok(body) => body,
+                Err(e) => return Ok(e.to_http_api_error().response()),
+            };
+
+            // The body is passed on decoded
+            parts.headers.remove(CONTENT_ENCODING);
+            parts.headers.insert(CONTENT_LENGTH, body.len().into());
+
+            let body_hash = state.body_hash(&body);
+
+            let cached = state
+                .cache
+                .lock()
+                .get(&key)
+                .map(|cached| (cached.body_hash, cached.to_response()));
+            if let Some((cached_body_hash, response)) = cached {
+                if cached_body_hash != body_hash {
+                    debug!(path=%key.0, namespace=%key.1, "idempotency key reused with a different body");
+                    return Ok(HttpApiError::new(
+                        HttpApiErrorCode::UnprocessableEntity,
+                        "Idempotency-Key was used for a request with a different body",
+                    )
+                    .response());
+                }
+                debug!(path=%key.0, namespace=%key.1, "replaying response to idempotent request");
+                return Ok(response);
+            }
+
+            let response = inner
+                .call(Request::from_parts(parts, Body::from(body)))
+                .await?;
+            if response.status().is_server_error() {
+                return Ok(response);
+            }
+
+            let (parts, body) = response.into_parts();
+            let body = match hyper::body::to_bytes(body).await {
+                Ok(body) => body,
+                Err(e) => {
+                    warn!(error=%e, "failed to buffer response to idempotent request");
+                    let mut response = Response::new(Body::empty());
+                    *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                    return Ok(response);
+                }
+            };
+
+            state.cache.lock().insert(
+                key,
+                CachedResponse {
+                    body_hash,
+                    status: parts.status,
+                    headers: parts.headers.clone(),
+                    body: body.clone(),
+                },
+            );
+
+            Ok(Response::from_parts(parts, Body::from(body)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tower::ServiceExt;
+
+    use super::*;
+
+    /// Returns a layered service that responds with the number of requests it
+    /// has processed, and `status`.
+    fn layered_service(
+        config: IdempotencyConfig,
+        status: StatusCode,
+    ) -> impl Service<Request<Body>, Response = Response<Body>, Error = Infallible> + Clone {
+        let processed = Arc::new(AtomicUsize::new(0));
+        let inner = tower::service_fn(move |_req: Request<Body>| {
+            let n = processed.fetch_add(1, Ordering::SeqCst) + 1;
+            let mut response = Response::new(Body::from(n.to_string()));
+            *response.status_mut() = status;
+            futures::future::ready(Ok::<_, Infallible>(response))
+        });
+        IdempotencyLayer::new(Some(config)).layer(inner)
+    }
+
+    fn config() -> IdempotencyConfig {
+        IdempotencyConfig {
+            ttl: Duration::from_secs(60),
+            max_entries: 10,
+            max_body_size: 1024,
+            routes: vec!["/api/v2/write".to_owned()],
+        }
+    }
+
+    fn write(key: Option<&str>, bucket: &str) -> Request<Body> {
+        let mut req = Request::post(format!("/api/v2/write?org=org&bucket={}", bucket));
+        if let Some(key) = key {
+            req = req.header(IDEMPOTENCY_KEY_HEADER, key);
+        }
+        req.body(Body::from("cpu usage=1 1")).unwrap()
+    }
+
+    async fn send<S>(service: &S, req: Request<Body>) -> String
+    where
+        S: Service<Request<Body>, Response = Response<Body>, Error = Infallible> + Clone,
+    {
+        send_with_status(service, req).await.1
+    }
+
+    async fn send_with_status<S>(service: &S, req: Request<Body>) -> (StatusCode, String)
+    where
+        S: Service<Request<Body>, Response = Response<Body>, Error = Infallible> + Clone,
+    {
+        let response = service.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_replay() {
+        let service = layered_service(config(), StatusCode::NO_CONTENT);
+
+        // A repeated request with the same key returns the original response
+        assert_eq!(send(&service, write(Some("a"), "b1")).await, "1");
+        assert_eq!(send(&service, write(Some("a"), "b1")).await, "1");
+
+        // A different key, or namespace, is processed
+        assert_eq!(send(&service, write(Some("b"), "b1")).await, "2");
+        assert_eq!(send(&service, write(Some("a"), "b2")).await, "3");
+        assert_eq!(send(&service, write(Some("b"), "b1")).await, "2");
+
+        // as are requests without a key
+        assert_eq!(send(&service, write(None, "b1")).await, "4");
+        assert_eq!(send(&service, write(None, "b1")).await, "5");
+
+        // and requests to other routes
+        let query = || {
+            Request::post("/api/v2/query?org=org&bucket=b1")
+                .header(IDEMPOTENCY_KEY_HEADER, "a")
+                .body(Body::empty())
+                .unwrap()
+        };
+        assert_eq!(send(&service, query()).await, "6");
+        assert_eq!(send(&service, query()).await, "7");
+    }
+
+    #[tokio::test]
+    async fn test_replay_expiry_and_eviction() {
+        let service = layered_service(
+            IdempotencyConfig {
+                ttl: Duration::ZERO,
+                ..config()
+            },
+            StatusCode::NO_CONTENT,
+        );
+        assert_eq!(send(&service, write(Some("a"), "b1")).await, "1");
+        assert_eq!(send(&service, write(Some("a"), "b1")).await, "2");
+
+        let service = layered_service(
+            IdempotencyConfig {
+                max_entries: 1,
+                ..config()
+            },
+            StatusCode::NO_CONTENT,
+        );
+        assert_eq!(send(&service, write(Some("a"), "b1")).await, "1");
+        assert_eq!(send(&service, write(Some("b"), "b1")).await, "2");
+        assert_eq!(send(&service, write(Some("b"), "b1")).await, "2");
+        assert_eq!(send(&service, write(Some("a"), "b1")).await, "3");
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_not_replayed() {
+        let service = layered_service(config(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(send(&service, write(Some("a"), "b1")).await, "1");
+        assert_eq!(send(&service, write(Some("a"), "b1")).await, "2");
+    }
+
+    #[tokio::test]
+    async fn test_replay_different_body() {
+        let service = layered_service(config(), StatusCode::NO_CONTENT);
+        assert_eq!(send(&service, write(Some("a"), "b1")).await, "1");
+
+        // A retry with the same key and a different body is rejected, rather
+        // than given the response to the original request
+        let (status, _) = send_with_status(
+            &service,
+            Request::post("/api/v2/write?org=org&bucket=b1")
+                .header(IDEMPOTENCY_KEY_HEADER, "a")
+                .body(Body::from("cpu usage=2 1"))
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        // and the original response is still replayed to the original request
+        assert_eq!(send(&service, write(Some("a"), "b1")).await, "1");
+    }
+
+    #[tokio::test]
+    async fn test_replay_per_caller() {
+        let service = layered_service(config(), StatusCode::NO_CONTENT);
+        let write_as = |token: &str| {
+            Request::post("/api/v2/write?org=org&bucket=b1")
+                .header(IDEMPOTENCY_KEY_HEADER, "a")
+                .header(AUTHORIZATION, format!("Token {}", token))
+                .body(Body::from("cpu usage=1 1"))
+                .unwrap()
+        };
+
+        // The same key used by a different caller is processed
+        assert_eq!(send(&service, write_as("alice")).await, "1");
+        assert_eq!(send(&service, write_as("alice")).await, "1");
+        assert_eq!(send(&service, write_as("bob")).await, "2");
+        assert_eq!(send(&service, write_as("bob")).await, "2");
+        assert_eq!(send(&service, write(Some("a"), "b1")).await, "3");
+
+        // as is the InfluxDB 1.x API with different credential parameters
+        let write_v1 = |user: &str| {
+            Request::post(format!("/write?db=db&u={}&p=secret", user))
+                .header(IDEMPOTENCY_KEY_HEADER, "a")
+                .body(Body::from("cpu usage=1 1"))
+                .unwrap()
+        };
+        let service = layered_service(
+            IdempotencyConfig {
+                routes: vec!["/write".to_owned()],
+                ..config()
+            },
+            StatusCode::NO_CONTENT,
+        );
+        assert_eq!(send(&service, write_v1("alice")).await, "1");
+        assert_eq!(send(&service, write_v1("alice")).await, "1");
+        assert_eq!(send(&service, write_v1("bob")).await, "2");
+    }
+
+    #[tokio::test]
+    async fn test_replay_body_too_large() {
+        let service = layered_service(
+            IdempotencyConfig {
+                max_body_size: 4,
+                ..config()
+            },
+            StatusCode::NO_CONTENT,
+        );
+        let (status, _) = send_with_status(&service, write(Some("a"), "b1")).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+        // A declared length over the limit is rejected without reading the body
+        let req = Request::post("/api/v2/write?org=org&bucket=b1")
+            .header(IDEMPOTENCY_KEY_HEADER, "a")
+            .header(CONTENT_LENGTH, "1024")
+            .body(Body::wrap_stream(futures::stream::pending::<
+                Result<Bytes, std::io::Error>,
+            >()))
+            .unwrap();
+        let (status, _) =
+            tokio::time::timeout(Duration::from_secs(1), send_with_status(&service, req))
+                .await
+                .expect("body should not be read");
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_replay_content_length_mismatch() {
+        let service = layered_service(config(), StatusCode::NO_CONTENT);
+        let req = Request::post("/api/v2/write?org=org&bucket=b1")
+            .header(IDEMPOTENCY_KEY_HEADER, "a")
+            .header(CONTENT_LENGTH, "100")
+            .body(Body::from("cpu usage=1 1"))
+            .unwrap();
+        let (status, _) = send_with_status(&service, req).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -1,20 +1,31 @@
-use std::{convert::Infallible, num::NonZeroI32, sync::Arc};
+use std::{
+    convert::Infallible,
+    num::NonZeroI32,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use hyper::{
+    header::{CONTENT_LENGTH, CONTENT_TYPE},
     http::HeaderValue,
     server::conn::{AddrIncoming, AddrStream},
     Body, Method, Request, Response,
 };
-use observability_deps::tracing::{debug, error};
+use metric::U64Gauge;
+use observability_deps::tracing::{debug, error, warn};
 use serde::Deserialize;
-use snafu::Snafu;
+use snafu::{ResultExt, Snafu};
 use tokio_util::sync::CancellationToken;
 use tower::Layer;
-use trace_http::{ctx::TraceHeaderParser, tower::TraceLayer};
+use trace_http::{ctx::TraceHeaderParser, metrics::PathTemplates, tower::TraceLayer};
 
 use crate::{
-    http::error::{HttpApiError, HttpApiErrorExt, HttpApiErrorSource},
-    server_type::ServerType,
+    http::{
+        error::{HttpApiError, HttpApiErrorCode, HttpApiErrorExt, HttpApiErrorSource},
+        idempotency::{IdempotencyConfig, IdempotencyLayer},
+        utils::{BodyExcerpt, ParseBodyError, RedactedQuery},
+    },
+    server_type::{LogFilterError, ServerType},
 };
 
 #[cfg(feature = "heappy")]
@@ -23,7 +34,11 @@ mod heappy;
 #[cfg(feature = "pprof")]
 mod pprof;
 
+#[cfg(any(feature = "pprof", feature = "heappy"))]
+mod profile_sessions;
+
 pub mod error;
+pub mod idempotency;
 pub mod metrics;
 pub mod utils;
 
@@ -40,6 +55,19 @@ pub enum ApplicationError {
         source: serde_urlencoded::de::Error,
     },
 
+    #[snafu(display("Invalid content-length header: {:?}", value))]
+    InvalidContentLength { value: HeaderValue },
+
+    #[snafu(display(
+        "Declared content-length of {} bytes exceeds limit of {} bytes",
+        content_length,
+        max_request_size
+    ))]
+    RequestSizeExceeded {
+        content_length: u64,
+        max_request_size: usize,
+    },
+
     #[snafu(display("PProf error: {}", source))]
     PProf {
         source: Box<dyn std::error::Error + Send + Sync>,
@@ -66,45 +94,165 @@ pub enum ApplicationError {
     #[snafu(display("pprof support is not compiled"))]
     PProfIsNotCompiled,
 
+    #[snafu(display("Resetting metrics is not enabled"))]
+    MetricsResetDisabled,
+
+    #[snafu(display("Invalid global metric label: {}", source))]
+    InvalidMetricLabel {
+        source: metric_exporters::InvalidLabelError,
+    },
+
+    #[snafu(display("{}", source))]
+    LogFilter { source: LogFilterError },
+
+    #[snafu(display("Invalid log level request: {}", source))]
+    InvalidLogLevelRequest { source: serde_json::Error },
+
+    #[snafu(display("Error reading request body: {}", source))]
+    ReadingBody { source: ParseBodyError },
+
     #[snafu(display("Route error from run mode: {}", e))]
     RunModeRouteError { e: Box<dyn HttpApiErrorSource> },
 }
 
+impl ApplicationError {
+    /// Stable, machine-readable code identifying this error, for clients to
+    /// branch on instead of the message.
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            Self::InvalidQueryString { .. } => "invalid_query_string",
+            Self::InvalidContentLength { .. } => "invalid_content_length",
+            Self::RequestSizeExceeded { .. } => "request_size_exceeded",
+            Self::PProf { .. } => "pprof",
+            #[cfg(feature = "heappy")]
+            Self::HeappyError { .. } => "heappy",
+            Self::Prost { .. } => "prost",
+            Self::ProstIO { .. } => "prost_io",
+            Self::EmptyFlamegraph => "empty_flamegraph",
+            Self::HeappyIsNotCompiled => "heappy_not_compiled",
+            Self::PProfIsNotCompiled => "pprof_not_compiled",
+            Self::MetricsResetDisabled => "metrics_reset_disabled",
+            Self::InvalidMetricLabel { .. } => "invalid_metric_label",
+            Self::LogFilter {
+                source: LogFilterError::Unsupported,
+            } => "log_filter_unsupported",
+            Self::LogFilter {
+                source: LogFilterError::Invalid { .. },
+            } => "invalid_log_filter",
+            Self::InvalidLogLevelRequest { .. } => "invalid_log_level_request",
+            Self::ReadingBody { .. } => "reading_body",
+            Self::RunModeRouteError { .. } => "run_mode_route_error",
+        }
+    }
+}
+
 impl HttpApiErrorSource for ApplicationError {
     fn to_http_api_error(&self) -> HttpApiError {
-        match self {
+        let error = match self {
             e @ Self::InvalidQueryString { .. } => e.invalid(),
+            e @ Self::InvalidContentLength { .. } => e.invalid(),
+            e @ Self::RequestSizeExceeded { .. } => {
+                HttpApiError::new(HttpApiErrorCode::RequestTooLarge, e.to_string())
+            }
             e @ Self::PProf { .. } => e.internal_error(),
             e @ Self::Prost { .. } => e.internal_error(),
             e @ Self::ProstIO { .. } => e.internal_error(),
             e @ Self::EmptyFlamegraph => e.empty_value(),
             e @ Self::HeappyIsNotCompiled => e.internal_error(),
             e @ Self::PProfIsNotCompiled => e.internal_error(),
+            e @ Self::MetricsResetDisabled => {
+                HttpApiError::new(HttpApiErrorCode::Forbidden, e.to_string())
+            }
+            e @ Self::InvalidMetricLabel { .. } => e.internal_error(),
+            e @ Self::LogFilter {
+                source: LogFilterError::Unsupported,
+            } => HttpApiError::new(HttpApiErrorCode::NotImplemented, e.to_string()),
+            e @ Self::LogFilter {
+                source: LogFilterError::Invalid { .. },
+            } => e.invalid(),
+            e @ Self::InvalidLogLevelRequest { .. } => e.invalid(),
+            e @ Self::ReadingBody { .. } => e.invalid(),
             #[cfg(feature = "heappy")]
             e @ Self::HeappyError { .. } => e.internal_error(),
-            Self::RunModeRouteError { e } => e.to_http_api_error(),
-        }
+            // the run mode is responsible for its own error codes
+            Self::RunModeRouteError { e } => return e.to_http_api_error(),
+        };
+
+        error.with_error_code(self.error_code())
     }
 }
 
+/// Configuration of the HTTP server.
+#[derive(Debug, Clone, Default)]
+pub struct HttpConfig {
+    /// Maximum size of an HTTP request, in bytes.
+    pub max_request_size: usize,
+
+    /// Requests taking at least this long to handle are logged at warn level,
+    /// along with a redacted excerpt of their body.
+    pub slow_request_threshold: Option<Duration>,
+
+    /// Serve `POST /debug/metrics/reset`, for use in tests.
+    pub enable_metrics_reset: bool,
+
+    /// Replay the responses to retried requests carrying an `Idempotency-Key`
+    /// header. If `None`, the header is ignored.
+    pub idempotency: Option<IdempotencyConfig>,
+}
+
+/// The paths that requests are labelled with in the HTTP request metrics, unless
+/// a server type specifies its own. Requests for any other path are labelled
+/// [`PathTemplates::OTHER`].
+pub const DEFAULT_PATH_TEMPLATES: &[&str] = &[
+    "/health",
+    "/metrics",
+    "/api/versions",
+    "/api/v2/write",
+    "/api/v2/delete",
+    "/debug/metrics/reset",
+    "/debug/pprof",
+    "/debug/pprof/profile",
+    "/debug/pprof/stream",
+    "/debug/pprof/allocs",
+    "/debug/pprof/active",
+    "/debug/log-level",
+];
+
 pub async fn serve(
     addr: AddrIncoming,
     server_type: Arc<dyn ServerType>,
     shutdown: CancellationToken,
     trace_header_parser: TraceHeaderParser,
+    config: HttpConfig,
 ) -> Result<(), hyper::Error> {
     let metric_registry = server_type.metric_registry();
     let trace_collector = server_type.trace_collector();
+    let in_flight = metric_registry
+        .register_metric::<U64Gauge>(
+            "http_requests_in_flight",
+            "number of HTTP requests currently being handled",
+        )
+        .recorder(&[]);
 
-    let trace_layer = TraceLayer::new(trace_header_parser, metric_registry, trace_collector, false);
+    let trace_layer = TraceLayer::new(trace_header_parser, metric_registry, trace_collector, false)
+        .with_path_templates(PathTemplates::new(server_type.http_path_templates()));
+    let idempotency_layer = IdempotencyLayer::new(config.idempotency.clone());
 
     hyper::Server::builder(addr)
         .serve(hyper::service::make_service_fn(|_conn: &AddrStream| {
             let server_type = Arc::clone(&server_type);
+            let config = config.clone();
+            let in_flight = in_flight.clone();
             let service = hyper::service::service_fn(move |request: Request<_>| {
-                route_request(Arc::clone(&server_type), request)
+                route_request(
+                    Arc::clone(&server_type),
+                    request,
+                    config.clone(),
+                    in_flight.clone(),
+                )
             });
 
+            let service = idempotency_layer.layer(service);
             let service = trace_layer.layer(service);
             futures::future::ready(Ok::<_, Infallible>(service))
         }))
@@ -112,10 +260,34 @@ pub async fn serve(
         .await
 }
 
+/// Increments a gauge of in-flight requests for as long as it is held.
+///
+/// The gauge is decremented on drop, so requests that are cancelled or whose
+/// handler panics are no longer counted.
+#[derive(Debug)]
+struct InFlightGuard(U64Gauge);
+
+impl InFlightGuard {
+    fn new(gauge: U64Gauge) -> Self {
+        gauge.inc(1);
+        Self(gauge)
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.dec(1);
+    }
+}
+
 async fn route_request(
     server_type: Arc<dyn ServerType>,
     mut req: Request<Body>,
+    config: HttpConfig,
+    in_flight: U64Gauge,
 ) -> Result<Response<Body>, Infallible> {
+    let _in_flight = InFlightGuard::new(in_flight);
+
     // we don't need the authorization header and we don't want to accidentally log it.
     req.headers_mut().remove("authorization");
     debug!(request = ?req,"Processing request");
@@ -123,19 +295,56 @@ async fn route_request(
     let method = req.method().clone();
     let uri = req.uri().clone();
     let content_length = req.headers().get("content-length").cloned();
+    let start = Instant::now();
 
-    let response = match (method.clone(), uri.path()) {
-        (Method::GET, "/health") => health(),
-        (Method::GET, "/metrics") => handle_metrics(server_type.as_ref()),
-        (Method::GET, "/debug/pprof") => pprof_home(req).await,
-        (Method::GET, "/debug/pprof/profile") => pprof_profile(req).await,
-        (Method::GET, "/debug/pprof/allocs") => pprof_heappy_profile(req).await,
-        _ => server_type
-            .route_http_request(req)
-            .await
-            .map_err(|e| ApplicationError::RunModeRouteError { e }),
+    // Record the start of the body as the handler reads it, should the request
+    // turn out to be slow.
+    let body_excerpt = BodyExcerpt::default();
+    if config.slow_request_threshold.is_some() {
+        let body = std::mem::take(req.body_mut());
+        *req.body_mut() = body_excerpt.capture(body);
+    }
+
+    let response = match check_content_length(&req, config.max_request_size) {
+        Ok(()) => match (method.clone(), uri.path()) {
+            (Method::GET, "/health") => health(),
+            (Method::GET, "/metrics") => handle_metrics(server_type.as_ref()),
+            (Method::GET, "/api/versions") => handle_api_versions(server_type.as_ref()),
+            (Method::POST, "/debug/metrics/reset") => {
+                handle_metrics_reset(server_type.as_ref(), config.enable_metrics_reset)
+            }
+            (Method::GET, "/debug/pprof") => pprof_home(req).await,
+            (Method::GET, "/debug/pprof/profile") => pprof_profile(req).await,
+            (Method::GET, "/debug/pprof/stream") => pprof_stream(req).await,
+            (Method::GET, "/debug/pprof/allocs") => pprof_heappy_profile(req).await,
+            (Method::GET, "/debug/pprof/active") => pprof_active(),
+            (Method::GET, "/debug/log-level") => handle_get_log_level(server_type.as_ref()),
+            (Method::POST, "/debug/log-level") => {
+                handle_set_log_level(server_type.as_ref(), req, config.max_request_size).await
+            }
+            _ => server_type
+                .route_http_request(req)
+                .await
+                .map_err(|e| ApplicationError::RunModeRouteError { e }),
+        },
+        Err(e) => Err(e),
     };
 
+    // Log the query string and the excerpt of the body read by the handler,
+    // either of which may carry the query text of the InfluxQL API, with any
+    // credentials redacted.
+    let elapsed = start.elapsed();
+    if matches!(config.slow_request_threshold, Some(threshold) if elapsed >= threshold) {
+        warn!(
+            %method,
+            path = %uri.path(),
+            query = %RedactedQuery(uri.query().unwrap_or_default()),
+            body = %body_excerpt,
+            ?elapsed,
+            "Slow HTTP request"
+        );
+    }
+
     // TODO: Move logging to TraceLayer
     match response {
         Ok(response) => {
@@ -154,6 +363,42 @@ async fn route_request(
     }
 }
 
+/// Validates the declared `content-length` of `req` before any of the body is read.
+///
+/// Requests declaring more than `max_request_size` bytes are rejected without
+/// buffering. A body that differs in size from the declared length is rejected
+/// as it is read, by the body reader of the route, such as
+/// [`utils::parse_body`].
+fn check_content_length(
+    req: &Request<Body>,
+    max_request_size: usize,
+) -> Result<(), ApplicationError> {
+    let value = match req.headers().get(CONTENT_LENGTH) {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+
+    let content_length = match value.to_str().ok().and_then(|v| v.parse::<u64>().ok()) {
+        Some(content_length) => content_length,
+        None => {
+            return InvalidContentLengthSnafu {
+                value: value.clone(),
+            }
+            .fail()
+        }
+    };
+
+    if content_length > max_request_size as u64 {
+        return RequestSizeExceededSnafu {
+            content_length,
+            max_request_size,
+        }
+        .fail();
+    }
+
+    Ok(())
+}
+
 fn health() -> Result<Response<Body>, ApplicationError> {
     let response_body = "OK";
     Ok(Response::new(Body::from(response_body.to_string())))
@@ -161,12 +406,82 @@ fn health() -> Result<Response<Body>, ApplicationError> {
 
 fn handle_metrics(server_type: &dyn ServerType) -> Result<Response<Body>, ApplicationError> {
     let mut body: Vec<u8> = Default::default();
-    let mut reporter = metric_exporters::PrometheusTextEncoder::new(&mut body);
+    let mut reporter = metric_exporters::PrometheusTextEncoder::new(&mut body)
+        .with_global_labels(server_type.global_metric_labels())
+        .context(InvalidMetricLabelSnafu)?;
     server_type.metric_registry().report(&mut reporter);
 
     Ok(Response::new(Body::from(body)))
 }
 
+/// Resets the counters of the metric registry, returning the number reset.
+fn handle_metrics_reset(
+    server_type: &dyn ServerType,
+    enabled: bool,
+) -> Result<Response<Body>, ApplicationError> {
+    if !enabled {
+        return MetricsResetDisabledSnafu.fail();
+    }
+
+    let reset = server_type.metric_registry().reset();
+    debug!(reset, "Reset metrics");
+    let body = serde_json::json!({ "reset": reset }).to_string();
+
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .expect("response should be valid"))
+}
+
+fn handle_get_log_level(server_type: &dyn ServerType) -> Result<Response<Body>, ApplicationError> {
+    let filter = server_type.log_filter().context(LogFilterSnafu)?;
+    let body = serde_json::json!({ "filter": filter }).to_string();
+
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .expect("response should be valid"))
+}
+
+/// The body of a `POST /debug/log-level` request.
+#[derive(Debug, Deserialize)]
+struct LogLevelRequest {
+    /// The new log filter, such as `debug` or `info,ioxd=debug`.
+    level: String,
+}
+
+async fn handle_set_log_level(
+    server_type: &dyn ServerType,
+    req: Request<Body>,
+    max_request_size: usize,
+) -> Result<Response<Body>, ApplicationError> {
+    let body = utils::parse_body(req, max_request_size)
+        .await
+        .context(ReadingBodySnafu)?;
+    let request: LogLevelRequest =
+        serde_json::from_slice(&body).context(InvalidLogLevelRequestSnafu)?;
+
+    server_type
+        .set_log_filter(&request.level)
+        .context(LogFilterSnafu)?;
+    warn!(filter = %request.level, "Reconfigured log filter");
+    let body = serde_json::json!({ "filter": request.level }).to_string();
+
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .expect("response should be valid"))
+}
+
+fn handle_api_versions(server_type: &dyn ServerType) -> Result<Response<Body>, ApplicationError> {
+    let body = serde_json::json!({ "versions": server_type.api_versions() }).to_string();
+
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .expect("response should be valid"))
+}
+
 async fn pprof_home(req: Request<Body>) -> Result<Response<Body>, ApplicationError> {
     let default_host = HeaderValue::from_static("localhost");
     let host = req
@@ -189,6 +504,22 @@ async fn pprof_home(req: Request<Body>) -> Result<Response<Body>, ApplicationErr
     ))))
 }
 
+/// Lists the CPU / allocation profiling sessions currently in progress.
+fn pprof_active() -> Result<Response<Body>, ApplicationError> {
+    #[cfg(any(feature = "pprof", feature = "heappy"))]
+    let sessions = profile_sessions::active();
+    // Sessions are only started by the profilers
+    #[cfg(not(any(feature = "pprof", feature = "heappy")))]
+    let sessions: [(); 0] = [];
+
+    let body = serde_json::to_string(&sessions).expect("sessions serialize");
+
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .expect("response should be valid"))
+}
+
 #[derive(Debug, Deserialize)]
 struct PProfArgs {
     #[serde(default = "PProfArgs::default_seconds")]
@@ -210,6 +541,35 @@ impl PProfArgs {
     }
 }
 
+/// The longest a CPU profile may be streamed for, irrespective of the
+/// requested `max_seconds`.
+const PPROF_STREAM_MAX_SECONDS: u64 = 3600;
+
+#[derive(Debug, Deserialize)]
+struct PProfStreamArgs {
+    // The duration of each profile in the stream.
+    #[serde(default = "PProfStreamArgs::default_seconds")]
+    #[allow(dead_code)]
+    seconds: u64,
+    #[serde(default = "PProfArgs::default_frequency")]
+    #[allow(dead_code)]
+    frequency: NonZeroI32,
+    // The total duration of the stream, capped at PPROF_STREAM_MAX_SECONDS.
+    #[serde(default = "PProfStreamArgs::default_max_seconds")]
+    #[allow(dead_code)]
+    max_seconds: u64,
+}
+
+impl PProfStreamArgs {
+    fn default_seconds() -> u64 {
+        10
+    }
+
+    fn default_max_seconds() -> u64 {
+        300
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct PProfAllocsArgs {
     #[serde(default = "PProfAllocsArgs::default_seconds")]
@@ -239,7 +599,6 @@ impl PProfAllocsArgs {
 #[cfg(feature = "pprof")]
 async fn pprof_profile(req: Request<Body>) -> Result<Response<Body>, ApplicationError> {
     use ::pprof::protos::Message;
-    use snafu::ResultExt;
 
     let query_string = req.uri().query().unwrap_or_default();
     let query: PProfArgs = serde_urlencoded::from_str(query_string)
@@ -287,11 +646,31 @@ async fn pprof_profile(_req: Request<Body>) -> Result<Response<Body>, Applicatio
     PProfIsNotCompiledSnafu {}.fail()
 }
 
+/// Streams CPU profiles until the client disconnects, or `max_seconds` elapse.
+#[cfg(feature = "pprof")]
+async fn pprof_stream(req: Request<Body>) -> Result<Response<Body>, ApplicationError> {
+    let query_string = req.uri().query().unwrap_or_default();
+    let query: PProfStreamArgs = serde_urlencoded::from_str(query_string)
+        .context(InvalidQueryStringSnafu { query_string })?;
+
+    let max_seconds = query.max_seconds.min(PPROF_STREAM_MAX_SECONDS);
+    let seconds = query.seconds.clamp(1, max_seconds.max(1));
+    let profiles = self::pprof::stream_rsprof(seconds, query.frequency.get(), max_seconds);
+
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, "application/octet-stream")
+        .body(Body::wrap_stream(profiles))
+        .expect("response should be valid"))
+}
+
+#[cfg(not(feature = "pprof"))]
+async fn pprof_stream(_req: Request<Body>) -> Result<Response<Body>, ApplicationError> {
+    PProfIsNotCompiledSnafu {}.fail()
+}
+
 // If heappy support is enabled, call it
 #[cfg(feature = "heappy")]
 async fn pprof_heappy_profile(req: Request<Body>) -> Result<Response<Body>, ApplicationError> {
-    use snafu::ResultExt;
-
     let query_string = req.uri().query().unwrap_or_default();
     let query: PProfAllocsArgs = serde_urlencoded::from_str(query_string)
         .context(InvalidQueryStringSnafu { query_string })?;
@@ -328,3 +707,576 @@ async fn pprof_heappy_profile(req: Request<Body>) -> Result<Response<Body>, Appl
 async fn pprof_heappy_profile(_req: Request<Body>) -> Result<Response<Body>, ApplicationError> {
     HeappyIsNotCompiledSnafu {}.fail()
 }
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use hyper::StatusCode;
+    use metric::Registry;
+    use test_helpers::{assert_contains, assert_not_contains, tracing::TracingCapture};
+    use trace::TraceCollector;
+
+    use crate::{
+        rpc::RpcBuilderInput,
+        server_type::{RpcError, ServerType},
+    };
+
+    use super::*;
+
+    /// A [`ServerType`] that reads the body of each request and takes `delay`
+    /// to handle it.
+    #[derive(Debug)]
+    struct SlowServerType {
+        metric_registry: Arc<Registry>,
+        delay: Duration,
+        global_metric_labels: Vec<(String, String)>,
+        /// The log filter, if reconfiguring it is supported.
+        log_filter: Option<parking_lot::Mutex<String>>,
+    }
+
+    #[async_trait]
+    impl ServerType for SlowServerType {
+        fn metric_registry(&self) -> Arc<Registry> {
+            Arc::clone(&self.metric_registry)
+        }
+
+        fn trace_collector(&self) -> Option<Arc<dyn TraceCollector>> {
+            None
+        }
+
+        fn global_metric_labels(&self) -> Vec<(String, String)> {
+            self.global_metric_labels.clone()
+        }
+
+        fn log_filter(&self) -> Result<String, LogFilterError> {
+            match &self.log_filter {
+                Some(filter) => Ok(filter.lock().clone()),
+                None => Err(LogFilterError::Unsupported),
+            }
+        }
+
+        fn set_log_filter(&self, filter: &str) -> Result<(), LogFilterError> {
+            let current = self
+                .log_filter
+                .as_ref()
+                .ok_or(LogFilterError::Unsupported)?;
+            if !["trace", "debug", "info", "warn", "error"].contains(&filter) {
+                return Err(LogFilterError::Invalid {
+                    filter: filter.to_string(),
+                    reason: "unknown level".to_string(),
+                });
+            }
+            *current.lock() = filter.to_string();
+            Ok(())
+        }
+
+        async fn route_http_request(
+            &self,
+            req: Request<Body>,
+        ) -> Result<Response<Body>, Box<dyn HttpApiErrorSource>> {
+            hyper::body::to_bytes(req.into_body()).await.unwrap();
+            tokio::time::sleep(self.delay).await;
+            Ok(Response::new(Body::empty()))
+        }
+
+        async fn server_grpc(
+            self: Arc<Self>,
+            _builder_input: RpcBuilderInput,
+        ) -> Result<(), RpcError> {
+            unimplemented!()
+        }
+
+        async fn join(self: Arc<Self>) {}
+
+        fn shutdown(&self) {}
+    }
+
+    async fn route_slow_request(delay: Duration, slow_request_threshold: Duration) {
+        let server_type = Arc::new(SlowServerType {
+            metric_registry: Default::default(),
+            delay,
+            global_metric_labels: vec![],
+            log_filter: None,
+        });
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/query?db=foo&p=secret")
+            .body(Body::from("q=SELECT+1&password=secret"))
+            .unwrap();
+        let config = HttpConfig {
+            max_request_size: 1024,
+            slow_request_threshold: Some(slow_request_threshold),
+            ..Default::default()
+        };
+
+        let response = route_request(server_type, req, config, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_slow_request_logging() {
+        let capture = TracingCapture::new();
+        route_slow_request(Duration::from_millis(50), Duration::from_millis(10)).await;
+
+        let logs = capture.to_string();
+        assert_contains!(&logs, "Slow HTTP request");
+        assert_contains!(&logs, "path = /query");
+        assert_contains!(&logs, "query = db=foo&p=REDACTED");
+        assert_contains!(&logs, "body = q=SELECT+1&password=REDACTED");
+        assert_not_contains!(&logs, "secret");
+    }
+
+    #[tokio::test]
+    async fn test_fast_request_logging() {
+        let capture = TracingCapture::new();
+        route_slow_request(Duration::ZERO, Duration::from_secs(3600)).await;
+
+        assert_not_contains!(capture.to_string(), "Slow HTTP request");
+    }
+
+    async fn reset_metrics(enable_metrics_reset: bool) -> (Arc<Registry>, Response<Body>) {
+        let metric_registry = Arc::new(Registry::new());
+        metric_registry
+            .register_metric::<metric::U64Counter>("requests", "test counter")
+            .recorder(&[("status", "ok")])
+            .inc(42);
+
+        let server_type = Arc::new(SlowServerType {
+            metric_registry: Arc::clone(&metric_registry),
+            delay: Duration::ZERO,
+            global_metric_labels: vec![],
+            log_filter: None,
+        });
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost/debug/metrics/reset")
+            .body(Body::empty())
+            .unwrap();
+        let config = HttpConfig {
+            max_request_size: 1024,
+            enable_metrics_reset,
+            ..Default::default()
+        };
+
+        let response = route_request(server_type, req, config, Default::default())
+            .await
+            .unwrap();
+        (metric_registry, response)
+    }
+
+    fn requests_counter(metric_registry: &Registry) -> u64 {
+        metric_registry
+            .get_instrument::<metric::Metric<metric::U64Counter>>("requests")
+            .unwrap()
+            .get_observer(&metric::Attributes::from(&[("status", "ok")]))
+            .unwrap()
+            .fetch()
+    }
+
+    #[tokio::test]
+    async fn test_metrics_reset() {
+        let (metric_registry, response) = reset_metrics(true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body, serde_json::json!({ "reset": 1 }));
+        assert_eq!(requests_counter(&metric_registry), 0);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_reset_disabled() {
+        let (metric_registry, response) = reset_metrics(false).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error_code"], "metrics_reset_disabled");
+        assert_eq!(requests_counter(&metric_registry), 42);
+    }
+
+    async fn route_log_level(
+        server_type: &Arc<SlowServerType>,
+        method: Method,
+        body: &str,
+    ) -> Response<Body> {
+        let req = Request::builder()
+            .method(method)
+            .uri("http://localhost/debug/log-level")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let config = HttpConfig {
+            max_request_size: 1024,
+            ..Default::default()
+        };
+
+        route_request(
+            Arc::clone(server_type) as _,
+            req,
+            config,
+            Default::default(),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_log_level() {
+        let server_type = Arc::new(SlowServerType {
+            metric_registry: Default::default(),
+            delay: Duration::ZERO,
+            global_metric_labels: vec![],
+            log_filter: Some(parking_lot::Mutex::new("info".to_string())),
+        });
+
+        let response = route_log_level(&server_type, Method::GET, "").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_string(response).await, r#"{"filter":"info"}"#);
+
+        let response = route_log_level(&server_type, Method::POST, r#"{"level":"debug"}"#).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_string(response).await, r#"{"filter":"debug"}"#);
+
+        let response = route_log_level(&server_type, Method::GET, "").await;
+        assert_eq!(body_string(response).await, r#"{"filter":"debug"}"#);
+
+        // Invalid filters and requests are rejected, leaving the filter unchanged
+        for body in [r#"{"level":"loud"}"#, r#"{"lvl":"info"}"#, "info"] {
+            let response = route_log_level(&server_type, Method::POST, body).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", body);
+        }
+        let response = route_log_level(&server_type, Method::GET, "").await;
+        assert_eq!(body_string(response).await, r#"{"filter":"debug"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_log_level_unsupported() {
+        let server_type = Arc::new(SlowServerType {
+            metric_registry: Default::default(),
+            delay: Duration::ZERO,
+            global_metric_labels: vec![],
+            log_filter: None,
+        });
+
+        for (method, body) in [(Method::GET, ""), (Method::POST, r#"{"level":"debug"}"#)] {
+            let response = route_log_level(&server_type, method, body).await;
+            assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+
+            let body: serde_json::Value =
+                serde_json::from_str(&body_string(response).await).unwrap();
+            assert_eq!(body["error_code"], "log_filter_unsupported");
+        }
+    }
+
+    async fn body_string(response: Response<Body>) -> String {
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    async fn get_metrics(global_metric_labels: Vec<(String, String)>) -> Response<Body> {
+        let metric_registry = Arc::new(Registry::new());
+        metric_registry
+            .register_metric::<metric::U64Counter>("requests", "test counter")
+            .recorder(&[("status", "ok")])
+            .inc(42);
+
+        let server_type = Arc::new(SlowServerType {
+            metric_registry,
+            delay: Duration::ZERO,
+            global_metric_labels,
+            log_filter: None,
+        });
+        let req = Request::builder()
+            .uri("http://localhost/metrics")
+            .body(Body::empty())
+            .unwrap();
+        let config = HttpConfig {
+            max_request_size: 1024,
+            ..Default::default()
+        };
+
+        route_request(server_type, req, config, Default::default())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_global_metric_labels() {
+        let response = get_metrics(vec![
+            ("env".to_string(), "prod".to_string()),
+            ("region".to_string(), "eu-west".to_string()),
+        ])
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_string(response).await;
+        assert_contains!(
+            &body,
+            "\nrequests_total{status=\"ok\",env=\"prod\",region=\"eu-west\"} 42\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_global_metric_labels_invalid() {
+        let response = get_metrics(vec![("__name__".to_string(), "foo".to_string())]).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let body: serde_json::Value = serde_json::from_str(&body_string(response).await).unwrap();
+        assert_eq!(body["error_code"], "invalid_metric_label");
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_requests() {
+        let server_type = Arc::new(SlowServerType {
+            metric_registry: Default::default(),
+            delay: Duration::ZERO,
+            global_metric_labels: vec![],
+            log_filter: None,
+        });
+        let config = HttpConfig {
+            max_request_size: 1024,
+            ..Default::default()
+        };
+        let in_flight = U64Gauge::default();
+
+        // The handler reads the body of each request, so a request is held open
+        // for as long as its body sender is
+        let (senders, mut requests): (Vec<_>, Vec<_>) = (0..2)
+            .map(|_| {
+                let (body_tx, body) = Body::channel();
+                let req = Request::builder()
+                    .uri("http://localhost/api/v2/write")
+                    .body(body)
+                    .unwrap();
+                let request = Box::pin(route_request(
+                    Arc::clone(&server_type) as _,
+                    req,
+                    config.clone(),
+                    in_flight.clone(),
+                ));
+                (body_tx, request)
+            })
+            .unzip();
+        assert_eq!(in_flight.fetch(), 0);
+
+        for (i, request) in requests.iter_mut().enumerate() {
+            assert!(futures::poll!(request).is_pending());
+            assert_eq!(in_flight.fetch(), i as u64 + 1);
+        }
+
+        drop(senders);
+        for (i, request) in requests.into_iter().enumerate() {
+            let response = request.await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(in_flight.fetch(), 1 - i as u64);
+        }
+    }
+
+    #[test]
+    fn test_in_flight_guard_panic() {
+        let in_flight = U64Gauge::default();
+
+        let captured = in_flight.clone();
+        let result = std::panic::catch_unwind(move || {
+            let _guard = InFlightGuard::new(captured.clone());
+            assert_eq!(captured.fetch(), 1);
+            panic!("handler panicked");
+        });
+
+        assert!(result.is_err());
+        assert_eq!(in_flight.fetch(), 0);
+    }
+
+    fn request_with_content_length(content_length: &str, body: &'static str) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri("https://ye-olde-non-existent-server/")
+            .header(CONTENT_LENGTH, content_length)
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_check_content_length() {
+        let req = request_with_content_length("3", "foo");
+        check_content_length(&req, 3).unwrap();
+
+        // No declared length leaves the body to be checked when it is read
+        let req = Request::builder().body(Body::from("foo")).unwrap();
+        check_content_length(&req, 1).unwrap();
+    }
+
+    #[test]
+    fn test_check_content_length_too_large() {
+        let req = request_with_content_length("1048577", "");
+        let err = check_content_length(&req, 1024 * 1024).unwrap_err();
+        assert!(matches!(
+            err,
+            ApplicationError::RequestSizeExceeded {
+                content_length: 1048577,
+                max_request_size: 1048576
+            }
+        ));
+        assert_eq!(
+            err.to_http_api_error().response().status(),
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
+
+    #[test]
+    fn test_check_content_length_invalid() {
+        let req = request_with_content_length("bananas", "foo");
+        let err = check_content_length(&req, 1024).unwrap_err();
+        assert!(matches!(err, ApplicationError::InvalidContentLength { .. }));
+        assert_eq!(
+            err.to_http_api_error().response().status(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    /// The code expected for each variant, with no wildcard arm so that a new
+    /// variant fails to compile until its code is pinned here and it is added
+    /// to the cases of [`test_error_codes`].
+    fn expected_error_code(error: &ApplicationError) -> &'static str {
+        match error {
+            ApplicationError::InvalidQueryString { .. } => "invalid_query_string",
+            ApplicationError::InvalidContentLength { .. } => "invalid_content_length",
+            ApplicationError::RequestSizeExceeded { .. } => "request_size_exceeded",
+            ApplicationError::PProf { .. } => "pprof",
+            #[cfg(feature = "heappy")]
+            ApplicationError::HeappyError { .. } => "heappy",
+            ApplicationError::Prost { .. } => "prost",
+            ApplicationError::ProstIO { .. } => "prost_io",
+            ApplicationError::EmptyFlamegraph => "empty_flamegraph",
+            ApplicationError::HeappyIsNotCompiled => "heappy_not_compiled",
+            ApplicationError::PProfIsNotCompiled => "pprof_not_compiled",
+            ApplicationError::MetricsResetDisabled => "metrics_reset_disabled",
+            ApplicationError::InvalidMetricLabel { .. } => "invalid_metric_label",
+            ApplicationError::LogFilter {
+                source: LogFilterError::Unsupported,
+            } => "log_filter_unsupported",
+            ApplicationError::LogFilter {
+                source: LogFilterError::Invalid { .. },
+            } => "invalid_log_filter",
+            ApplicationError::InvalidLogLevelRequest { .. } => "invalid_log_level_request",
+            ApplicationError::ReadingBody { .. } => "reading_body",
+            ApplicationError::RunModeRouteError { .. } => "run_mode_route_error",
+        }
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let query_error = serde_urlencoded::from_str::<PProfArgs>("seconds=bananas").unwrap_err();
+        let io_error = || std::io::Error::new(std::io::ErrorKind::Other, "io error");
+
+        let cases = vec![
+            ApplicationError::InvalidQueryString {
+                query_string: "seconds=bananas".to_string(),
+                source: query_error,
+            },
+            ApplicationError::InvalidContentLength {
+                value: HeaderValue::from_static("bananas"),
+            },
+            ApplicationError::RequestSizeExceeded {
+                content_length: 2,
+                max_request_size: 1,
+            },
+            ApplicationError::PProf {
+                source: Box::new(io_error()),
+            },
+            ApplicationError::Prost {
+                source: Box::new(io_error()),
+            },
+            ApplicationError::ProstIO { source: io_error() },
+            ApplicationError::EmptyFlamegraph,
+            ApplicationError::HeappyIsNotCompiled,
+            ApplicationError::PProfIsNotCompiled,
+            ApplicationError::MetricsResetDisabled,
+            ApplicationError::InvalidMetricLabel {
+                source: metric_exporters::InvalidLabelError::Empty,
+            },
+            ApplicationError::LogFilter {
+                source: LogFilterError::Unsupported,
+            },
+            ApplicationError::LogFilter {
+                source: LogFilterError::Invalid {
+                    filter: "loud".to_string(),
+                    reason: "unknown level".to_string(),
+                },
+            },
+            ApplicationError::InvalidLogLevelRequest {
+                source: serde_json::from_str::<LogLevelRequest>("info").unwrap_err(),
+            },
+            ApplicationError::ReadingBody {
+                source: ParseBodyError::RequestSizeExceeded { max_body_size: 1 },
+            },
+            ApplicationError::RunModeRouteError {
+                e: Box::new(ApplicationError::EmptyFlamegraph),
+            },
+        ];
+
+        let n_cases = cases.len();
+        let mut codes = std::collections::BTreeSet::new();
+        for error in cases {
+            assert_eq!(error.error_code(), expected_error_code(&error), "{}", error);
+            codes.insert(error.error_code());
+        }
+
+        // No two variants share a code
+        assert_eq!(codes.len(), n_cases);
+    }
+
+    #[tokio::test]
+    async fn test_error_code_in_body() {
+        let response = ApplicationError::PProfIsNotCompiled
+            .to_http_api_error()
+            .response();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "code": "internal error",
+                "message": "pprof support is not compiled",
+                "error_code": "pprof_not_compiled",
+            })
+        );
+
+        // Errors from the run mode carry their own codes
+        let response = ApplicationError::RunModeRouteError {
+            e: Box::new(ApplicationError::EmptyFlamegraph),
+        }
+        .to_http_api_error()
+        .response();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error_code"], "empty_flamegraph");
+    }
+
+    #[test]
+    fn test_default_path_templates() {
+        let templates = PathTemplates::new(DEFAULT_PATH_TEMPLATES.iter().copied());
+
+        // The routes served by route_request are labelled with their own path
+        for path in [
+            "/health",
+            "/metrics",
+            "/api/versions",
+            "/debug/metrics/reset",
+            "/debug/pprof",
+            "/debug/pprof/profile",
+            "/debug/pprof/stream",
+            "/debug/pprof/allocs",
+            "/debug/pprof/active",
+            "/debug/log-level",
+        ] {
+            assert_eq!(templates.normalize(path), path);
+        }
+
+        // No server type serves the 1.x API
+        assert_eq!(templates.normalize("/query"), PathTemplates::OTHER);
+        assert_eq!(templates.normalize("/write"), PathTemplates::OTHER);
+    }
+}
@@ -1,8 +1,13 @@
+use futures::{Stream, StreamExt};
 use observability_deps::tracing::info;
+use pprof::protos::Message;
 use tokio::time::Duration;
 
+use super::profile_sessions::{self, ProfileKind};
+
 pub async fn dump_rsprof(seconds: u64, frequency: i32) -> pprof::Result<pprof::Report> {
     let guard = pprof::ProfilerGuard::new(frequency)?;
+    let _session = profile_sessions::start(ProfileKind::Cpu, seconds);
     info!(
         "start profiling {} seconds with frequency {} /s",
         seconds, frequency
@@ -16,3 +21,74 @@ pub async fn dump_rsprof(seconds: u64, frequency: i32) -> pprof::Result<pprof::R
     );
     guard.report().build()
 }
+
+/// Streams consecutive CPU profiles of `seconds` each, until `max_seconds` have
+/// been profiled or the stream is dropped, such as when the client disconnects.
+///
+/// Each profile is encoded as a length-delimited pprof protobuf.
+pub fn stream_rsprof(
+    seconds: u64,
+    frequency: i32,
+    max_seconds: u64,
+) -> impl Stream<Item = Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>> {
+    let snapshots = (max_seconds / seconds.max(1)).max(1);
+
+    futures::stream::iter(0..snapshots).then(move |_| async move {
+        let report = dump_rsprof(seconds, frequency).await?;
+        let mut body = Vec::new();
+        report.pprof()?.encode_length_delimited(&mut body)?;
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(body)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Only one profiler may run at a time.
+    static PROFILER: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    #[tokio::test]
+    async fn test_stream_rsprof() {
+        let _profiler = PROFILER.lock().await;
+
+        // The stream ends once `max_seconds` have been profiled
+        let snapshots = stream_rsprof(0, 99, 2).collect::<Vec<_>>().await;
+        assert_eq!(snapshots.len(), 2);
+        for snapshot in snapshots {
+            assert!(!snapshot.unwrap().is_empty());
+        }
+
+        // but may be dropped before then, such as when the client disconnects
+        let snapshots = stream_rsprof(0, 99, 3600).take(2).collect::<Vec<_>>().await;
+        assert_eq!(snapshots.len(), 2);
+
+        // which stops the profiler, so that another may be started
+        dump_rsprof(0, 99).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_active_session() {
+        let _profiler = PROFILER.lock().await;
+
+        let is_active = || {
+            profile_sessions::active()
+                .iter()
+                .any(|s| s.kind == ProfileKind::Cpu && s.requested_seconds == 3600)
+        };
+        assert!(!is_active());
+
+        // The session starts before the profile first waits
+        let mut profile = Box::pin(dump_rsprof(3600, 99));
+        assert!(futures::poll!(&mut profile).is_pending());
+        assert!(is_active());
+
+        // and ends when the profile is dropped, such as when the client
+        // disconnects
+        drop(profile);
+        assert!(!is_active());
+
+        // which stops the profiler, so that another may be started
+        dump_rsprof(0, 99).await.unwrap();
+    }
+}
@@ -0,0 +1,127 @@
+//! Tracks in-progress CPU / allocation profiling sessions so operators can see
+//! them via `/debug/pprof/active`.
+//!
+//! Compiled only when the "pprof" or "heappy" feature is enabled
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+static SESSIONS: Mutex<Vec<Session>> = parking_lot::const_mutex(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The kind of profile being collected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ProfileKind {
+    #[cfg(feature = "pprof")]
+    Cpu,
+    #[cfg(feature = "heappy")]
+    Alloc,
+}
+
+#[derive(Debug)]
+struct Session {
+    id: u64,
+    kind: ProfileKind,
+    started_at: SystemTime,
+    started: Instant,
+    requested_seconds: u64,
+}
+
+/// A description of a running profiling session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct ActiveSession {
+    pub(crate) kind: ProfileKind,
+    /// Seconds since the UNIX epoch at which the session started.
+    pub(crate) started_at: u64,
+    pub(crate) requested_seconds: u64,
+    /// Seconds until the session is expected to complete.
+    pub(crate) remaining: u64,
+}
+
+/// Marks a profiling session as active until dropped.
+#[derive(Debug)]
+#[must_use = "the session is only active while the guard is held"]
+pub(crate) struct SessionGuard {
+    id: u64,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        SESSIONS.lock().retain(|s| s.id != self.id);
+    }
+}
+
+/// Record the start of a `kind` profile lasting `requested_seconds`.
+pub(crate) fn start(kind: ProfileKind, requested_seconds: u64) -> SessionGuard {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    SESSIONS.lock().push(Session {
+        id,
+        kind,
+        started_at: SystemTime::now(),
+        started: Instant::now(),
+        requested_seconds,
+    });
+    SessionGuard { id }
+}
+
+/// Return all the currently running profiling sessions, oldest first.
+pub(crate) fn active() -> Vec<ActiveSession> {
+    SESSIONS
+        .lock()
+        .iter()
+        .map(|s| ActiveSession {
+            kind: s.kind,
+            started_at: s
+                .started_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            requested_seconds: s.requested_seconds,
+            remaining: Duration::from_secs(s.requested_seconds)
+                .saturating_sub(s.started.elapsed())
+                .as_secs(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "heappy")]
+    const KIND: ProfileKind = ProfileKind::Alloc;
+    #[cfg(not(feature = "heappy"))]
+    const KIND: ProfileKind = ProfileKind::Cpu;
+
+    // Other tests may run profiles concurrently, so only the sessions started
+    // here are inspected.
+    fn find(requested_seconds: u64) -> Vec<ActiveSession> {
+        active()
+            .into_iter()
+            .filter(|s| s.requested_seconds == requested_seconds)
+            .collect()
+    }
+
+    #[test]
+    fn test_session_guard() {
+        const SECONDS: u64 = 424_242;
+
+        assert!(find(SECONDS).is_empty());
+
+        let guard = start(KIND, SECONDS);
+        let sessions = find(SECONDS);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].kind, KIND);
+        assert!(sessions[0].started_at > 0);
+        assert!(sessions[0].remaining <= SECONDS);
+
+        drop(guard);
+        assert!(find(SECONDS).is_empty());
+    }
+}
@@ -12,7 +12,10 @@ use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use trace::RingBufferTraceCollector;
 
-use crate::{http::serve, server_type::ServerType};
+use crate::{
+    http::{serve, HttpConfig},
+    server_type::ServerType,
+};
 
 /// checks a http response against expected results
 pub async fn check_response(
@@ -114,6 +117,10 @@ where
                 server_type_captured,
                 CancellationToken::new(),
                 trace_header_parser,
+                HttpConfig {
+                    max_request_size: TEST_MAX_REQUEST_SIZE,
+                    ..Default::default()
+                },
             )
             .await
             .unwrap();
@@ -162,6 +169,21 @@ where
     check_response("health", response, StatusCode::OK, Some("OK")).await;
 }
 
+/// Assert that the API versions route reports `expected`, such as
+/// `{"versions":["v2"]}`.
+pub async fn assert_api_versions<T>(test_server: TestServer<T>, expected: &str)
+where
+    T: ServerType,
+{
+    let client = Client::new();
+    let response = client
+        .get(&format!("{}/api/versions", test_server.url()))
+        .send()
+        .await;
+
+    check_response("api versions", response, StatusCode::OK, Some(expected)).await;
+}
+
 /// Assert that metrics exposure is working.
 pub async fn assert_metrics<T>(test_server: TestServer<T>)
 where
@@ -1,10 +1,13 @@
+use std::sync::Arc;
+
 use bytes::{Bytes, BytesMut};
-use futures::StreamExt;
-use http::header::CONTENT_ENCODING;
+use futures::{StreamExt, TryStreamExt};
+use http::header::{CONTENT_ENCODING, CONTENT_LENGTH};
 use hyper::Body;
+use parking_lot::Mutex;
 use snafu::{ResultExt, Snafu};
 
-use super::error::{HttpApiError, HttpApiErrorExt, HttpApiErrorSource};
+use super::error::{HttpApiError, HttpApiErrorCode, HttpApiErrorExt, HttpApiErrorSource};
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Snafu)]
@@ -12,6 +15,16 @@ pub enum ParseBodyError {
     #[snafu(display("Body exceeds limit of {} bytes", max_body_size))]
     RequestSizeExceeded { max_body_size: usize },
 
+    #[snafu(display(
+        "Declared content-length of {} bytes does not match body of {} bytes",
+        content_length,
+        body_length
+    ))]
+    ContentLengthMismatch {
+        content_length: u64,
+        body_length: u64,
+    },
+
     #[snafu(display("Invalid content encoding: {}", content_encoding))]
     InvalidContentEncoding { content_encoding: String },
 
@@ -31,7 +44,10 @@ pub enum ParseBodyError {
 impl HttpApiErrorSource for ParseBodyError {
     fn to_http_api_error(&self) -> HttpApiError {
         match self {
-            e @ Self::RequestSizeExceeded { .. } => e.invalid(),
+            e @ Self::RequestSizeExceeded { .. } => {
+                HttpApiError::new(HttpApiErrorCode::RequestTooLarge, e.to_string())
+            }
+            e @ Self::ContentLengthMismatch { .. } => e.invalid(),
             e @ Self::InvalidContentEncoding { .. } => e.invalid(),
             e @ Self::ReadingHeaderAsUtf8 { .. } => e.invalid(),
             e @ Self::ReadingBodyAsGzip { .. } => e.invalid(),
@@ -40,8 +56,81 @@ impl HttpApiErrorSource for ParseBodyError {
     }
 }
 
+/// Query string parameters that carry credentials.
+const CREDENTIAL_PARAMS: &[&str] = &["p", "password", "token"];
+
+/// Displays a URL query string with the values of any credential parameters,
+/// such as `p` and `token`, replaced with `REDACTED`, so that it may be logged.
+#[derive(Debug, Clone, Copy)]
+pub struct RedactedQuery<'a>(pub &'a str);
+
+impl std::fmt::Display for RedactedQuery<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, param) in self.0.split('&').enumerate() {
+            if i > 0 {
+                f.write_str("&")?;
+            }
+            match param.split_once('=') {
+                Some((name, _)) if CREDENTIAL_PARAMS.contains(&name) => {
+                    write!(f, "{}=REDACTED", name)?
+                }
+                _ => f.write_str(param)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The maximum number of leading bytes of a request body kept by a
+/// [`BodyExcerpt`].
+pub const MAX_BODY_EXCERPT_SIZE: usize = 1024;
+
+#[derive(Debug, Default)]
+struct BodyExcerptState {
+    bytes: Vec<u8>,
+    truncated: bool,
+}
+
+/// Keeps the first [`MAX_BODY_EXCERPT_SIZE`] bytes of a request body as they
+/// are read by its handler, so that the body may be logged once it has been
+/// consumed.
+///
+/// The excerpt is displayed as (lossy) UTF-8 with the values of any credential
+/// parameters redacted, as for [`RedactedQuery`], and is followed by `...` if
+/// the body was longer.
+#[derive(Debug, Clone, Default)]
+pub struct BodyExcerpt(Arc<Mutex<BodyExcerptState>>);
+
+impl BodyExcerpt {
+    /// Wrap `body` so that the bytes read from it are recorded in this excerpt.
+    pub fn capture(&self, body: Body) -> Body {
+        let state = Arc::clone(&self.0);
+        Body::wrap_stream(body.inspect_ok(move |chunk| {
+            let mut state = state.lock();
+            let n = chunk.len().min(MAX_BODY_EXCERPT_SIZE - state.bytes.len());
+            state.bytes.extend_from_slice(&chunk[..n]);
+            state.truncated |= n < chunk.len();
+        }))
+    }
+}
+
+impl std::fmt::Display for BodyExcerpt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.0.lock();
+        let text = String::from_utf8_lossy(&state.bytes);
+        write!(f, "{}", RedactedQuery(&text))?;
+        if state.truncated {
+            f.write_str("...")?;
+        }
+        Ok(())
+    }
+}
+
 /// Parse the request's body into raw bytes, applying size limits and
 /// content encoding as needed.
+///
+/// If the request declares a `content-length`, a body of any other length is
+/// rejected.
 pub async fn parse_body(
     req: hyper::Request<Body>,
     max_size: usize,
@@ -64,6 +153,12 @@ pub async fn parse_body(
         }
     };
 
+    let content_length = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+
     let mut payload = req.into_body();
 
     let mut body = BytesMut::new();
@@ -76,7 +171,11 @@ pub async fn parse_body(
             });
         }
         body.extend_from_slice(&chunk);
+
+        // stop reading a body longer than declared
+        check_content_length(content_length, body.len(), false)?;
     }
+    check_content_length(content_length, body.len(), true)?;
     let body = body.freeze();
 
     // apply any content encoding needed
@@ -110,6 +209,27 @@ pub async fn parse_body(
     }
 }
 
+/// Fails if `body_length` bytes exceed the declared `content_length`, or, once
+/// the body has been read to the `end`, differ from it.
+fn check_content_length(
+    content_length: Option<u64>,
+    body_length: usize,
+    end: bool,
+) -> Result<(), ParseBodyError> {
+    let body_length = body_length as u64;
+    match content_length {
+        Some(content_length)
+            if body_length > content_length || (end && body_length != content_length) =>
+        {
+            Err(ParseBodyError::ContentLengthMismatch {
+                content_length,
+                body_length,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{io::Write, iter};
@@ -124,6 +244,44 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_redacted_query() {
+        assert_eq!(RedactedQuery("").to_string(), "");
+        assert_eq!(
+            RedactedQuery("db=foo&q=SELECT+1").to_string(),
+            "db=foo&q=SELECT+1"
+        );
+        assert_eq!(
+            RedactedQuery("u=bob&p=secret&q=SELECT+1&token=secret").to_string(),
+            "u=bob&p=REDACTED&q=SELECT+1&token=REDACTED"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_body_excerpt() {
+        let excerpt = BodyExcerpt::default();
+        let body = excerpt.capture(Body::from("q=SELECT+1&p=secret"));
+        hyper::body::to_bytes(body).await.unwrap();
+        assert_eq!(excerpt.to_string(), "q=SELECT+1&p=REDACTED");
+
+        // Only the leading bytes of a long body are kept
+        let excerpt = BodyExcerpt::default();
+        let chunks = ["a".repeat(1000), "b".repeat(1000), "c".repeat(1000)];
+        let body = excerpt.capture(Body::wrap_stream(stream::iter(
+            chunks.into_iter().map(Ok::<_, std::io::Error>),
+        )));
+        assert_eq!(hyper::body::to_bytes(body).await.unwrap().len(), 3000);
+        assert_eq!(
+            excerpt.to_string(),
+            format!("{}{}...", "a".repeat(1000), "b".repeat(24))
+        );
+
+        // Nothing is recorded for an unread body
+        let excerpt = BodyExcerpt::default();
+        let _body = excerpt.capture(Body::from("unread"));
+        assert_eq!(excerpt.to_string(), "");
+    }
+
     #[tokio::test]
     async fn client_hangup_during_parse() {
         #[derive(Debug, Snafu)]
@@ -152,6 +310,55 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_content_length_mismatch() {
+        let request = |content_length: &str, chunks: Vec<&'static str>| {
+            let chunks = chunks.into_iter().map(Ok::<_, std::io::Error>);
+            Request::builder()
+                .uri("https://ye-olde-non-existent-server/")
+                .header(CONTENT_LENGTH, content_length)
+                .body(Body::wrap_stream(stream::iter(chunks)))
+                .unwrap()
+        };
+
+        let got = parse_body(request("6", vec!["foo", "bar"]), TEST_MAX_REQUEST_SIZE)
+            .await
+            .unwrap();
+        assert_eq!(got, "foobar");
+
+        // A body shorter than declared
+        let err = parse_body(request("10", vec!["foo", "bar"]), TEST_MAX_REQUEST_SIZE)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ParseBodyError::ContentLengthMismatch {
+                content_length: 10,
+                body_length: 6
+            }
+        ));
+        assert_eq!(
+            err.to_http_api_error().response().status(),
+            hyper::StatusCode::BAD_REQUEST
+        );
+
+        // A body longer than declared is rejected once it exceeds the declared length
+        let err = parse_body(request("2", vec!["foo", "bar"]), TEST_MAX_REQUEST_SIZE)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ParseBodyError::ContentLengthMismatch {
+                content_length: 2,
+                body_length: 3
+            }
+        ));
+        assert_eq!(
+            err.to_http_api_error().response().status(),
+            hyper::StatusCode::BAD_REQUEST
+        );
+    }
+
     #[tokio::test]
     async fn test_read_gzipped_body_truncation() {
         const MAX_BYTES: usize = 1024;
@@ -136,6 +136,19 @@ pub async fn serve(
 
     let captured_server_type = Arc::clone(&server_type);
     let captured_shutdown = frontend_shutdown.clone();
+    let http_config = http::HttpConfig {
+        max_request_size: common_state.run_config().max_http_request_size,
+        slow_request_threshold: common_state.run_config().slow_http_request_threshold,
+        enable_metrics_reset: common_state.run_config().enable_metrics_reset,
+        idempotency: common_state.run_config().idempotency_key_ttl.map(|ttl| {
+            http::idempotency::IdempotencyConfig {
+                ttl,
+                max_entries: common_state.run_config().idempotency_key_cache_size,
+                max_body_size: common_state.run_config().max_http_request_size,
+                routes: common_state.run_config().idempotency_key_routes.clone(),
+            }
+        }),
+    };
     let http_server = async move {
         if let Some(http_listener) = http_listener {
             info!(server_type=?captured_server_type, "HTTP server listening");
@@ -144,6 +157,7 @@ pub async fn serve(
                 captured_server_type,
                 captured_shutdown,
                 trace_header_parser,
+                http_config,
             )
             .await?
         } else {
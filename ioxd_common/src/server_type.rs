@@ -5,6 +5,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use hyper::{Body, Request, Response};
 use metric::Registry;
+use serde::Serialize;
 use snafu::Snafu;
 use trace::TraceCollector;
 
@@ -35,6 +36,27 @@ impl From<tonic::transport::Error> for RpcError {
     }
 }
 
+/// An error reconfiguring the log filter of a server at runtime.
+#[derive(Debug, Snafu)]
+pub enum LogFilterError {
+    #[snafu(display("Reconfiguring the log filter is not supported"))]
+    Unsupported,
+
+    #[snafu(display("Invalid log filter '{}': {}", filter, reason))]
+    Invalid { filter: String, reason: String },
+}
+
+/// A version of the InfluxDB HTTP API that a server may speak.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiVersion {
+    /// The InfluxDB 1.x API, such as `/write` and `/query`.
+    V1,
+
+    /// The InfluxDB 2.x API, such as `/api/v2/write` and `/api/v2/query`.
+    V2,
+}
+
 #[async_trait]
 pub trait ServerType: std::fmt::Debug + Send + Sync + 'static {
     /// Metric registry associated with the server.
@@ -43,6 +65,39 @@ pub trait ServerType: std::fmt::Debug + Send + Sync + 'static {
     /// Trace collector associated with the server, if any.
     fn trace_collector(&self) -> Option<Arc<dyn TraceCollector>>;
 
+    /// Versions of the HTTP API spoken by the server, reported by `/api/versions`.
+    fn api_versions(&self) -> &[ApiVersion] {
+        &[]
+    }
+
+    /// Labels applied to every metric exported by `/metrics`, in addition to
+    /// its own attributes, such as the environment or region of a deployment.
+    fn global_metric_labels(&self) -> Vec<(String, String)> {
+        vec![]
+    }
+
+    /// Templates of the paths served over HTTP, with which the HTTP request
+    /// metrics are labelled in place of the raw request path, such as
+    /// `/api/v2/buckets/{id}`.
+    fn http_path_templates(&self) -> Vec<String> {
+        crate::http::DEFAULT_PATH_TEMPLATES
+            .iter()
+            .map(|t| t.to_string())
+            .collect()
+    }
+
+    /// The current log filter of the server, such as `info,ioxd=debug`,
+    /// reported by `GET /debug/log-level`.
+    fn log_filter(&self) -> Result<String, LogFilterError> {
+        Err(LogFilterError::Unsupported)
+    }
+
+    /// Reconfigure the log filter of the server at runtime, as requested by
+    /// `POST /debug/log-level`.
+    fn set_log_filter(&self, _filter: &str) -> Result<(), LogFilterError> {
+        Err(LogFilterError::Unsupported)
+    }
+
     /// Route given HTTP request.
     ///
     /// Note that this is only called if none of the shared, common routes (e.g. `/health`) match.
@@ -9,7 +9,7 @@ use ioxd_common::{
     http::error::{HttpApiError, HttpApiErrorSource},
     rpc::RpcBuilderInput,
     serve_builder,
-    server_type::{CommonServerState, RpcError, ServerType},
+    server_type::{ApiVersion, CommonServerState, RpcError, ServerType},
     setup_builder,
 };
 use metric::Registry;
@@ -18,8 +18,9 @@ use object_store::DynObjectStore;
 use observability_deps::tracing::info;
 use router::{
     dml_handlers::{
-        DmlHandler, DmlHandlerChainExt, FanOutAdaptor, InstrumentationDecorator, Partitioner,
-        SchemaValidator, ShardedWriteBuffer, WriteSummaryAdapter,
+        ConcurrencyLimit, DmlHandler, DmlHandlerChainExt, FanOutAdaptor, InstrumentationDecorator,
+        Partitioner, SchemaLimitDmlHandler, SchemaLimits, SchemaValidator, ShardedWriteBuffer,
+        WriteSummaryAdapter,
     },
     namespace_cache::{
         metrics::InstrumentedCache, MemoryNamespaceCache, NamespaceCache, ShardedCache,
@@ -43,6 +44,8 @@ use tokio_util::sync::CancellationToken;
 use trace::TraceCollector;
 use write_summary::WriteSummary;
 
+pub use router::dml_handlers::SchemaLimits;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("failed to initialise write buffer connection: {0}")]
@@ -105,6 +108,11 @@ where
         self.trace_collector.as_ref().map(Arc::clone)
     }
 
+    /// The router serves the InfluxDB 2.x write and delete APIs.
+    fn api_versions(&self) -> &[ApiVersion] {
+        &[ApiVersion::V2]
+    }
+
     /// Dispatches `req` to the router [`HttpDelegate`] delegate.
     ///
     /// [`HttpDelegate`]: router::server::http::HttpDelegate
@@ -172,6 +180,8 @@ pub async fn create_router_server_type(
     write_buffer_config: &WriteBufferConfig,
     query_pool_name: &str,
     request_limit: usize,
+    dml_request_limit: usize,
+    schema_limits: SchemaLimits,
 ) -> Result<Arc<dyn ServerType>> {
     // Initialise the sharded write buffer and instrument it with DML handler
     // metrics.
@@ -204,6 +214,16 @@ pub async fn create_router_server_type(
     let schema_validator =
         InstrumentationDecorator::new("schema_validator", &*metrics, schema_validator);
 
+    // Reject writes that would push a table over the configured schema limits
+    // before the schema validator adds their new columns to the catalog.
+    let schema_validator = SchemaLimitDmlHandler::new(
+        schema_validator,
+        Arc::clone(&catalog),
+        Arc::clone(&ns_cache),
+        schema_limits,
+        &*metrics,
+    );
+
     // Add a write partitioner into the handler stack that splits by the date
     // portion of the write's timestamp.
     let partitioner = Partitioner::new(PartitionTemplate {
@@ -285,6 +305,10 @@ pub async fn create_router_server_type(
     // Record the overall request handling latency
     let handler_stack = InstrumentationDecorator::new("request", &*metrics, handler_stack);
 
+    // Bound the number of requests executing in the handler stack, shedding
+    // any requests in excess of the limit before their body is read.
+    let handler_stack = ConcurrencyLimit::new(handler_stack, dml_request_limit);
+
     // Initialise the shard-mapping gRPC service.
     let shard_service = init_shard_service(sharder, write_buffer_config, catalog).await?;
 
@@ -399,8 +423,13 @@ where
 
 #[cfg(test)]
 mod tests {
-    use data_types::ColumnType;
+    use data_types::{ColumnType, ShardIndex};
     use iox_catalog::mem::MemCatalog;
+    use ioxd_common::http::test_utils::{assert_api_versions, TestServer};
+    use router::{
+        dml_handlers::mock::MockDmlHandler, namespace_resolver::mock::MockNamespaceResolver,
+    };
+    use write_buffer::mock::MockBufferForWritingThatAlwaysErrors;
 
     use super::*;
 
@@ -440,4 +469,47 @@ mod tests {
 
         assert!(got.tables.get("name").is_some());
     }
+
+    #[tokio::test]
+    async fn test_api_versions() {
+        let metrics = Arc::new(Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Arc::clone(&metrics)));
+        let topic = catalog
+            .repositories()
+            .await
+            .topics()
+            .create_or_get("foo")
+            .await
+            .unwrap();
+
+        let shard = Shard::new(
+            ShardIndex::new(0),
+            Arc::new(MockBufferForWritingThatAlwaysErrors),
+            &metrics,
+        );
+        let sharder = JumpHash::new([Arc::new(shard)]);
+        let shard_service = ShardService::new(sharder, topic, Arc::clone(&catalog))
+            .await
+            .unwrap();
+
+        let http = HttpDelegate::new(
+            1024,
+            1,
+            MockNamespaceResolver::default(),
+            Arc::new(MockDmlHandler::<HashMap<String, MutableBatch>>::default()),
+            &metrics,
+        );
+        let grpc = GrpcDelegate::new(
+            catalog,
+            Arc::new(object_store::memory::InMemory::new()),
+            shard_service,
+        );
+        let server = RouterServer::new(http, grpc, metrics, None);
+        let server_type = Arc::new(RouterServerType::new(
+            server,
+            &CommonServerState::for_testing(),
+        ));
+
+        assert_api_versions(TestServer::new(server_type), r#"{"versions":["v2"]}"#).await;
+    }
 }
@@ -12,7 +12,7 @@ use snafu::Snafu;
 use tokio_util::sync::CancellationToken;
 use trace::TraceCollector;
 
-use ioxd_common::server_type::{RpcError, ServerType};
+use ioxd_common::server_type::{ApiVersion, RpcError, ServerType};
 
 #[derive(Debug, Snafu)]
 pub enum ApplicationError {
@@ -43,6 +43,7 @@ pub struct TestServerType {
     trace_collector: Option<Arc<dyn TraceCollector>>,
     shutdown: CancellationToken,
     test_action: TestAction,
+    api_versions: Vec<ApiVersion>,
 }
 
 impl TestServerType {
@@ -56,6 +57,15 @@ impl TestServerType {
             trace_collector,
             shutdown: CancellationToken::new(),
             test_action,
+            api_versions: vec![],
+        }
+    }
+
+    /// Report the specified HTTP API versions from [`ServerType::api_versions`].
+    pub fn with_api_versions(self, api_versions: Vec<ApiVersion>) -> Self {
+        Self {
+            api_versions,
+            ..self
         }
     }
 }
@@ -70,6 +80,10 @@ impl ServerType for TestServerType {
         self.trace_collector.clone()
     }
 
+    fn api_versions(&self) -> &[ApiVersion] {
+        &self.api_versions
+    }
+
     async fn route_http_request(
         &self,
         req: Request<Body>,
@@ -108,3 +122,20 @@ impl ServerType for TestServerType {
         self.shutdown.cancel();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_versions() {
+        let server_type = TestServerType::new(Default::default(), None, TestAction::None);
+        assert!(server_type.api_versions().is_empty());
+
+        let server_type = server_type.with_api_versions(vec![ApiVersion::V1, ApiVersion::V2]);
+        assert_eq!(
+            server_type.api_versions(),
+            &[ApiVersion::V1, ApiVersion::V2]
+        );
+    }
+}
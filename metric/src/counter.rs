@@ -32,6 +32,11 @@ impl MetricObserver for U64Counter {
     fn observe(&self) -> Observation {
         Observation::U64Counter(self.fetch())
     }
+
+    fn reset(&self) -> bool {
+        self.state.store(0, Ordering::Relaxed);
+        true
+    }
 }
 
 #[cfg(test)]
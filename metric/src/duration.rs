@@ -45,6 +45,10 @@ impl MetricObserver for DurationCounter {
     fn observe(&self) -> Observation {
         Observation::DurationCounter(self.fetch())
     }
+
+    fn reset(&self) -> bool {
+        self.inner.reset()
+    }
 }
 
 /// An observation of a single `std::time::Duration`
@@ -264,6 +264,18 @@ impl Registry {
             instrument.report(reporter)
         }
     }
+
+    /// Reset every resettable metric in this registry to its initial value,
+    /// returning the number of observers that were reset
+    ///
+    /// This is intended for tests that want to observe metrics in isolation
+    pub fn reset(&self) -> usize {
+        let instruments = self.instruments.lock();
+        instruments
+            .values()
+            .map(|instrument| instrument.reset())
+            .sum()
+    }
 }
 
 /// `Instrument` is a type that knows how to write its observations to a `Reporter`
@@ -276,6 +288,14 @@ pub trait Instrument: std::fmt::Debug + Send + Sync {
     /// - call finish_metric once complete
     fn report(&self, reporter: &mut dyn Reporter);
 
+    /// Reset the state of this instrument, returning the number of observers
+    /// that were reset
+    ///
+    /// The default implementation does not support resetting and returns 0
+    fn reset(&self) -> usize {
+        0
+    }
+
     /// Returns the type as [`Any`](std::any::Any) so that it can be downcast to
     /// it underlying type
     fn as_any(&self) -> &dyn Any;
@@ -550,6 +570,51 @@ mod tests {
         assert_eq!(observation, Observation::U64Counter(23));
     }
 
+    #[test]
+    fn test_registry_reset() {
+        let registry = Registry::new();
+        let counter: Metric<U64Counter> = registry.register_metric("foo", "my magic description");
+        let duration: Metric<DurationCounter> =
+            registry.register_metric("bar", "my magic description");
+        let gauge: Metric<U64Gauge> = registry.register_metric("baz", "my magic description");
+
+        let recorder = counter.recorder(&[("tag1", "foo")]);
+        recorder.inc(23);
+        counter.recorder(&[("tag1", "bar")]).inc(53);
+        duration
+            .recorder(&[("tag1", "foo")])
+            .inc(std::time::Duration::from_secs(1));
+        gauge.recorder(&[("tag1", "value")]).set(49);
+
+        assert_eq!(registry.reset(), 3);
+
+        let attributes = Attributes::from(&[("tag1", "foo")]);
+        assert_eq!(
+            counter.get_observer(&attributes).unwrap().observe(),
+            Observation::U64Counter(0)
+        );
+        assert_eq!(
+            duration.get_observer(&attributes).unwrap().observe(),
+            Observation::DurationCounter(std::time::Duration::ZERO)
+        );
+
+        // Gauges are not reset
+        assert_eq!(
+            gauge
+                .get_observer(&Attributes::from(&[("tag1", "value")]))
+                .unwrap()
+                .observe(),
+            Observation::U64Gauge(49)
+        );
+
+        // Existing recorders continue to record to the reset observer
+        recorder.inc(2);
+        assert_eq!(
+            counter.get_observer(&attributes).unwrap().observe(),
+            Observation::U64Counter(2)
+        );
+    }
+
     #[test]
     #[should_panic(expected = "instrument foo registered with two different types")]
     fn test_type_mismatch() {
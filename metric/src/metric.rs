@@ -103,6 +103,11 @@ impl<T: MetricObserver> Instrument for Metric<T> {
         reporter.finish_metric();
     }
 
+    fn reset(&self) -> usize {
+        let values = self.shared.values.lock();
+        values.values().filter(|value| value.reset()).count()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -128,6 +133,12 @@ pub trait MetricObserver: MakeMetricObserver + std::fmt::Debug + Send + 'static
 
     /// Return the current value for this
     fn observe(&self) -> Observation;
+
+    /// Reset this `MetricObserver` to its initial value, returning false if
+    /// it does not support being reset
+    fn reset(&self) -> bool {
+        false
+    }
 }
 
 /// All `MetricObserver` must also implement `MakeMetricObserver` which defines
@@ -9,6 +9,7 @@
 )]
 
 use metric::{Attributes, MetricKind, Observation};
+use std::fmt::{Display, Formatter};
 use std::io::Write;
 
 use observability_deps::tracing::error;
@@ -34,6 +35,9 @@ pub struct PrometheusTextEncoder<'a, W: Write> {
     /// metric family together with a flag indicating that it was used
     metric: Option<(MetricFamily, bool)>,
 
+    /// labels applied to every observation, in addition to its attributes
+    global_labels: Vec<(String, String)>,
+
     encoder: TextEncoder,
     writer: &'a mut W,
 }
@@ -42,10 +46,78 @@ impl<'a, W: Write> PrometheusTextEncoder<'a, W> {
     pub fn new(writer: &'a mut W) -> Self {
         Self {
             metric: None,
+            global_labels: vec![],
             encoder: TextEncoder::new(),
             writer,
         }
     }
+
+    /// Applies `labels` to every exported observation, such as to identify the
+    /// environment or region of a deployment.
+    ///
+    /// Where an observation has an attribute of the same name as a label, the
+    /// attribute takes precedence. Returns an error if any label name is not a
+    /// valid prometheus label name, or is reserved.
+    pub fn with_global_labels(
+        mut self,
+        labels: Vec<(String, String)>,
+    ) -> Result<Self, InvalidLabelError> {
+        for (name, _) in &labels {
+            validate_label_name(name)?;
+        }
+
+        self.global_labels = labels;
+        Ok(self)
+    }
+}
+
+/// Label names used by the exposition format itself.
+const RESERVED_LABEL_NAMES: &[&str] = &["le", "quantile"];
+
+/// An error returned when a global label name cannot be exported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidLabelError {
+    /// The label name is empty.
+    Empty,
+
+    /// The label name contains characters other than ASCII letters, digits and
+    /// underscores, or begins with a digit.
+    InvalidName(String),
+
+    /// The label name is reserved by prometheus.
+    Reserved(String),
+}
+
+impl Display for InvalidLabelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "label name must not be empty"),
+            Self::InvalidName(name) => write!(f, "invalid label name: {:?}", name),
+            Self::Reserved(name) => write!(f, "label name is reserved: {:?}", name),
+        }
+    }
+}
+
+impl std::error::Error for InvalidLabelError {}
+
+/// Validates `name` against the prometheus data model - <https://prometheus.io/docs/concepts/data_model/>
+fn validate_label_name(name: &str) -> Result<(), InvalidLabelError> {
+    let mut chars = name.chars();
+    match chars.next() {
+        None => return Err(InvalidLabelError::Empty),
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        Some(_) => return Err(InvalidLabelError::InvalidName(name.to_string())),
+    }
+
+    if !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(InvalidLabelError::InvalidName(name.to_string()));
+    }
+
+    if name.starts_with("__") || RESERVED_LABEL_NAMES.contains(&name) {
+        return Err(InvalidLabelError::Reserved(name.to_string()));
+    }
+
+    Ok(())
 }
 
 impl<'a, W: Write> metric::Reporter for PrometheusTextEncoder<'a, W> {
@@ -86,9 +158,17 @@ impl<'a, W: Write> metric::Reporter for PrometheusTextEncoder<'a, W> {
 
         let mut metric = Metric::default();
 
+        let global_labels = self
+            .global_labels
+            .iter()
+            .filter(|(name, _)| !attributes.iter().any(|(k, _)| *k == name.as_str()))
+            .map(|(name, value)| (name.as_str(), value.as_str()));
+
         metric.set_label(
             attributes
                 .iter()
+                .map(|(name, value)| (*name, &**value))
+                .chain(global_labels)
                 .map(|(name, value)| {
                     let mut pair = LabelPair::default();
                     pair.set_name(name.to_string());
@@ -290,4 +370,73 @@ foo_total{tag1="value",tag2="value2"} 7
         // no errors
         assert_not_contains!(tracing_capture.to_string(), "error");
     }
+
+    #[test]
+    fn test_encode_global_labels() {
+        let registry = Registry::new();
+
+        let counter: Metric<U64Counter> = registry.register_metric("foo", "a counter metric");
+        counter.recorder(&[("tag1", "value")]).inc(5);
+        counter.recorder(&[("env", "test")]).inc(7);
+
+        let histogram: Metric<U64Histogram> =
+            registry.register_metric_with_options("bar", "a histogram metric", || {
+                U64HistogramOptions::new([5])
+            });
+        histogram.recorder(&[]).record(3);
+
+        let labels = vec![
+            ("env".to_string(), "prod".to_string()),
+            ("region".to_string(), "eu-west".to_string()),
+        ];
+
+        let mut buffer = Vec::new();
+        let mut encoder = PrometheusTextEncoder::new(&mut buffer)
+            .with_global_labels(labels)
+            .unwrap();
+        registry.report(&mut encoder);
+
+        let buffer = String::from_utf8(buffer).unwrap();
+
+        let expected = r#"
+# HELP bar a histogram metric
+# TYPE bar histogram
+bar_bucket{env="prod",region="eu-west",le="5"} 1
+bar_bucket{env="prod",region="eu-west",le="+Inf"} 1
+bar_sum{env="prod",region="eu-west"} 3
+bar_count{env="prod",region="eu-west"} 1
+# HELP foo_total a counter metric
+# TYPE foo_total counter
+foo_total{env="test",region="eu-west"} 7
+foo_total{tag1="value",env="prod",region="eu-west"} 5
+"#
+        .trim_start();
+
+        assert_eq!(&buffer, expected, "{}", buffer);
+    }
+
+    #[test]
+    fn test_global_labels_invalid() {
+        let invalid = |name: &str| {
+            let mut buffer = Vec::new();
+            PrometheusTextEncoder::new(&mut buffer)
+                .with_global_labels(vec![(name.to_string(), "value".to_string())])
+                .unwrap_err()
+        };
+
+        assert_eq!(invalid(""), InvalidLabelError::Empty);
+        assert_eq!(
+            invalid("1region"),
+            InvalidLabelError::InvalidName("1region".to_string())
+        );
+        assert_eq!(
+            invalid("region-name"),
+            InvalidLabelError::InvalidName("region-name".to_string())
+        );
+        assert_eq!(invalid("le"), InvalidLabelError::Reserved("le".to_string()));
+        assert_eq!(
+            invalid("__name__"),
+            InvalidLabelError::Reserved("__name__".to_string())
+        );
+    }
 }
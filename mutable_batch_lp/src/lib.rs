@@ -35,8 +35,8 @@ pub enum Error {
     #[snafu(display("empty write payload"))]
     EmptyPayload,
 
-    #[snafu(display("timestamp overflows i64"))]
-    TimestampOverflow,
+    #[snafu(display("timestamp overflows i64 on line {}", line))]
+    TimestampOverflow { line: usize },
 }
 
 /// Result type for line protocol conversion
@@ -106,7 +106,7 @@ impl LinesConverter {
             if let Some(t) = line.timestamp.as_mut() {
                 *t = t
                     .checked_mul(self.timestamp_base)
-                    .ok_or(Error::TimestampOverflow)?;
+                    .ok_or(Error::TimestampOverflow { line: line_idx + 1 })?;
             }
 
             self.stats.num_lines += 1;
@@ -1,3 +1,5 @@
+use std::task::{Context, Poll};
+
 use async_trait::async_trait;
 use data_types::{DatabaseName, DeletePredicate, NamespaceId};
 use trace::ctx::SpanContext;
@@ -55,6 +57,20 @@ where
     type WriteError = DmlError;
     type DeleteError = DmlError;
 
+    /// The chain is ready when both handlers are ready.
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), DmlError>> {
+        match self.first.poll_ready(cx) {
+            Poll::Ready(Ok(())) => self.second.poll_ready(cx),
+            v => v,
+        }
+    }
+
+    /// Neither handler is called.
+    fn record_skipped(&self, span_ctx: Option<&SpanContext>) {
+        self.first.record_skipped(span_ctx);
+        self.second.record_skipped(span_ctx);
+    }
+
     /// Write `batches` to `namespace`.
     async fn write(
         &self,
@@ -63,11 +79,17 @@ where
         input: Self::WriteInput,
         span_ctx: Option<SpanContext>,
     ) -> Result<Self::WriteOutput, Self::WriteError> {
-        let output = self
+        let output = match self
             .first
             .write(namespace, namespace_id, input, span_ctx.clone())
             .await
-            .map_err(Into::into)?;
+        {
+            Ok(v) => v,
+            Err(e) => {
+                self.second.record_skipped(span_ctx.as_ref());
+                return Err(e.into());
+            }
+        };
 
         self.second
             .write(namespace, namespace_id, output, span_ctx)
@@ -83,10 +105,14 @@ where
         predicate: &DeletePredicate,
         span_ctx: Option<SpanContext>,
     ) -> Result<(), Self::DeleteError> {
-        self.first
+        if let Err(e) = self
+            .first
             .delete(namespace, table_name, predicate, span_ctx.clone())
             .await
-            .map_err(Into::into)?;
+        {
+            self.second.record_skipped(span_ctx.as_ref());
+            return Err(e.into());
+        }
 
         self.second
             .delete(namespace, table_name, predicate, span_ctx)
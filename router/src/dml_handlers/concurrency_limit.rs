@@ -0,0 +1,237 @@
+use std::task::{Context, Poll, Waker};
+
+use async_trait::async_trait;
+use data_types::{DatabaseName, DeletePredicate, NamespaceId};
+use parking_lot::Mutex;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use trace::ctx::SpanContext;
+
+use super::{DmlError, DmlHandler};
+
+/// A [`DmlHandler`] decorator that limits the number of DML requests
+/// concurrently executing in the inner handler `T`.
+///
+/// Requests in excess of the limit wait for an executing request to complete.
+/// Callers wishing to apply backpressure instead of queuing can consult
+/// [`DmlHandler::poll_ready()`], which returns [`Poll::Pending`] while the
+/// limit is reached and wakes the caller once a request completes.
+#[derive(Debug)]
+pub struct ConcurrencyLimit<T> {
+    inner: T,
+    sem: Semaphore,
+
+    /// Tasks waiting in [`DmlHandler::poll_ready()`] for a permit to be
+    /// released.
+    waiters: Mutex<Vec<Waker>>,
+}
+
+impl<T> ConcurrencyLimit<T> {
+    /// Initialise a new [`ConcurrencyLimit`] allowing at most `max_requests`
+    /// to concurrently execute in `inner`.
+    pub fn new(inner: T, max_requests: usize) -> Self {
+        Self {
+            inner,
+            sem: Semaphore::new(max_requests),
+            waiters: Default::default(),
+        }
+    }
+
+    async fn acquire(&self) -> Permit<'_> {
+        let permit = self
+            .sem
+            .acquire()
+            .await
+            .expect("concurrency limit semaphore closed");
+
+        Permit {
+            permit: Some(permit),
+            waiters: &self.waiters,
+        }
+    }
+}
+
+/// A permit to execute a request, waking any tasks waiting for capacity when
+/// dropped.
+struct Permit<'a> {
+    permit: Option<SemaphorePermit<'a>>,
+    waiters: &'a Mutex<Vec<Waker>>,
+}
+
+impl<'a> Drop for Permit<'a> {
+    fn drop(&mut self) {
+        // Release the permit before waking the waiters, so they observe the
+        // freed capacity.
+        drop(self.permit.take());
+        for waker in std::mem::take(&mut *self.waiters.lock()) {
+            waker.wake();
+        }
+    }
+}
+
+#[async_trait]
+impl<T> DmlHandler for ConcurrencyLimit<T>
+where
+    T: DmlHandler,
+{
+    type WriteInput = T::WriteInput;
+    type WriteOutput = T::WriteOutput;
+    type WriteError = T::WriteError;
+    type DeleteError = T::DeleteError;
+
+    /// Ready when fewer than the maximum number of requests are executing and
+    /// the inner handler is ready.
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), DmlError>> {
+        if self.sem.available_permits() == 0 {
+            {
+                let mut waiters = self.waiters.lock();
+                if !waiters.iter().any(|w| w.will_wake(cx.waker())) {
+                    waiters.push(cx.waker().clone());
+                }
+            }
+
+            // A permit may have been released before the waker was
+            // registered, in which case the wakeup would be missed.
+            if self.sem.available_permits() == 0 {
+                return Poll::Pending;
+            }
+        }
+
+        self.inner.poll_ready(cx)
+    }
+
+    /// Propagate the skipped request to the inner handler.
+    fn record_skipped(&self, span_ctx: Option<&SpanContext>) {
+        self.inner.record_skipped(span_ctx)
+    }
+
+    /// Wait for capacity and pass the write through to the inner handler.
+    async fn write(
+        &self,
+        namespace: &DatabaseName<'static>,
+        namespace_id: NamespaceId,
+        input: Self::WriteInput,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<Self::WriteOutput, Self::WriteError> {
+        let _permit = self.acquire().await;
+        self.inner
+            .write(namespace, namespace_id, input, span_ctx)
+            .await
+    }
+
+    /// Wait for capacity and pass the delete through to the inner handler.
+    async fn delete(
+        &self,
+        namespace: &DatabaseName<'static>,
+        table_name: &str,
+        predicate: &DeletePredicate,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<(), Self::DeleteError> {
+        let _permit = self.acquire().await;
+        self.inner
+            .delete(namespace, table_name, predicate, span_ctx)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use futures::{future::poll_fn, poll};
+    use test_helpers::timeout::FutureTimeout;
+
+    use super::*;
+
+    /// A [`DmlHandler`] that blocks writes until `gate` has a permit.
+    #[derive(Debug)]
+    struct GatedHandler {
+        gate: Semaphore,
+    }
+
+    #[async_trait]
+    impl DmlHandler for GatedHandler {
+        type WriteInput = ();
+        type WriteOutput = ();
+        type WriteError = DmlError;
+        type DeleteError = DmlError;
+
+        async fn write(
+            &self,
+            _namespace: &DatabaseName<'static>,
+            _namespace_id: NamespaceId,
+            _input: Self::WriteInput,
+            _span_ctx: Option<SpanContext>,
+        ) -> Result<Self::WriteOutput, Self::WriteError> {
+            self.gate.acquire().await.unwrap().forget();
+            Ok(())
+        }
+
+        async fn delete(
+            &self,
+            _namespace: &DatabaseName<'static>,
+            _table_name: &str,
+            _predicate: &DeletePredicate,
+            _span_ctx: Option<SpanContext>,
+        ) -> Result<(), Self::DeleteError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_saturated_pending_until_capacity_frees() {
+        let handler = Arc::new(ConcurrencyLimit::new(
+            GatedHandler {
+                gate: Semaphore::new(0),
+            },
+            1,
+        ));
+
+        // Ready while under the limit
+        assert!(matches!(
+            poll!(poll_fn(|cx| handler.poll_ready(cx))),
+            Poll::Ready(Ok(()))
+        ));
+
+        // Start a write that blocks in the inner handler, saturating the limit
+        let write = tokio::spawn({
+            let handler = Arc::clone(&handler);
+            async move {
+                let ns = "bananas".try_into().unwrap();
+                handler.write(&ns, NamespaceId::new(1), (), None).await
+            }
+        });
+        async {
+            while handler.sem.available_permits() != 0 {
+                tokio::task::yield_now().await;
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(1))
+        .await;
+
+        // Readiness is pending while saturated
+        let ready = tokio::spawn({
+            let handler = Arc::clone(&handler);
+            poll_fn(move |cx| handler.poll_ready(cx))
+        });
+        assert!(poll!(poll_fn(|cx| handler.poll_ready(cx))).is_pending());
+        tokio::task::yield_now().await;
+        assert!(!ready.is_finished());
+
+        // Completing the write frees capacity and wakes the waiting task
+        handler.inner.gate.add_permits(1);
+        write
+            .with_timeout_panic(Duration::from_secs(1))
+            .await
+            .expect("write task panicked")
+            .expect("write should succeed");
+        ready
+            .with_timeout_panic(Duration::from_secs(1))
+            .await
+            .expect("readiness task panicked")
+            .expect("handler should be ready");
+        assert!(matches!(
+            poll!(poll_fn(|cx| handler.poll_ready(cx))),
+            Poll::Ready(Ok(()))
+        ));
+    }
+}
@@ -1,11 +1,15 @@
-use std::{fmt::Debug, marker::PhantomData};
+use std::{
+    fmt::Debug,
+    marker::PhantomData,
+    task::{Context, Poll},
+};
 
 use async_trait::async_trait;
 use data_types::{DatabaseName, DeletePredicate, NamespaceId};
 use futures::{stream::FuturesUnordered, TryStreamExt};
 use trace::ctx::SpanContext;
 
-use super::DmlHandler;
+use super::{DmlError, DmlHandler};
 
 /// A [`FanOutAdaptor`] takes an iterator of DML write operation inputs and
 /// executes them concurrently against the inner handler, returning once all
@@ -44,6 +48,16 @@ where
     type WriteError = T::WriteError;
     type DeleteError = T::DeleteError;
 
+    /// Propagate the readiness of the inner handler.
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), DmlError>> {
+        self.inner.poll_ready(cx)
+    }
+
+    /// Propagate the skipped request to the inner handler.
+    fn record_skipped(&self, span_ctx: Option<&SpanContext>) {
+        self.inner.record_skipped(span_ctx)
+    }
+
     /// Concurrently execute the write inputs in `input` against the inner
     /// handler, returning early and aborting in-flight writes if an error
     /// occurs.
@@ -1,3 +1,5 @@
+use std::task::{Context, Poll};
+
 use async_trait::async_trait;
 use data_types::{DatabaseName, DeletePredicate, NamespaceId};
 use iox_time::{SystemProvider, TimeProvider};
@@ -7,11 +9,18 @@ use trace::{
     span::{SpanExt, SpanRecorder},
 };
 
-use super::DmlHandler;
+use super::{DmlError, DmlErrorKind, DmlHandler};
 
 /// An instrumentation decorator recording call latencies for [`DmlHandler`] implementations.
 ///
 /// Metrics are broken down by operation (write/delete) and result (success/error).
+///
+/// Each call is also recorded as a tracing span named after the handler, with
+/// an event describing the outcome of the call - `ok`, `error: <kind>`, or
+/// `skip` for a handler not called because an earlier stage of the pipeline
+/// failed. Spans of instrumented handlers called by the inner handler are
+/// recorded as children of this span, so that a trace shows the outcome of
+/// each stage of the handler pipeline.
 #[derive(Debug)]
 pub struct InstrumentationDecorator<T, P = SystemProvider> {
     name: &'static str,
@@ -58,12 +67,29 @@ impl<T> InstrumentationDecorator<T> {
 impl<T> DmlHandler for InstrumentationDecorator<T>
 where
     T: DmlHandler,
+    T::WriteError: DmlErrorKind,
+    T::DeleteError: DmlErrorKind,
 {
     type WriteInput = T::WriteInput;
     type WriteError = T::WriteError;
     type DeleteError = T::DeleteError;
     type WriteOutput = T::WriteOutput;
 
+    /// Propagate the readiness of the inner handler.
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), DmlError>> {
+        self.inner.poll_ready(cx)
+    }
+
+    /// Record a span for this handler with a `skip` event, and propagate the
+    /// skipped request to the inner handler.
+    fn record_skipped(&self, span_ctx: Option<&SpanContext>) {
+        let mut span_recorder = SpanRecorder::new(span_ctx.child_span(self.name));
+        span_recorder.event("skip");
+
+        let span_ctx = span_recorder.span().map(|span| span.ctx.clone());
+        self.inner.record_skipped(span_ctx.as_ref());
+    }
+
     /// Call the inner `write` method and record the call latency.
     async fn write(
         &self,
@@ -74,27 +100,25 @@ where
     ) -> Result<Self::WriteOutput, Self::WriteError> {
         let t = self.time_provider.now();
 
-        // Create a tracing span for this handler.
-        let mut span_recorder =
-            SpanRecorder::new(span_ctx.clone().map(|parent| parent.child(self.name)));
+        // Create a tracing span for this handler, and pass it to the inner
+        // handler so that any instrumented handlers it calls are recorded as
+        // children of it.
+        let mut span_recorder = SpanRecorder::new(span_ctx.child_span(self.name));
+        let span_ctx = span_recorder.span().map(|span| span.ctx.clone());
 
         let res = self
             .inner
             .write(namespace, namespace_id, input, span_ctx)
             .await;
 
+        record_outcome(&mut span_recorder, &res);
+
         // Avoid exploding if time goes backwards - simply drop the measurement
         // if it happens.
         if let Some(delta) = self.time_provider.now().checked_duration_since(t) {
             match &res {
-                Ok(_) => {
-                    span_recorder.ok("success");
-                    self.write_success.record(delta)
-                }
-                Err(e) => {
-                    span_recorder.error(e.to_string());
-                    self.write_error.record(delta)
-                }
+                Ok(_) => self.write_success.record(delta),
+                Err(_) => self.write_error.record(delta),
             };
         }
 
@@ -111,26 +135,25 @@ where
     ) -> Result<(), Self::DeleteError> {
         let t = self.time_provider.now();
 
-        // Create a tracing span for this handler.
+        // Create a tracing span for this handler, and pass it to the inner
+        // handler so that any instrumented handlers it calls are recorded as
+        // children of it.
         let mut span_recorder = SpanRecorder::new(span_ctx.child_span(self.name));
+        let span_ctx = span_recorder.span().map(|span| span.ctx.clone());
 
         let res = self
             .inner
             .delete(namespace, table_name, predicate, span_ctx)
             .await;
 
+        record_outcome(&mut span_recorder, &res);
+
         // Avoid exploding if time goes backwards - simply drop the measurement
         // if it happens.
         if let Some(delta) = self.time_provider.now().checked_duration_since(t) {
             match &res {
-                Ok(_) => {
-                    span_recorder.ok("success");
-                    self.delete_success.record(delta)
-                }
-                Err(e) => {
-                    span_recorder.error(e.to_string());
-                    self.delete_error.record(delta)
-                }
+                Ok(_) => self.delete_success.record(delta),
+                Err(_) => self.delete_error.record(delta),
             };
         }
 
@@ -138,6 +161,21 @@ where
     }
 }
 
+/// Record the outcome of a handler call as an event on its span - either
+/// `ok`, or `error: <kind>` followed by an event containing the error message.
+fn record_outcome<T, E>(span_recorder: &mut SpanRecorder, res: &Result<T, E>)
+where
+    E: DmlErrorKind + std::fmt::Display,
+{
+    match res {
+        Ok(_) => span_recorder.ok("ok"),
+        Err(e) => {
+            span_recorder.error(format!("error: {}", e.kind()));
+            span_recorder.event(e.to_string());
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -149,7 +187,7 @@ mod tests {
     use write_summary::WriteSummary;
 
     use super::*;
-    use crate::dml_handlers::{mock::MockDmlHandler, DmlError};
+    use crate::dml_handlers::{mock::MockDmlHandler, DmlHandlerChainExt, SchemaError};
 
     const HANDLER_NAME: &str = "bananas";
 
@@ -291,4 +329,74 @@ mod tests {
         assert_metric_hit(&*metrics, "dml_handler_delete_duration", "error");
         assert_trace(traces, SpanStatus::Err);
     }
+
+    #[tokio::test]
+    async fn test_write_chain_span_events() {
+        let ns = "platanos".try_into().unwrap();
+        let metrics = Arc::new(metric::Registry::default());
+        let traces: Arc<dyn TraceCollector> = Arc::new(RingBufferTraceCollector::new(5));
+        let span = SpanContext::new(Arc::clone(&traces));
+
+        let schema_validator = InstrumentationDecorator::new(
+            "schema_validator",
+            &*metrics,
+            MockDmlHandler::<()>::default().with_write_return([Err(DmlError::Schema(
+                SchemaError::ServiceLimit("too many columns".into()),
+            ))]),
+        );
+        let partitioner = Arc::new(MockDmlHandler::<WriteSummary>::default());
+        let handler = InstrumentationDecorator::new(
+            "request",
+            &*metrics,
+            schema_validator.and_then(InstrumentationDecorator::new(
+                "partitioner",
+                &*metrics,
+                Arc::clone(&partitioner),
+            )),
+        );
+
+        let err = handler
+            .write(&ns, NamespaceId::new(42), (), Some(span))
+            .await
+            .expect_err("schema validation configured to fail");
+        assert_matches!(err, DmlError::Schema(SchemaError::ServiceLimit(_)));
+        assert!(partitioner.calls().is_empty());
+
+        let traces = traces
+            .as_any()
+            .downcast_ref::<RingBufferTraceCollector>()
+            .expect("unexpected collector impl");
+        let spans = traces.spans();
+
+        // The failing stage completes first, followed by the skipped
+        // partitioner stage, both recorded as children of the request span.
+        let got = spans
+            .iter()
+            .map(|s| {
+                (
+                    s.name.as_ref(),
+                    s.status,
+                    s.events.iter().map(|e| e.msg.as_ref()).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            got,
+            [
+                (
+                    "schema_validator",
+                    SpanStatus::Err,
+                    vec!["error: schema", "service limit reached: too many columns"]
+                ),
+                ("partitioner", SpanStatus::Unknown, vec!["skip"]),
+                (
+                    "request",
+                    SpanStatus::Err,
+                    vec!["error: schema", "service limit reached: too many columns"]
+                ),
+            ]
+        );
+        assert_eq!(spans[0].ctx.parent_span_id, Some(spans[2].ctx.span_id));
+        assert_eq!(spans[1].ctx.parent_span_id, Some(spans[2].ctx.span_id));
+    }
 }
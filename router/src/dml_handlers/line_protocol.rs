@@ -0,0 +1,250 @@
+use std::task::{Context, Poll};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use data_types::{DatabaseName, DeletePredicate, NamespaceId};
+use hashbrown::HashMap;
+use iox_time::TimeProvider;
+use mutable_batch::MutableBatch;
+use mutable_batch_lp::LinesConverter;
+use observability_deps::tracing::*;
+use trace::ctx::SpanContext;
+
+use super::{DmlError, DmlHandler};
+
+/// A [`DmlHandler`] decorator that parses the raw line protocol of a write into
+/// per-table [`MutableBatch`] instances, passing them to the inner handler `T`.
+/// Deletes pass through unmodified.
+///
+/// Points without a timestamp are assigned the time at which the write is
+/// parsed, as reported by the [`TimeProvider`]. Timestamps are nanoseconds,
+/// unless configured with [`LineProtocolDmlHandler::with_timestamp_base()`]. A
+/// payload containing no lines is passed to `T` as an empty set of batches.
+///
+/// A payload that cannot be parsed is rejected with
+/// [`DmlError::InvalidLineProtocol`], identifying the first invalid line.
+#[derive(Debug)]
+pub struct LineProtocolDmlHandler<T, P> {
+    inner: T,
+    time_provider: P,
+    timestamp_base: i64,
+}
+
+impl<T, P> LineProtocolDmlHandler<T, P> {
+    /// Initialise a new [`LineProtocolDmlHandler`] decorating `inner`, using
+    /// `time_provider` to timestamp points without a timestamp.
+    pub fn new(inner: T, time_provider: P) -> Self {
+        Self {
+            inner,
+            time_provider,
+            timestamp_base: 1,
+        }
+    }
+
+    /// Multiply the timestamps of the line protocol by `timestamp_base` to
+    /// convert them to nanoseconds, such as `1_000_000_000` for seconds.
+    pub fn with_timestamp_base(mut self, timestamp_base: i64) -> Self {
+        self.timestamp_base = timestamp_base;
+        self
+    }
+}
+
+#[async_trait]
+impl<T, P> DmlHandler for LineProtocolDmlHandler<T, P>
+where
+    T: DmlHandler<WriteInput = HashMap<String, MutableBatch>>,
+    P: TimeProvider,
+{
+    type WriteError = DmlError;
+    type DeleteError = T::DeleteError;
+
+    type WriteInput = Bytes;
+    type WriteOutput = T::WriteOutput;
+
+    /// Propagate the readiness of the inner handler.
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), DmlError>> {
+        self.inner.poll_ready(cx)
+    }
+
+    /// Propagate the skipped request to the inner handler.
+    fn record_skipped(&self, span_ctx: Option<&SpanContext>) {
+        self.inner.record_skipped(span_ctx)
+    }
+
+    /// Parse the line protocol in `body` and pass the resulting batches to the
+    /// inner handler.
+    async fn write(
+        &self,
+        namespace: &DatabaseName<'static>,
+        namespace_id: NamespaceId,
+        body: Self::WriteInput,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<Self::WriteOutput, Self::WriteError> {
+        let batches = parse_line_protocol(
+            &body,
+            self.time_provider.now().timestamp_nanos(),
+            self.timestamp_base,
+        )
+        .map_err(|e| {
+            self.inner.record_skipped(span_ctx.as_ref());
+            e
+        })?;
+
+        debug!(%namespace, num_tables=batches.len(), "parsed line protocol");
+
+        self.inner
+            .write(namespace, namespace_id, batches, span_ctx)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Pass the delete request through unmodified to the inner handler.
+    async fn delete(
+        &self,
+        namespace: &DatabaseName<'static>,
+        table_name: &str,
+        predicate: &DeletePredicate,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<(), Self::DeleteError> {
+        self.inner
+            .delete(namespace, table_name, predicate, span_ctx)
+            .await
+    }
+}
+
+/// Parse `body` into per-table batches, assigning `default_time` to any points
+/// without a timestamp, and multiplying all others by `timestamp_base`.
+fn parse_line_protocol(
+    body: &[u8],
+    default_time: i64,
+    timestamp_base: i64,
+) -> Result<HashMap<String, MutableBatch>, DmlError> {
+    let body = std::str::from_utf8(body).map_err(|e| DmlError::InvalidLineProtocol {
+        line: body[..e.valid_up_to()]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count()
+            + 1,
+        reason: e.to_string(),
+    })?;
+
+    let mut converter = LinesConverter::new(default_time);
+    converter.set_timestamp_base(timestamp_base);
+    match converter.write_lp(body).and_then(|_| converter.finish()) {
+        Ok((batches, _stats)) => Ok(batches),
+        Err(mutable_batch_lp::Error::EmptyPayload) => Ok(HashMap::default()),
+        Err(mutable_batch_lp::Error::LineProtocol { source, line }) => {
+            Err(DmlError::InvalidLineProtocol {
+                line,
+                reason: source.to_string(),
+            })
+        }
+        Err(mutable_batch_lp::Error::Write { source, line }) => {
+            Err(DmlError::InvalidLineProtocol {
+                line,
+                reason: source.to_string(),
+            })
+        }
+        Err(mutable_batch_lp::Error::TimestampOverflow { line }) => {
+            Err(DmlError::InvalidLineProtocol {
+                line,
+                reason: "timestamp overflows i64".to_owned(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use assert_matches::assert_matches;
+    use hyper::StatusCode;
+    use iox_time::{MockProvider, Time};
+    use write_summary::WriteSummary;
+
+    use super::*;
+    use crate::dml_handlers::mock::{MockDmlHandler, MockDmlHandlerCall};
+
+    const DEFAULT_TIME: i64 = 42;
+
+    fn handler(
+        inner: Arc<MockDmlHandler<HashMap<String, MutableBatch>>>,
+    ) -> LineProtocolDmlHandler<Arc<MockDmlHandler<HashMap<String, MutableBatch>>>, Arc<MockProvider>>
+    {
+        LineProtocolDmlHandler::new(
+            inner,
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(DEFAULT_TIME))),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_write_multi_line() {
+        let ns = "bananas".try_into().unwrap();
+        let inner =
+            Arc::new(MockDmlHandler::default().with_write_return([Ok(WriteSummary::default())]));
+        let handler = handler(Arc::clone(&inner));
+
+        let body = "cpu,host=a usage=1 1\ncpu,host=b usage=2 2\n\nmem free=3";
+        handler
+            .write(&ns, NamespaceId::new(1), Bytes::from(body), None)
+            .await
+            .expect("write should succeed");
+
+        let calls = inner.calls();
+        assert_eq!(calls.len(), 1);
+        assert_matches!(&calls[0], MockDmlHandlerCall::Write { write_input, .. } => {
+            let mut tables = write_input.keys().map(String::as_str).collect::<Vec<_>>();
+            tables.sort_unstable();
+            assert_eq!(tables, ["cpu", "mem"]);
+            assert_eq!(write_input["cpu"].rows(), 2);
+
+            // Points without a timestamp are assigned the default time
+            let ts = write_input["mem"].timestamp_summary().unwrap();
+            assert_eq!(ts.stats.min, Some(DEFAULT_TIME));
+        });
+    }
+
+    #[tokio::test]
+    async fn test_write_malformed_line() {
+        let ns = "bananas".try_into().unwrap();
+        let inner = Arc::new(MockDmlHandler::default());
+        let handler = handler(Arc::clone(&inner));
+
+        let body = "cpu,host=a usage=1 1\ncpu,host=b usage=2 2\ncpu,host=c usage=\n";
+        let err = handler
+            .write(&ns, NamespaceId::new(1), Bytes::from(body), None)
+            .await
+            .expect_err("write should fail");
+        assert_matches!(err, DmlError::InvalidLineProtocol { line: 3, .. });
+
+        // A line conflicting with the type of an earlier one is also reported
+        let body = "cpu usage=1 1\ncpu usage=\"bananas\" 2\n";
+        let err = handler
+            .write(&ns, NamespaceId::new(1), Bytes::from(body), None)
+            .await
+            .expect_err("write should fail");
+        assert_matches!(err, DmlError::InvalidLineProtocol { line: 2, .. });
+
+        // The inner handler is not called
+        assert!(inner.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_write_timestamp_overflow() {
+        let ns = "bananas".try_into().unwrap();
+        let inner = Arc::new(MockDmlHandler::default());
+        let handler = handler(Arc::clone(&inner)).with_timestamp_base(1_000_000_000);
+
+        // The timestamp in seconds overflows i64 nanoseconds
+        let body = "cpu usage=1 1\ncpu usage=2 9223372036854775\n";
+        let err = handler
+            .write(&ns, NamespaceId::new(1), Bytes::from(body), None)
+            .await
+            .expect_err("write should fail");
+        assert_matches!(err, DmlError::InvalidLineProtocol { line: 2, .. });
+        assert_eq!(StatusCode::from(&err), StatusCode::BAD_REQUEST);
+
+        assert!(inner.calls().is_empty());
+    }
+}
@@ -51,6 +51,10 @@
 //! to the catalog and populates the [`NamespaceCache`], converging it to match
 //! the set of [`NamespaceSchema`] in the global catalog.
 //!
+//! The [`SchemaLimitDmlHandler`] decorating the [`SchemaValidator`] first
+//! rejects writes that would push a table over the configured per-table column
+//! or tag limits.
+//!
 //! The [`ShardedWriteBuffer`] uses a sharder implementation to direct the DML
 //! operations into a fixed set of shards.
 //!
@@ -63,6 +67,9 @@ pub use r#trait::*;
 mod schema_validation;
 pub use schema_validation::*;
 
+mod schema_limit;
+pub use schema_limit::*;
+
 pub mod nop;
 
 mod sharded_write_buffer;
@@ -71,6 +78,12 @@ pub use sharded_write_buffer::*;
 mod partitioner;
 pub use partitioner::*;
 
+mod line_protocol;
+pub use line_protocol::*;
+
+mod concurrency_limit;
+pub use concurrency_limit::*;
+
 mod instrumentation;
 pub use instrumentation::*;
 
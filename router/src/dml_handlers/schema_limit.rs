@@ -0,0 +1,493 @@
+use std::{
+    collections::BTreeSet,
+    fmt::Display,
+    ops::DerefMut,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use async_trait::async_trait;
+use data_types::{DatabaseName, DeletePredicate, NamespaceId, NamespaceSchema};
+use hashbrown::HashMap;
+use iox_catalog::interface::{get_schema_by_name, Catalog};
+use metric::U64Counter;
+use mutable_batch::MutableBatch;
+use observability_deps::tracing::*;
+use schema::InfluxColumnType;
+use trace::ctx::SpanContext;
+
+use super::{DmlError, DmlHandler, SchemaError};
+use crate::namespace_cache::NamespaceCache;
+
+/// The configured limits on the schema of each table, enforced by a
+/// [`SchemaLimitDmlHandler`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SchemaLimits {
+    /// The maximum number of columns, including tags and the timestamp, in
+    /// each table.
+    pub max_columns_per_table: Option<usize>,
+
+    /// The maximum number of tag columns in each table.
+    pub max_tags_per_table: Option<usize>,
+}
+
+/// A limit of a [`SchemaLimits`] that a write would exceed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaLimit {
+    /// The maximum number of columns in a table.
+    Columns(usize),
+
+    /// The maximum number of tag columns in a table.
+    Tags(usize),
+}
+
+impl Display for SchemaLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Columns(n) => write!(f, "{} columns", n),
+            Self::Tags(n) => write!(f, "{} tags", n),
+        }
+    }
+}
+
+/// A [`DmlHandler`] decorator that rejects writes that would push a table over
+/// the configured [`SchemaLimits`] with [`DmlError::SchemaLimitExceeded`],
+/// before passing them to the inner handler `T`. Deletes pass through
+/// unmodified.
+///
+/// The limits are evaluated against the schema snapshot in the namespace cache
+/// `C`, which is expected to be shared with the [`SchemaValidator`] that adds
+/// the new columns of a write to the catalog, and so must be placed before it.
+/// As for the [`SchemaValidator`], the schema of a namespace absent from the
+/// cache is loaded from the catalog, and cached. A table absent from the
+/// schema is treated as having no columns.
+///
+/// As with the namespace column limit enforced by the [`SchemaValidator`], a
+/// table already over a limit continues to accept writes that add no columns,
+/// and concurrent writes adding disjoint columns may together exceed it.
+///
+/// [`SchemaValidator`]: super::SchemaValidator
+#[derive(Debug)]
+pub struct SchemaLimitDmlHandler<T, C> {
+    inner: T,
+    catalog: Arc<dyn Catalog>,
+    cache: C,
+    limits: SchemaLimits,
+
+    service_limit_hit: U64Counter,
+}
+
+impl<T, C> SchemaLimitDmlHandler<T, C> {
+    /// Initialise a new [`SchemaLimitDmlHandler`] decorating `inner`, enforcing
+    /// `limits` against the schemas in `cache`, loading those it does not
+    /// contain from `catalog`.
+    pub fn new(
+        inner: T,
+        catalog: Arc<dyn Catalog>,
+        cache: C,
+        limits: SchemaLimits,
+        metrics: &metric::Registry,
+    ) -> Self {
+        let service_limit_hit = metrics
+            .register_metric::<U64Counter>(
+                "schema_limit_exceeded",
+                "number of writes rejected for exceeding a configured table schema limit",
+            )
+            .recorder(&[]);
+
+        Self {
+            inner,
+            catalog,
+            cache,
+            limits,
+            service_limit_hit,
+        }
+    }
+}
+
+#[async_trait]
+impl<T, C> DmlHandler for SchemaLimitDmlHandler<T, C>
+where
+    T: DmlHandler<WriteInput = HashMap<String, MutableBatch>>,
+    C: NamespaceCache,
+{
+    type WriteError = DmlError;
+    type DeleteError = T::DeleteError;
+
+    type WriteInput = T::WriteInput;
+    type WriteOutput = T::WriteOutput;
+
+    /// Propagate the readiness of the inner handler.
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), DmlError>> {
+        self.inner.poll_ready(cx)
+    }
+
+    /// Propagate the skipped request to the inner handler.
+    fn record_skipped(&self, span_ctx: Option<&SpanContext>) {
+        self.inner.record_skipped(span_ctx)
+    }
+
+    /// Validate `batches` against the configured limits, passing them to the
+    /// inner handler if no table would exceed them.
+    async fn write(
+        &self,
+        namespace: &DatabaseName<'static>,
+        namespace_id: NamespaceId,
+        batches: Self::WriteInput,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<Self::WriteOutput, Self::WriteError> {
+        // Load the namespace schema from the cache, falling back to pulling it
+        // from the global catalog (if it exists).
+        let schema = match self.cache.get_schema(namespace) {
+            Some(v) => v,
+            None => {
+                let mut repos = self.catalog.repositories().await;
+                let schema = get_schema_by_name(namespace, repos.deref_mut())
+                    .await
+                    .map_err(|e| {
+                        warn!(
+                            error=%e,
+                            %namespace,
+                            %namespace_id,
+                            "failed to retrieve namespace schema"
+                        );
+                        DmlError::Schema(SchemaError::NamespaceLookup(e))
+                    })
+                    .map(Arc::new);
+                let schema = match schema {
+                    Ok(v) => v,
+                    Err(e) => {
+                        self.inner.record_skipped(span_ctx.as_ref());
+                        return Err(e);
+                    }
+                };
+
+                self.cache
+                    .put_schema(namespace.clone(), Arc::clone(&schema));
+
+                trace!(%namespace, "schema cache populated");
+                schema
+            }
+        };
+
+        if let Err(e) = validate_schema_limits(&batches, &schema, self.limits) {
+            warn!(
+                %namespace,
+                %namespace_id,
+                error=%e,
+                "schema limit exceeded"
+            );
+            self.service_limit_hit.inc(1);
+            self.inner.record_skipped(span_ctx.as_ref());
+            return Err(e);
+        }
+
+        self.inner
+            .write(namespace, namespace_id, batches, span_ctx)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Pass the delete request through unmodified to the inner handler.
+    async fn delete(
+        &self,
+        namespace: &DatabaseName<'static>,
+        table_name: &str,
+        predicate: &DeletePredicate,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<(), Self::DeleteError> {
+        self.inner
+            .delete(namespace, table_name, predicate, span_ctx)
+            .await
+    }
+}
+
+fn validate_schema_limits(
+    batches: &HashMap<String, MutableBatch>,
+    schema: &NamespaceSchema,
+    limits: SchemaLimits,
+) -> Result<(), DmlError> {
+    for (table_name, batch) in batches {
+        let table = schema.tables.get(table_name);
+
+        if let Some(max_columns_per_table) = limits.max_columns_per_table {
+            let existing: BTreeSet<&str> = table
+                .map(|t| t.columns.keys().map(String::as_str).collect())
+                .unwrap_or_default();
+            let new = batch.columns().map(|(name, _)| name.as_str());
+            if exceeds_limit(existing, new, max_columns_per_table) {
+                return Err(DmlError::SchemaLimitExceeded {
+                    table: table_name.clone(),
+                    limit: SchemaLimit::Columns(max_columns_per_table),
+                });
+            }
+        }
+
+        if let Some(max_tags_per_table) = limits.max_tags_per_table {
+            let existing: BTreeSet<&str> = table
+                .map(|t| {
+                    t.columns
+                        .iter()
+                        .filter(|(_, c)| c.is_tag())
+                        .map(|(name, _)| name.as_str())
+                        .collect()
+                })
+                .unwrap_or_default();
+            let new = batch
+                .columns()
+                .filter(|(_, c)| c.influx_type() == InfluxColumnType::Tag)
+                .map(|(name, _)| name.as_str());
+            if exceeds_limit(existing, new, max_tags_per_table) {
+                return Err(DmlError::SchemaLimitExceeded {
+                    table: table_name.clone(),
+                    limit: SchemaLimit::Tags(max_tags_per_table),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns true if adding the `new` columns to the `existing` columns adds at
+/// least one, and results in more than `limit`.
+fn exceeds_limit<'a>(
+    mut existing: BTreeSet<&'a str>,
+    new: impl Iterator<Item = &'a str>,
+    limit: usize,
+) -> bool {
+    let existing_count = existing.len();
+    existing.extend(new);
+    existing.len() > existing_count && existing.len() > limit
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use data_types::ColumnType;
+    use iox_tests::util::TestCatalog;
+    use once_cell::sync::Lazy;
+    use write_summary::WriteSummary;
+
+    use super::*;
+    use crate::{
+        dml_handlers::mock::{MockDmlHandler, MockDmlHandlerCall},
+        namespace_cache::MemoryNamespaceCache,
+    };
+
+    static NAMESPACE: Lazy<DatabaseName<'static>> = Lazy::new(|| "bananas".try_into().unwrap());
+
+    type TestHandler = SchemaLimitDmlHandler<
+        Arc<MockDmlHandler<HashMap<String, MutableBatch>>>,
+        Arc<MemoryNamespaceCache>,
+    >;
+
+    fn lp_to_writes(lp: &str) -> HashMap<String, MutableBatch> {
+        let (writes, _) = mutable_batch_lp::lines_to_batches_stats(lp, 42)
+            .expect("failed to build test writes from LP");
+        writes
+    }
+
+    /// Returns a catalog containing the table `bananas` with the tags `tag1`
+    /// and `tag2`, the field `val` and the timestamp, and a handler enforcing
+    /// `limits` with an empty cache.
+    async fn handler(
+        limits: SchemaLimits,
+    ) -> (
+        Arc<MockDmlHandler<HashMap<String, MutableBatch>>>,
+        Arc<MemoryNamespaceCache>,
+        TestHandler,
+    ) {
+        let catalog = TestCatalog::new();
+        let namespace = catalog.create_namespace(&NAMESPACE).await;
+        let table = namespace.create_table("bananas").await;
+        table.create_column("tag1", ColumnType::Tag).await;
+        table.create_column("tag2", ColumnType::Tag).await;
+        table.create_column("val", ColumnType::I64).await;
+        table.create_column("time", ColumnType::Time).await;
+
+        let inner = Arc::new(
+            MockDmlHandler::default()
+                .with_write_return([Ok(WriteSummary::default()), Ok(WriteSummary::default())]),
+        );
+        let cache = Arc::new(MemoryNamespaceCache::default());
+        let metrics = metric::Registry::default();
+        let handler = SchemaLimitDmlHandler::new(
+            Arc::clone(&inner),
+            catalog.catalog(),
+            Arc::clone(&cache),
+            limits,
+            &metrics,
+        );
+        (inner, cache, handler)
+    }
+
+    #[tokio::test]
+    async fn test_write_within_limits() {
+        let (inner, _cache, handler) = handler(SchemaLimits {
+            max_columns_per_table: Some(5),
+            max_tags_per_table: Some(2),
+        })
+        .await;
+
+        // Existing columns, and one new field, are within the limits
+        handler
+            .write(
+                &NAMESPACE,
+                NamespaceId::new(42),
+                lp_to_writes("bananas,tag1=A,tag2=B val=42i,val2=42i 123456"),
+                None,
+            )
+            .await
+            .expect("write should succeed");
+
+        // as is a new table
+        handler
+            .write(
+                &NAMESPACE,
+                NamespaceId::new(42),
+                lp_to_writes("platanos,tag1=A,tag2=B val=42i 123456"),
+                None,
+            )
+            .await
+            .expect("write should succeed");
+
+        assert_eq!(inner.calls().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_write_over_tag_limit() {
+        let (inner, _cache, handler) = handler(SchemaLimits {
+            max_columns_per_table: None,
+            max_tags_per_table: Some(2),
+        })
+        .await;
+
+        let err = handler
+            .write(
+                &NAMESPACE,
+                NamespaceId::new(42),
+                lp_to_writes("bananas,tag1=A,tag2=B,tag3=C val=42i 123456"),
+                None,
+            )
+            .await
+            .expect_err("write should fail");
+        assert_matches!(err, DmlError::SchemaLimitExceeded { table, limit } => {
+            assert_eq!(table, "bananas");
+            assert_eq!(limit, SchemaLimit::Tags(2));
+        });
+        assert_eq!(
+            handler.service_limit_hit.fetch(),
+            1,
+            "limit metric not incremented"
+        );
+
+        // A new table with too many tags is also rejected
+        let err = handler
+            .write(
+                &NAMESPACE,
+                NamespaceId::new(42),
+                lp_to_writes("platanos,tag1=A,tag2=B,tag3=C val=42i 123456"),
+                None,
+            )
+            .await
+            .expect_err("write should fail");
+        assert_matches!(err, DmlError::SchemaLimitExceeded { table, .. } if table == "platanos");
+
+        // The inner handler is not called
+        assert!(inner.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_write_over_column_limit() {
+        let (inner, _cache, handler) = handler(SchemaLimits {
+            max_columns_per_table: Some(4),
+            max_tags_per_table: None,
+        })
+        .await;
+
+        let err = handler
+            .write(
+                &NAMESPACE,
+                NamespaceId::new(42),
+                lp_to_writes("bananas,tag1=A val=42i,val2=42i 123456"),
+                None,
+            )
+            .await
+            .expect_err("write should fail");
+        assert_matches!(err, DmlError::SchemaLimitExceeded { table, limit } => {
+            assert_eq!(table, "bananas");
+            assert_eq!(limit, SchemaLimit::Columns(4));
+        });
+        assert!(inner.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_write_over_limit_existing_columns() {
+        // A table already over the limits accepts writes adding no columns
+        let (inner, _cache, handler) = handler(SchemaLimits {
+            max_columns_per_table: Some(1),
+            max_tags_per_table: Some(1),
+        })
+        .await;
+
+        handler
+            .write(
+                &NAMESPACE,
+                NamespaceId::new(42),
+                lp_to_writes("bananas,tag1=A,tag2=B val=42i 123456"),
+                None,
+            )
+            .await
+            .expect("write should succeed");
+        assert_matches!(inner.calls().as_slice(), [MockDmlHandlerCall::Write { .. }]);
+    }
+
+    #[tokio::test]
+    async fn test_write_cold_cache() {
+        // The table in the catalog is already at both limits
+        let (inner, cache, handler) = handler(SchemaLimits {
+            max_columns_per_table: Some(4),
+            max_tags_per_table: Some(2),
+        })
+        .await;
+        assert!(cache.get_schema(&NAMESPACE).is_none());
+
+        // The first write to the namespace is checked against the catalog
+        // schema, rather than that of an empty namespace
+        let err = handler
+            .write(
+                &NAMESPACE,
+                NamespaceId::new(42),
+                lp_to_writes("bananas,tag3=C val=42i 123456"),
+                None,
+            )
+            .await
+            .expect_err("write should fail");
+        assert_matches!(err, DmlError::SchemaLimitExceeded { table, .. } if table == "bananas");
+        assert!(inner.calls().is_empty());
+
+        // and the schema is cached
+        let schema = cache
+            .get_schema(&NAMESPACE)
+            .expect("cache should be populated");
+        assert_eq!(schema.tables["bananas"].columns.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_write_namespace_not_found() {
+        let (inner, _cache, handler) = handler(SchemaLimits::default()).await;
+
+        let err = handler
+            .write(
+                &"platanos".try_into().unwrap(),
+                NamespaceId::new(42),
+                lp_to_writes("bananas val=42i 123456"),
+                None,
+            )
+            .await
+            .expect_err("write should fail");
+        assert_matches!(err, DmlError::Schema(SchemaError::NamespaceLookup(_)));
+        assert!(inner.calls().is_empty());
+    }
+}
@@ -1,11 +1,16 @@
-use std::{error::Error, fmt::Debug, sync::Arc};
+use std::{
+    error::Error,
+    fmt::Debug,
+    sync::Arc,
+    task::{Context, Poll},
+};
 
 use async_trait::async_trait;
 use data_types::{DatabaseName, DeletePredicate, NamespaceId};
 use thiserror::Error;
 use trace::ctx::SpanContext;
 
-use super::{partitioner::PartitionError, SchemaError, ShardError};
+use super::{partitioner::PartitionError, SchemaError, SchemaLimit, ShardError};
 
 /// Errors emitted by a [`DmlHandler`] implementation during DML request
 /// processing.
@@ -27,11 +32,71 @@ pub enum DmlError {
     #[error(transparent)]
     Partition(#[from] PartitionError),
 
+    /// A write would push a table over a configured schema limit.
+    #[error("schema limit exceeded: table {table} would have more than {limit}")]
+    SchemaLimitExceeded {
+        /// The name of the table.
+        table: String,
+        /// The limit that would be exceeded.
+        limit: SchemaLimit,
+    },
+
+    /// The line protocol of a write could not be parsed.
+    #[error("invalid line protocol at line {line}: {reason}")]
+    InvalidLineProtocol {
+        /// The 1-based number of the first invalid line.
+        line: usize,
+        /// A description of why the line is invalid.
+        reason: String,
+    },
+
     /// An unknown error occured while processing the DML request.
     #[error("internal dml handler error: {0}")]
     Internal(Box<dyn Error + Send + Sync>),
 }
 
+/// Classifies the errors of a [`DmlHandler`], so that they may be recorded
+/// without first being converted into a [`DmlError`].
+pub trait DmlErrorKind {
+    /// Returns a short, static description of the kind of error, suitable for
+    /// use as a metric label or span event.
+    ///
+    /// The kind is the same as that of the [`DmlError`] the error converts into.
+    fn kind(&self) -> &'static str;
+}
+
+impl DmlErrorKind for DmlError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::DatabaseNotFound(_) => "database_not_found",
+            Self::WriteBuffer(e) => e.kind(),
+            Self::Schema(e) => e.kind(),
+            Self::Partition(e) => e.kind(),
+            Self::SchemaLimitExceeded { .. } => "schema_limit_exceeded",
+            Self::InvalidLineProtocol { .. } => "invalid_line_protocol",
+            Self::Internal(_) => "internal",
+        }
+    }
+}
+
+impl DmlErrorKind for ShardError {
+    fn kind(&self) -> &'static str {
+        "write_buffer"
+    }
+}
+
+impl DmlErrorKind for SchemaError {
+    fn kind(&self) -> &'static str {
+        "schema"
+    }
+}
+
+impl DmlErrorKind for PartitionError {
+    fn kind(&self) -> &'static str {
+        "partition"
+    }
+}
+
 /// A composable, abstract handler of DML requests.
 #[async_trait]
 pub trait DmlHandler: Debug + Send + Sync {
@@ -55,6 +120,28 @@ pub trait DmlHandler: Debug + Send + Sync {
     /// The error type of the delete handler.
     type DeleteError: Error + Into<DmlError> + Send;
 
+    /// Returns [`Poll::Ready`] when this handler is able to accept another
+    /// DML request, or [`Poll::Pending`] if it is saturated, in which case
+    /// the task in `cx` is woken once capacity becomes available.
+    ///
+    /// Like [`tower::Service::poll_ready()`], this allows callers to apply
+    /// backpressure (or shed load) before queues grow unbounded. Handlers
+    /// wrapping an inner [`DmlHandler`] must propagate its readiness. The
+    /// default implementation is always ready.
+    ///
+    /// [`tower::Service::poll_ready()`]: https://docs.rs/tower/latest/tower/trait.Service.html#tymethod.poll_ready
+    fn poll_ready(&self, _cx: &mut Context<'_>) -> Poll<Result<(), DmlError>> {
+        Poll::Ready(Ok(()))
+    }
+
+    /// Record that a request was not passed to this handler because an
+    /// earlier stage of the handler pipeline failed, so that the skipped
+    /// stages appear in the trace of the request.
+    ///
+    /// Handlers wrapping an inner [`DmlHandler`] must propagate it. The default
+    /// implementation records nothing.
+    fn record_skipped(&self, _span_ctx: Option<&SpanContext>) {}
+
     /// Write `batches` to `namespace`.
     async fn write(
         &self,
@@ -84,6 +171,14 @@ where
     type WriteError = T::WriteError;
     type DeleteError = T::DeleteError;
 
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), DmlError>> {
+        (**self).poll_ready(cx)
+    }
+
+    fn record_skipped(&self, span_ctx: Option<&SpanContext>) {
+        (**self).record_skipped(span_ctx)
+    }
+
     async fn write(
         &self,
         namespace: &DatabaseName<'static>,
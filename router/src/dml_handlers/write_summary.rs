@@ -1,4 +1,7 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    task::{Context, Poll},
+};
 
 use async_trait::async_trait;
 use data_types::{DatabaseName, DeletePredicate, NamespaceId};
@@ -6,7 +9,7 @@ use dml::DmlMeta;
 use trace::ctx::SpanContext;
 use write_summary::WriteSummary;
 
-use super::DmlHandler;
+use super::{DmlError, DmlHandler};
 
 /// A [`WriteSummaryAdapter`] wraps DML Handler that produces
 ///  `Vec<Vec<DmlMeta>>` for each write, and produces a WriteSummary,
@@ -35,6 +38,16 @@ where
     type WriteError = T::WriteError;
     type DeleteError = T::DeleteError;
 
+    /// Propagate the readiness of the inner handler.
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), DmlError>> {
+        self.inner.poll_ready(cx)
+    }
+
+    /// Propagate the skipped request to the inner handler.
+    fn record_skipped(&self, span_ctx: Option<&SpanContext>) {
+        self.inner.record_skipped(span_ctx)
+    }
+
     /// Sends `input` to the inner handler, which returns a
     /// `Vec<Vec<DmlMeta>>`, creating a `WriteSummary`
     async fn write(
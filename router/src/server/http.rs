@@ -6,9 +6,12 @@ use std::{str::Utf8Error, sync::Arc, time::Instant};
 
 use bytes::{Bytes, BytesMut};
 use data_types::{org_and_bucket_to_database, OrgBucketMappingError};
-use futures::StreamExt;
+use futures::{future::poll_fn, FutureExt, StreamExt};
 use hashbrown::HashMap;
-use hyper::{header::CONTENT_ENCODING, Body, Method, Request, Response, StatusCode};
+use hyper::{
+    header::{CONTENT_ENCODING, CONTENT_LENGTH},
+    Body, Method, Request, Response, StatusCode,
+};
 use iox_time::{SystemProvider, TimeProvider};
 use metric::{DurationHistogram, U64Counter};
 use mutable_batch::MutableBatch;
@@ -60,6 +63,16 @@ pub enum Error {
     #[error("max request size ({0} bytes) exceeded")]
     RequestSizeExceeded(usize),
 
+    /// The client sent a request body that differs in size from its declared
+    /// `Content-Length`.
+    #[error("declared content-length of {declared} bytes does not match body of {actual} bytes")]
+    ContentLengthMismatch {
+        /// The `Content-Length` of the request.
+        declared: u64,
+        /// The number of body bytes read before the mismatch was detected.
+        actual: u64,
+    },
+
     /// Decoding a gzip-compressed stream of data failed.
     #[error("error decoding gzip stream: {0}")]
     InvalidGzip(std::io::Error),
@@ -91,6 +104,10 @@ pub enum Error {
     /// simultaneous requests.
     #[error("this service is overloaded, please try again later")]
     RequestLimit,
+
+    /// The [`DmlHandler`] stack is not ready to accept more requests.
+    #[error("the write pipeline is saturated, please try again later")]
+    DmlHandlerSaturated,
 }
 
 impl Error {
@@ -108,6 +125,7 @@ impl Error {
             Error::ParseDelete(_) => StatusCode::BAD_REQUEST,
             Error::ParseHttpDelete(_) => StatusCode::BAD_REQUEST,
             Error::RequestSizeExceeded(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Error::ContentLengthMismatch { .. } => StatusCode::BAD_REQUEST,
             Error::InvalidContentEncoding(_) => {
                 // https://www.rfc-editor.org/rfc/rfc7231#section-6.5.13
                 StatusCode::UNSUPPORTED_MEDIA_TYPE
@@ -115,6 +133,7 @@ impl Error {
             Error::DmlHandler(err) => StatusCode::from(err),
             Error::NamespaceResolver(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Error::RequestLimit => StatusCode::SERVICE_UNAVAILABLE,
+            Error::DmlHandlerSaturated => StatusCode::SERVICE_UNAVAILABLE,
         }
     }
 }
@@ -141,6 +160,11 @@ impl From<&DmlError> for StatusCode {
 
             DmlError::Internal(_) | DmlError::WriteBuffer(_) => StatusCode::INTERNAL_SERVER_ERROR,
             DmlError::Partition(PartitionError::BatchWrite(_)) => StatusCode::INTERNAL_SERVER_ERROR,
+            DmlError::SchemaLimitExceeded { .. } => {
+                // https://docs.influxdata.com/influxdb/cloud/account-management/limits/#api-error-responses
+                StatusCode::BAD_REQUEST
+            }
+            DmlError::InvalidLineProtocol { .. } => StatusCode::BAD_REQUEST,
         }
     }
 }
@@ -247,6 +271,7 @@ pub struct HttpDelegate<D, N, T = SystemProvider> {
     write_metric_body_size: U64Counter,
     delete_metric_body_size: U64Counter,
     request_limit_rejected: U64Counter,
+    dml_handler_saturated_rejected: U64Counter,
 }
 
 impl<D, N> HttpDelegate<D, N, SystemProvider> {
@@ -298,6 +323,12 @@ impl<D, N> HttpDelegate<D, N, SystemProvider> {
                 "number of HTTP requests rejected due to exceeding parallel request limit",
             )
             .recorder(&[]);
+        let dml_handler_saturated_rejected = metrics
+            .register_metric::<U64Counter>(
+                "http_dml_handler_saturated_rejected",
+                "number of HTTP requests rejected due to the DML handlers not being ready",
+            )
+            .recorder(&[]);
         let http_line_protocol_parse_duration = metrics
             .register_metric::<DurationHistogram>(
                 "http_line_protocol_parse_duration",
@@ -318,6 +349,7 @@ impl<D, N> HttpDelegate<D, N, SystemProvider> {
             write_metric_body_size,
             delete_metric_body_size,
             request_limit_rejected,
+            dml_handler_saturated_rejected,
         }
     }
 }
@@ -348,6 +380,18 @@ where
             Err(e) => panic!("request limiter error: {}", e),
         };
 
+        // Shed the request if the DML handler stack is saturated, rather than
+        // queuing it behind the requests it is already servicing.
+        match poll_fn(|cx| self.dml_handler.poll_ready(cx)).now_or_never() {
+            Some(Ok(())) => {}
+            Some(Err(e)) => return Err(Error::DmlHandler(e)),
+            None => {
+                error!("dml handler saturated - dropping request");
+                self.dml_handler_saturated_rejected.inc(1);
+                return Err(Error::DmlHandlerSaturated);
+            }
+        }
+
         // Route the request to a handler.
         match (req.method(), req.uri().path()) {
             (&Method::POST, "/api/v2/write") => self.write_handler(req).await,
@@ -477,6 +521,9 @@ where
 
     /// Parse the request's body into raw bytes, applying the configured size
     /// limits and decoding any content encoding.
+    ///
+    /// If the request declares a `Content-Length`, a body of any other length
+    /// is rejected.
     async fn read_body(&self, req: hyper::Request<Body>) -> Result<Bytes, Error> {
         let encoding = req
             .headers()
@@ -489,6 +536,13 @@ where
             Some(v) => return Err(Error::InvalidContentEncoding(v.to_string())),
         };
 
+        // A malformed length is rejected by the HTTP server before routing.
+        let declared = req
+            .headers()
+            .get(&CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok());
+
         let mut payload = req.into_body();
 
         let mut body = BytesMut::new();
@@ -499,6 +553,26 @@ where
                 return Err(Error::RequestSizeExceeded(self.max_request_bytes));
             }
             body.extend_from_slice(&chunk);
+
+            // Stop reading a body longer than declared.
+            match declared {
+                Some(declared) if body.len() as u64 > declared => {
+                    return Err(Error::ContentLengthMismatch {
+                        declared,
+                        actual: body.len() as u64,
+                    })
+                }
+                _ => {}
+            }
+        }
+        match declared {
+            Some(declared) if body.len() as u64 != declared => {
+                return Err(Error::ContentLengthMismatch {
+                    declared,
+                    actual: body.len() as u64,
+                })
+            }
+            _ => {}
         }
         let body = body.freeze();
 
@@ -549,7 +623,10 @@ mod tests {
 
     use super::*;
     use crate::{
-        dml_handlers::mock::{MockDmlHandler, MockDmlHandlerCall},
+        dml_handlers::{
+            mock::{MockDmlHandler, MockDmlHandlerCall},
+            ConcurrencyLimit,
+        },
         namespace_resolver::mock::MockNamespaceResolver,
     };
 
@@ -1178,6 +1255,104 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_content_length_mismatch() {
+        let route = |content_length: &'static str, body: &'static str| async move {
+            let dml_handler =
+                Arc::new(MockDmlHandler::default().with_write_return([Ok(summary())]));
+            let delegate = HttpDelegate::new(
+                MAX_BYTES,
+                100,
+                MockNamespaceResolver::default().with_mapping("bananas_test", NAMESPACE_ID),
+                Arc::clone(&dml_handler),
+                &metric::Registry::default(),
+            );
+            let request = Request::builder()
+                .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+                .method("POST")
+                .header(CONTENT_LENGTH, content_length)
+                .body(Body::from(body))
+                .unwrap();
+
+            (delegate.route(request).await, dml_handler.calls())
+        };
+
+        let body = "platanos,tag1=A val=42i 123456";
+        let (got, calls) = route("30", body).await;
+        assert_matches!(got, Ok(_));
+        assert_matches!(calls.as_slice(), [MockDmlHandlerCall::Write { .. }]);
+
+        // A body shorter than declared
+        let (got, calls) = route("31", body).await;
+        let err = got.expect_err("short body should be rejected");
+        assert_matches!(
+            err,
+            Error::ContentLengthMismatch {
+                declared: 31,
+                actual: 30
+            }
+        );
+        assert_eq!(err.as_status_code(), StatusCode::BAD_REQUEST);
+        assert_matches!(calls.as_slice(), []);
+
+        // A body longer than declared
+        let (got, calls) = route("29", body).await;
+        let err = got.expect_err("long body should be rejected");
+        assert_matches!(
+            err,
+            Error::ContentLengthMismatch {
+                declared: 29,
+                actual: 30
+            }
+        );
+        assert_eq!(err.as_status_code(), StatusCode::BAD_REQUEST);
+        assert_matches!(calls.as_slice(), []);
+    }
+
+    // This test ensures requests are shed, before their body is read, while
+    // the DML handler stack is not ready to accept more requests.
+    #[tokio::test]
+    async fn test_dml_handler_saturated() {
+        let mock_namespace_resolver =
+            MockNamespaceResolver::default().with_mapping("bananas_test", NAMESPACE_ID);
+
+        // A limit of zero concurrent requests is always saturated
+        let dml_handler = Arc::new(ConcurrencyLimit::new(MockDmlHandler::default(), 0));
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            100,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+        );
+
+        let (body_tx, rx) = tokio::sync::mpsc::channel::<Result<&'static str, MockError>>(1);
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+            .method("POST")
+            .body(Body::wrap_stream(ReceiverStream::new(rx)))
+            .unwrap();
+
+        let err = delegate
+            .route(request)
+            .with_timeout_panic(Duration::from_secs(1))
+            .await
+            .expect_err("request should be rejected");
+        assert_matches!(err, Error::DmlHandlerSaturated);
+        assert_eq!(err.as_status_code(), StatusCode::SERVICE_UNAVAILABLE);
+
+        // The rejection is recorded distinctly from the request limit
+        assert_metric_hit(&*metrics, "http_dml_handler_saturated_rejected", Some(1));
+        assert_metric_hit(&*metrics, "http_request_limit_rejected", Some(0));
+
+        // The body was not read
+        body_tx
+            .send(Ok("wat"))
+            .await
+            .expect_err("channel should be closed");
+    }
+
     #[derive(Debug, Error)]
     enum MockError {
         #[error("bad stuff")]
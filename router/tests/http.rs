@@ -11,7 +11,8 @@ use mutable_batch::MutableBatch;
 use router::{
     dml_handlers::{
         Chain, DmlError, DmlHandlerChainExt, FanOutAdaptor, InstrumentationDecorator, Partitioned,
-        Partitioner, SchemaError, SchemaValidator, ShardedWriteBuffer, WriteSummaryAdapter,
+        Partitioner, SchemaError, SchemaLimit, SchemaLimitDmlHandler, SchemaLimits,
+        SchemaValidator, ShardedWriteBuffer, WriteSummaryAdapter,
     },
     namespace_cache::{MemoryNamespaceCache, ShardedCache},
     namespace_resolver::{NamespaceAutocreation, NamespaceSchemaResolver},
@@ -46,7 +47,13 @@ pub struct TestContext {
 type HttpDelegateStack = HttpDelegate<
     InstrumentationDecorator<
         Chain<
-            Chain<SchemaValidator<Arc<ShardedCache<Arc<MemoryNamespaceCache>>>>, Partitioner>,
+            Chain<
+                SchemaLimitDmlHandler<
+                    SchemaValidator<Arc<ShardedCache<Arc<MemoryNamespaceCache>>>>,
+                    Arc<ShardedCache<Arc<MemoryNamespaceCache>>>,
+                >,
+                Partitioner,
+            >,
             WriteSummaryAdapter<
                 FanOutAdaptor<
                     ShardedWriteBuffer<JumpHash<Arc<Shard>>>,
@@ -65,6 +72,11 @@ type HttpDelegateStack = HttpDelegate<
 /// catalog / write buffer backends.
 impl TestContext {
     pub fn new() -> Self {
+        Self::new_with_schema_limits(SchemaLimits::default())
+    }
+
+    /// Initialise a [`TestContext`] enforcing the given table schema limits.
+    pub fn new_with_schema_limits(schema_limits: SchemaLimits) -> Self {
         let metrics = Arc::new(metric::Registry::default());
         let time = iox_time::MockProvider::new(iox_time::Time::from_timestamp_millis(668563200000));
 
@@ -92,6 +104,13 @@ impl TestContext {
 
         let schema_validator =
             SchemaValidator::new(Arc::clone(&catalog), Arc::clone(&ns_cache), &*metrics);
+        let schema_validator = SchemaLimitDmlHandler::new(
+            schema_validator,
+            Arc::clone(&catalog),
+            Arc::clone(&ns_cache),
+            schema_limits,
+            &*metrics,
+        );
         let partitioner = Partitioner::new(PartitionTemplate {
             parts: vec![TemplatePart::TimeFormat("%Y-%m-%d".to_owned())],
         });
@@ -347,3 +366,68 @@ async fn test_schema_limit() {
     );
     assert_eq!(err.as_status_code(), StatusCode::BAD_REQUEST);
 }
+
+#[tokio::test]
+async fn test_table_schema_limit() {
+    let ctx = TestContext::new_with_schema_limits(SchemaLimits {
+        max_columns_per_table: None,
+        max_tags_per_table: Some(2),
+    });
+
+    // A write within the tag limit succeeds
+    let request = Request::builder()
+        .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+        .method("POST")
+        .body(Body::from("platanos,tag1=A,tag2=B val=42i 123456"))
+        .expect("failed to construct HTTP request");
+    let response = ctx
+        .delegate()
+        .route(request)
+        .await
+        .expect("LP write request failed");
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    // Adding a third tag is rejected
+    let request = Request::builder()
+        .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+        .method("POST")
+        .body(Body::from("platanos,tag1=A,tag2=B,tag3=C val=42i 123457"))
+        .expect("failed to construct HTTP request");
+    let err = ctx
+        .delegate()
+        .route(request)
+        .await
+        .expect_err("LP write request should fail");
+
+    assert_matches!(
+        &err,
+        router::server::http::Error::DmlHandler(
+            DmlError::SchemaLimitExceeded { table, limit }
+        ) => {
+            assert_eq!(table, "platanos");
+            assert_eq!(*limit, SchemaLimit::Tags(2));
+        }
+    );
+    assert_eq!(err.as_status_code(), StatusCode::BAD_REQUEST);
+
+    // and the tag is not added to the catalog
+    let mut repos = ctx.catalog().repositories().await;
+    let ns = repos
+        .namespaces()
+        .get_by_name("bananas_test")
+        .await
+        .expect("query should succeed")
+        .expect("namespace not found");
+    let table = repos
+        .tables()
+        .get_by_namespace_and_name(ns.id, "platanos")
+        .await
+        .expect("query should succeed")
+        .expect("table not found");
+    let columns = repos
+        .columns()
+        .list_by_table_id(table.id)
+        .await
+        .expect("query should succeed");
+    assert!(columns.iter().all(|c| c.name != "tag3"));
+}
@@ -97,5 +97,24 @@ fn benchmark_sharder(
     });
 }
 
-criterion_group!(benches, sharder_benchmarks);
+fn jump_hash_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("jump_hash");
+
+    for num_buckets in [10, 100, 1_000, 10_000, 100_000] {
+        group.throughput(Throughput::Elements(num_buckets as u64));
+        group.bench_function(format!("new {} buckets", num_buckets), |b| {
+            b.iter(|| JumpHash::new((0..num_buckets).map(Arc::new)));
+        });
+
+        let hasher = JumpHash::new((0..num_buckets).map(Arc::new));
+        group.throughput(Throughput::Elements(1));
+        group.bench_function(format!("shard_for_query {} buckets", num_buckets), |b| {
+            b.iter(|| hasher.shard_for_query("table", "namespace"));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, sharder_benchmarks, jump_hash_benchmarks);
 criterion_main!(benches);
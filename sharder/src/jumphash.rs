@@ -182,7 +182,11 @@ mod tests {
     use super::*;
     use data_types::TimestampRange;
     use hashbrown::HashMap;
-    use std::iter;
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        cell::Cell,
+        iter,
+    };
 
     #[test]
     fn test_consistent_hashing() {
@@ -393,4 +397,41 @@ mod tests {
         let shards: iter::Empty<i32> = iter::empty();
         JumpHash::new(shards);
     }
+
+    /// Counts the heap allocations made by the current thread, so that tests
+    /// running concurrently do not interfere.
+    #[derive(Debug)]
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = Cell::new(0);
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.with(|n| n.set(n.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn test_lookup_does_not_allocate() {
+        let hasher = JumpHash::new((0..1_000).map(Arc::new));
+        let table = "bananas".to_string();
+        let namespace = "platanos".to_string();
+
+        let before = ALLOCATIONS.with(Cell::get);
+        for i in 0..1_000 {
+            hasher.hash(i);
+            hasher.shard_for_query(&table, &namespace);
+        }
+        assert_eq!(ALLOCATIONS.with(Cell::get), before);
+    }
 }
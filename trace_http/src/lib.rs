@@ -11,5 +11,5 @@
 
 mod classify;
 pub mod ctx;
-mod metrics;
+pub mod metrics;
 pub mod tower;
@@ -14,6 +14,9 @@ pub struct MetricsCollection {
     /// Metric registry for registering new metrics
     metric_registry: Arc<metric::Registry>,
 
+    /// Templates to normalize request paths to, or None to use the raw path
+    path_templates: Option<Arc<PathTemplates>>,
+
     /// Metrics keyed by request path or None for 404 responses
     metrics: Mutex<HashMap<Option<String>, Metrics>>,
 }
@@ -23,6 +26,18 @@ impl MetricsCollection {
         Self {
             is_grpc,
             metric_registry,
+            path_templates: None,
+            metrics: Default::default(),
+        }
+    }
+
+    /// Returns a new `MetricsCollection` that labels requests with the template of
+    /// `path_templates` that their path matches, rather than the raw path
+    pub fn with_path_templates(&self, path_templates: PathTemplates) -> Self {
+        Self {
+            is_grpc: self.is_grpc,
+            metric_registry: Arc::clone(&self.metric_registry),
+            path_templates: Some(Arc::new(path_templates)),
             metrics: Default::default(),
         }
     }
@@ -32,7 +47,10 @@ impl MetricsCollection {
         MetricsRecorder {
             metrics: Arc::clone(self),
             start_instant: Instant::now(),
-            path: Some(request.uri().path().to_string()),
+            path: Some(match &self.path_templates {
+                Some(templates) => templates.normalize(request.uri().path()).to_string(),
+                None => request.uri().path().to_string(),
+            }),
             classification: None,
         }
     }
@@ -55,6 +73,48 @@ impl MetricsCollection {
     }
 }
 
+/// A table of path templates, used to label requests with a bounded set of paths
+///
+/// A template is a path, in which a segment of the form `{name}` matches any
+/// single segment of a request path, such as `/api/v2/buckets/{id}`. Paths that
+/// match no template are labelled [`PathTemplates::OTHER`]
+#[derive(Debug, Clone, Default)]
+pub struct PathTemplates {
+    templates: Vec<String>,
+}
+
+impl PathTemplates {
+    /// The label of paths that match no template
+    pub const OTHER: &'static str = "other";
+
+    pub fn new(templates: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            templates: templates.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns the first template matched by `path`, or [`Self::OTHER`]
+    pub fn normalize(&self, path: &str) -> &str {
+        self.templates
+            .iter()
+            .find(|template| Self::matches(template, path))
+            .map_or(Self::OTHER, String::as_str)
+    }
+
+    fn matches(template: &str, path: &str) -> bool {
+        let mut template = template.split('/');
+        let mut path = path.split('/');
+        loop {
+            match (template.next(), path.next()) {
+                (None, None) => return true,
+                (Some(t), Some(p)) if t == p => {}
+                (Some(t), Some(p)) if t.starts_with('{') && t.ends_with('}') && !p.is_empty() => {}
+                _ => return false,
+            }
+        }
+    }
+}
+
 /// The request metrics for a specific set of attributes (e.g. path)
 #[derive(Debug)]
 struct Metrics {
@@ -142,3 +202,64 @@ impl Drop for MetricsRecorder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_templates() {
+        let templates = PathTemplates::new(["/api/v2/write", "/api/v2/buckets/{id}"]);
+
+        assert_eq!(templates.normalize("/api/v2/write"), "/api/v2/write");
+        assert_eq!(
+            templates.normalize("/api/v2/buckets/0123"),
+            "/api/v2/buckets/{id}"
+        );
+
+        assert_eq!(
+            templates.normalize("/api/v2/buckets/"),
+            PathTemplates::OTHER
+        );
+        assert_eq!(
+            templates.normalize("/api/v2/buckets/0123/labels"),
+            PathTemplates::OTHER
+        );
+        assert_eq!(templates.normalize("/api/v2/write/"), PathTemplates::OTHER);
+        assert_eq!(templates.normalize("/"), PathTemplates::OTHER);
+        assert_eq!(
+            PathTemplates::default().normalize("/api"),
+            PathTemplates::OTHER
+        );
+    }
+
+    #[test]
+    fn test_path_templates_metrics() {
+        let registry = Arc::new(metric::Registry::new());
+        let metrics = Arc::new(
+            MetricsCollection::new(Arc::clone(&registry), false)
+                .with_path_templates(PathTemplates::new(["/api/v2/write"])),
+        );
+
+        for uri in [
+            "http://localhost/api/v2/write?db=foo",
+            "http://localhost/api/v2/write?db=bar",
+            "http://localhost/api/v2/write/1234",
+        ] {
+            let request = http::Request::get(uri).body(()).unwrap();
+            metrics
+                .recorder(&request)
+                .set_classification(Classification::Ok);
+        }
+
+        let counter = |path: &'static str| {
+            registry
+                .get_instrument::<Metric<U64Counter>>("http_requests")
+                .unwrap()
+                .get_observer(&Attributes::from(&[("path", path), ("status", "ok")]))
+                .map_or(0, |observer| observer.fetch())
+        };
+        assert_eq!(counter("/api/v2/write"), 2);
+        assert_eq!(counter(PathTemplates::OTHER), 1);
+    }
+}
@@ -28,7 +28,7 @@ use trace::{span::SpanRecorder, TraceCollector};
 
 use crate::classify::{classify_headers, classify_response, Classification};
 use crate::ctx::{RequestLogContext, RequestLogContextExt, TraceHeaderParser};
-use crate::metrics::{MetricsCollection, MetricsRecorder};
+use crate::metrics::{MetricsCollection, MetricsRecorder, PathTemplates};
 
 /// `TraceLayer` implements `tower::Layer` and can be used to decorate a
 /// `tower::Service` to collect information about requests flowing through it
@@ -59,6 +59,15 @@ impl TraceLayer {
             collector,
         }
     }
+
+    /// Label the metrics of each request with the template of `path_templates`
+    /// that its path matches, rather than its raw path, bounding their cardinality
+    pub fn with_path_templates(self, path_templates: PathTemplates) -> Self {
+        Self {
+            metrics: Arc::new(self.metrics.with_path_templates(path_templates)),
+            ..self
+        }
+    }
 }
 
 impl<S> Layer<S> for TraceLayer {
@@ -17,6 +17,21 @@ pub enum Error {
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+/// The overall persistence state of a write across all the shards it
+/// was written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Durability {
+    /// The write has been persisted to parquet on every shard.
+    Durable,
+
+    /// The write has been persisted to parquet on some, but not all,
+    /// shards.
+    Partial,
+
+    /// The write has not been persisted on any shard.
+    Pending,
+}
+
 /// Contains information about a single write.
 ///
 /// A single write consisting of multiple lines of line protocol
@@ -125,6 +140,33 @@ impl WriteSummary {
 
         Ok(ShardWriteStatus::Durable)
     }
+
+    /// Given the write described by this summary and the progress of each
+    /// shard, returns the [`Durability`] of the write as a whole.
+    ///
+    /// Shards in this summary that have no entry in `progress` are treated
+    /// as not persisted. A summary of no shards describes a write with nothing
+    /// left to persist, so is [`Durability::Durable`].
+    pub fn durability(&self, progress: &BTreeMap<ShardIndex, ShardProgress>) -> Durability {
+        let empty = ShardProgress::new();
+        let persisted = self
+            .shards
+            .keys()
+            .filter(|shard_index| {
+                let shard_progress = progress.get(shard_index).unwrap_or(&empty);
+                matches!(
+                    self.write_status(**shard_index, shard_progress),
+                    Ok(ShardWriteStatus::Persisted)
+                )
+            })
+            .count();
+
+        match persisted {
+            n if n == self.shards.len() => Durability::Durable,
+            0 => Durability::Pending,
+            _ => Durability::Partial,
+        }
+    }
 }
 
 impl From<WriteSummary> for proto::WriteSummary {
@@ -421,6 +463,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn durability() {
+        let summary = test_summary();
+
+        // both shards persisted past the write
+        let progress = BTreeMap::from([
+            (
+                ShardIndex::new(1),
+                ShardProgress::new().with_persisted(SequenceNumber::new(3)),
+            ),
+            (
+                ShardIndex::new(2),
+                ShardProgress::new().with_persisted(SequenceNumber::new(1)),
+            ),
+        ]);
+        assert_eq!(summary.durability(&progress), Durability::Durable);
+
+        // shard 1 only persisted up to sequence number 2, shard 2 persisted
+        let progress = BTreeMap::from([
+            (
+                ShardIndex::new(1),
+                ShardProgress::new()
+                    .with_buffered(SequenceNumber::new(3))
+                    .with_persisted(SequenceNumber::new(2)),
+            ),
+            (
+                ShardIndex::new(2),
+                ShardProgress::new().with_persisted(SequenceNumber::new(1)),
+            ),
+        ]);
+        assert_eq!(summary.durability(&progress), Durability::Partial);
+
+        // shard 1 persisted, no information for shard 2
+        let progress = BTreeMap::from([(
+            ShardIndex::new(1),
+            ShardProgress::new().with_persisted(SequenceNumber::new(3)),
+        )]);
+        assert_eq!(summary.durability(&progress), Durability::Partial);
+
+        // both shards only buffered the write
+        let progress = BTreeMap::from([
+            (
+                ShardIndex::new(1),
+                ShardProgress::new().with_buffered(SequenceNumber::new(3)),
+            ),
+            (
+                ShardIndex::new(2),
+                ShardProgress::new().with_buffered(SequenceNumber::new(1)),
+            ),
+        ]);
+        assert_eq!(summary.durability(&progress), Durability::Pending);
+
+        // no progress information at all
+        assert_eq!(summary.durability(&BTreeMap::new()), Durability::Pending);
+
+        // a write to no shards has nothing left to persist
+        let summary = WriteSummary::default();
+        assert_eq!(summary.durability(&BTreeMap::new()), Durability::Durable);
+        assert_eq!(summary.durability(&progress), Durability::Durable);
+    }
+
     /// Return a write summary that describes a write with:
     /// shard 1 --> sequence 3
     /// shard 2 --> sequence 1